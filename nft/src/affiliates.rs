@@ -0,0 +1,88 @@
+use crate::*;
+
+//bounds of an affiliate code's length
+const MIN_CODE_LEN: usize = 3;
+const MAX_CODE_LEN: usize = 32;
+//the largest share of the owner's amount an affiliate can earn
+const MAX_AFFILIATE_BPS: u32 = 2_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct AffiliateInfo {
+    pub code: String,
+    pub account_id: AccountId,
+    pub mints_referred: u64,
+    pub total_earned: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Register the caller as the affiliate behind `code`. Codes are unique, lowercase
+    /// alphanumeric (plus `-` and `_`) and the attached deposit pays for their storage.
+    #[payable]
+    pub fn register_affiliate(&mut self, code: String) {
+        require!(
+            code.len() >= MIN_CODE_LEN && code.len() <= MAX_CODE_LEN,
+            format!("Affiliate code must be {} to {} characters", MIN_CODE_LEN, MAX_CODE_LEN)
+        );
+        require!(
+            code.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_'),
+            "Affiliate code may only contain lowercase letters, digits, '-' and '_'"
+        );
+        require!(self.affiliates.get(&code).is_none(), "Affiliate code is taken");
+        require!(self.affiliates.len() < MAX_AFFILIATES, "Too many affiliates");
+
+        let initial_storage_usage = env::storage_usage();
+        self.affiliates.insert(&code, &AffiliateInfo {
+            code: code.clone(),
+            account_id: env::predecessor_account_id(),
+            mints_referred: 0,
+            total_earned: U128(0),
+        });
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    /// Remove an abusive affiliate code.
+    #[payable]
+    pub fn remove_affiliate(&mut self, code: String) {
+        self.assert_owner();
+        require!(self.affiliates.remove(&code).is_some(), "Affiliate not found");
+    }
+
+    #[payable]
+    pub fn set_affiliate_bps(&mut self, affiliate_bps: u32) {
+        self.assert_owner();
+        require!(
+            affiliate_bps <= MAX_AFFILIATE_BPS,
+            format!("Affiliate share can't exceed {} bps", MAX_AFFILIATE_BPS)
+        );
+        self.affiliate_bps = affiliate_bps;
+    }
+
+    pub fn affiliate_info(&self, code: String) -> Option<AffiliateInfo> {
+        self.affiliates.get(&code)
+    }
+
+    /// Affiliates ordered by lifetime earnings, highest first.
+    pub fn affiliate_leaderboard(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<AffiliateInfo> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        let mut affiliates: Vec<AffiliateInfo> = self.affiliates.values().collect();
+        affiliates.sort_by(|a, b| b.total_earned.0.cmp(&a.total_earned.0).then(a.code.cmp(&b.code)));
+        affiliates.into_iter().skip(start).take(page_limit(limit)).collect()
+    }
+}
+
+impl Contract {
+    /// Resolve `code` to its affiliate account for a mint by `minter` and add the mint and the
+    /// affiliate's `share` to their lifetime counters.
+    pub(crate) fn internal_credit_affiliate(&mut self, code: &String, minter: &AccountId, share: u128) -> AccountId {
+        let mut info = self.affiliates.get(code).expect("Affiliate code not found");
+        require!(&info.account_id != minter, "Can't use your own affiliate code");
+
+        info.mints_referred += 1;
+        info.total_earned = U128(info.total_earned.0 + share);
+        self.affiliates.insert(code, &info);
+        info.account_id
+    }
+}
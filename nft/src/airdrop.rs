@@ -0,0 +1,54 @@
+use crate::*;
+
+/// Receivers per `nft_mint` event log, keeping each log well under the 16kB log limit.
+pub(crate) const AIRDROP_EVENT_BATCH: usize = 50;
+
+#[near_bindgen]
+impl Contract {
+    /// Mint one token to each of `receivers`, with the next free ids and `base_metadata`, its
+    /// title suffixed with the token id. Airdropped tokens have no vault, so the attached deposit
+    /// only pays for their storage, and they burn without any rewards.
+    #[payable]
+    pub fn airdrop(&mut self, receivers: Vec<AccountId>, base_metadata: TokenMetadata) -> Vec<TokenId> {
+        self.assert_owner();
+        self.assert_mint_not_paused();
+        require!(!receivers.is_empty(), "Nothing to mint");
+
+        let mut minted: Vec<Token> = Vec::with_capacity(receivers.len());
+        let mut storage_cost = 0;
+        for receiver_id in receivers {
+            let token_id = self.internal_assign_token_id(None);
+            let mut token_metadata = base_metadata.clone();
+            token_metadata.title = base_metadata.title.as_ref().map(|title| format!("{} #{}", title, token_id));
+            let options = MintOptions {
+                price: Some(0),
+                without_vault: true,
+                currency: self.base_currency.clone(),
+                ..Default::default()
+            };
+            let (token, _, token_storage) =
+                self.internal_record_mint(token_id, receiver_id.clone(), token_metadata, receiver_id, options);
+            storage_cost += token_storage;
+            minted.push(token);
+        }
+        let deposit = env::attached_deposit().as_yoctonear();
+        require!(
+            deposit >= storage_cost,
+            format!("Must attach {} yoctoNEAR more to cover token storage", storage_cost - deposit)
+        );
+
+        for batch in minted.chunks(AIRDROP_EVENT_BATCH) {
+            let token_ids: Vec<[&str; 1]> = batch.iter().map(|token| [token.token_id.as_str()]).collect();
+            let events: Vec<NftMint> = batch.iter()
+                .zip(token_ids.iter())
+                .map(|(token, token_ids)| NftMint { owner_id: &token.owner_id, token_ids, memo: None })
+                .collect();
+            NftMint::emit_many(&events);
+        }
+
+        if deposit > storage_cost {
+            Promise::new(env::predecessor_account_id()).transfer(NearToken::from_yoctonear(deposit - storage_cost));
+        }
+        minted.into_iter().map(|token| token.token_id).collect()
+    }
+}
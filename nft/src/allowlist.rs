@@ -0,0 +1,71 @@
+use crate::*;
+use near_sdk::CurveType;
+
+//how many mints a proof allows each account per phase by default
+pub const DEFAULT_ALLOWLIST_WALLET_LIMIT: u32 = 1;
+
+#[near_bindgen]
+impl Contract {
+    /// Require `nft_mint` callers to present a proof signed by `signer_pk` instead of being stored
+    /// on an allowlist. `None` turns the check off.
+    pub fn set_allowlist_signer(&mut self, signer_pk: Option<PublicKey>) {
+        self.assert_owner();
+        if let Some(signer_pk) = &signer_pk {
+            require!(signer_pk.curve_type() == CurveType::ED25519, "The signer key must be ed25519");
+        }
+        self.allowlist_signer_pk = signer_pk;
+    }
+
+    /// Start phase `phase_id`, in which every account may mint `wallet_limit` tokens with a proof
+    /// signed for the phase. Proofs signed for other phases stop working.
+    pub fn set_allowlist_phase(&mut self, phase_id: u32, wallet_limit: u32) {
+        self.assert_owner();
+        self.allowlist_phase_id = phase_id;
+        self.allowlist_wallet_limit = wallet_limit;
+    }
+
+    pub fn allowlist_signer(&self) -> Option<PublicKey> {
+        self.allowlist_signer_pk.clone()
+    }
+
+    pub fn allowlist_phase(&self) -> u32 {
+        self.allowlist_phase_id
+    }
+
+    /// How many tokens `account_id` has minted with a proof in the active phase.
+    pub fn allowlist_mints_of(&self, account_id: AccountId) -> u32 {
+        self.allowlist_mints.get(&(account_id, self.allowlist_phase_id)).unwrap_or(0)
+    }
+}
+
+impl Contract {
+    /// When an allowlist signer is set, check `proof` is its ed25519 signature over
+    /// `sha256(account_id || phase_id)`, with the active phase id as 4 little-endian bytes, and
+    /// count the mint against the account's limit for the phase.
+    pub(crate) fn internal_use_allowlist_proof(&mut self, account_id: &AccountId, proof: Option<Base64VecU8>) {
+        let signer_pk = match &self.allowlist_signer_pk {
+            Some(signer_pk) => signer_pk,
+            None => return,
+        };
+        let proof = proof.unwrap_or_else(|| env::panic_str("An allowlist proof is required to mint"));
+        let signature: [u8; 64] = proof.0.as_slice().try_into()
+            .unwrap_or_else(|_| env::panic_str("Allowlist proof must be a 64 byte signature"));
+        let public_key: [u8; 32] = signer_pk.as_bytes()[1..].try_into().unwrap();
+
+        let phase_id = self.allowlist_phase_id;
+        let mut message = account_id.as_str().as_bytes().to_vec();
+        message.extend_from_slice(&phase_id.to_le_bytes());
+        require!(
+            env::ed25519_verify(&signature, &env::sha256(&message), &public_key),
+            "Invalid allowlist proof for this phase"
+        );
+
+        let key = (account_id.clone(), phase_id);
+        let used = self.allowlist_mints.get(&key).unwrap_or(0);
+        require!(
+            used < self.allowlist_wallet_limit,
+            format!("Allowlist limit of {} mints reached for this phase", self.allowlist_wallet_limit)
+        );
+        self.allowlist_mints.insert(&key, &(used + 1));
+    }
+}
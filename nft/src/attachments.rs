@@ -0,0 +1,150 @@
+use crate::*;
+use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+
+//the most foreign NFTs one token can hold
+pub const MAX_ATTACHMENTS_PER_TOKEN: usize = 8;
+//gas for sending an attached NFT back and for settling the transfer
+const GAS_FOR_NFT_TRANSFER: Gas = Gas::from_tgas(15);
+const GAS_FOR_RESOLVE_DETACH: Gas = Gas::from_tgas(10);
+
+/// An NFT from another collection held by this contract as part of one of its tokens.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct AttachedNft {
+    pub contract_id: AccountId,
+    pub token_id: TokenId,
+    //paid for the attachment's storage from their storage deposit and is credited it back
+    pub attached_by: AccountId,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum TransferMsg {
+    Attach { to_token_id: TokenId },
+}
+
+#[near_bindgen]
+impl NonFungibleTokenReceiver for Contract {
+    /// Keep an NFT sent with `{"action":"attach","to_token_id":"..."}` as part of that token.
+    /// The sender must own the host token and its storage deposit pays for the attachment.
+    /// A token of this collection sent back with the `burn` msg is burned for its previous owner,
+    /// and with any other msg returned.
+    fn nft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_id: TokenId,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        let contract_id = env::predecessor_account_id();
+        if contract_id == env::current_account_id() {
+            if msg != "burn" {
+                return PromiseOrValue::Value(true);
+            }
+            self.internal_burn_sent_back(&sender_id, &previous_owner_id, &token_id);
+            return PromiseOrValue::Value(false);
+        }
+        let TransferMsg::Attach { to_token_id } = near_sdk::serde_json::from_str(&msg)
+            .unwrap_or_else(|_| env::panic_str("Unsupported transfer msg"));
+        self.assert_token_owner(&to_token_id, &previous_owner_id);
+
+        let initial_storage_usage = env::storage_usage();
+        let mut attached = self.attachments.get(&to_token_id).unwrap_or_default();
+        require!(
+            attached.len() < MAX_ATTACHMENTS_PER_TOKEN,
+            format!("A token can hold at most {} attachments", MAX_ATTACHMENTS_PER_TOKEN)
+        );
+        attached.push(AttachedNft { contract_id, token_id, attached_by: sender_id.clone() });
+        self.attachments.insert(&to_token_id, &attached);
+
+        let cost = storage_cost_since(initial_storage_usage);
+        let balance = self.storage_deposits.get(&sender_id).unwrap_or(0);
+        require!(balance >= cost, format!("Attaching needs {} yoctoNEAR of storage deposit", cost));
+        self.storage_deposits.insert(&sender_id, &(balance - cost));
+        PromiseOrValue::Value(false)
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Send the attachment at `index` of `to_token_id` back to the host token's owner.
+    #[payable]
+    pub fn detach(&mut self, to_token_id: TokenId, index: u32) -> Promise {
+        assert_one_yocto();
+        let owner = env::predecessor_account_id();
+        self.assert_token_owner(&to_token_id, &owner);
+        let mut attached = self.attachments.get(&to_token_id).unwrap_or_default();
+        require!((index as usize) < attached.len(), "No attachment at this index");
+
+        let initial_storage_usage = env::storage_usage();
+        let attachment = attached.remove(index as usize);
+        if attached.is_empty() {
+            self.attachments.remove(&to_token_id);
+        } else {
+            self.attachments.insert(&to_token_id, &attached);
+        }
+        let refund = storage_freed_since(initial_storage_usage);
+        self.internal_credit_storage(&attachment.attached_by, refund);
+
+        self.internal_send_attachment(&attachment, &owner)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_DETACH)
+                    .resolve_detach(to_token_id, attachment)
+            )
+    }
+
+    /// Put an attachment whose transfer failed back on its host token, if the token still exists.
+    #[private]
+    pub fn resolve_detach(&mut self, to_token_id: TokenId, attachment: AttachedNft) -> bool {
+        if env::promise_results_count() == 1 && matches!(env::promise_result(0), PromiseResult::Successful(_)) {
+            return true;
+        }
+        if self.tokens.owner_by_id.get(&to_token_id).is_some() {
+            let mut attached = self.attachments.get(&to_token_id).unwrap_or_default();
+            attached.push(attachment);
+            self.attachments.insert(&to_token_id, &attached);
+        }
+        false
+    }
+
+    pub fn attachments_of(&self, token_id: TokenId) -> Vec<AttachedNft> {
+        self.attachments.get(&token_id).unwrap_or_default()
+    }
+}
+
+impl Contract {
+    /// Send every attachment of a token being burned to `owner`.
+    pub(crate) fn internal_release_attachments(&mut self, token_id: &TokenId, owner: &AccountId) {
+        let attached = match self.attachments.get(token_id) {
+            Some(attached) => attached,
+            None => return,
+        };
+        let initial_storage_usage = env::storage_usage();
+        self.attachments.remove(token_id);
+        let refund = storage_freed_since(initial_storage_usage) / attached.len() as u128;
+        for attachment in attached.iter() {
+            self.internal_credit_storage(&attachment.attached_by, refund);
+            self.internal_send_attachment(attachment, owner);
+        }
+    }
+
+    fn internal_send_attachment(&self, attachment: &AttachedNft, receiver_id: &AccountId) -> Promise {
+        Promise::new(attachment.contract_id.clone()).function_call(
+            "nft_transfer".to_string(),
+            json!({
+                "receiver_id": receiver_id,
+                "token_id": attachment.token_id,
+            }).to_string().into_bytes().to_vec(),
+            NearToken::from_yoctonear(1),
+            GAS_FOR_NFT_TRANSFER
+        )
+    }
+
+    fn internal_credit_storage(&mut self, account_id: &AccountId, amount: u128) {
+        let balance = self.storage_deposits.get(account_id).unwrap_or(0);
+        self.storage_deposits.insert(account_id, &(balance + amount));
+    }
+}
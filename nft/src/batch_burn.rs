@@ -0,0 +1,62 @@
+use crate::*;
+use crate::burn_redeem::GAS_FOR_FT_REDEEM;
+
+//gas a burn spends on the token's own state and on scheduling its vault withdrawal
+const GAS_FOR_BURN_LOCAL: Gas = Gas::from_tgas(10);
+//most tokens one burn_many call burns: as many vault withdrawals as the gas of one transaction pays
+pub const MAX_BURNS_PER_CALL: u64 =
+    MAX_TRANSACTION_GAS.as_gas() / (GAS_FOR_VAULT_WITHDRAW.as_gas() + GAS_FOR_BURN_LOCAL.as_gas());
+
+#[near_bindgen]
+impl Contract {
+    /// Burn every token of `token_ids` the caller owns, at most `MAX_BURNS_PER_CALL`. Each token is
+    /// burned as by `burn`, with its own `nft_burn` event and vault withdrawal, but the other
+    /// holders are credited the burn fees of the successful withdrawals in a single pass of
+    /// `resolve_burn`. Nothing is burned unless every token can be. Requires exactly one
+    /// yoctoNEAR, as `burn` does.
+    #[payable]
+    pub fn burn_many(&mut self, token_ids: Vec<TokenId>) {
+        assert_one_yocto();
+        let owner = env::predecessor_account_id();
+        let count = token_ids.len() as u64;
+        require!(count > 0, "Nothing to burn");
+        require!(
+            count <= MAX_BURNS_PER_CALL,
+            format!("At most {} tokens can be burned at once", MAX_BURNS_PER_CALL)
+        );
+        // Vaults holding an FT register their owner with it first
+        let redeems = token_ids.iter().filter(|token_id| self.vault_currency(token_id).is_some()).count() as u64;
+        let gas_needed = (GAS_FOR_VAULT_WITHDRAW.as_gas() + GAS_FOR_BURN_LOCAL.as_gas()) * count
+            + GAS_FOR_FT_REDEEM.as_gas() * redeems;
+        require!(
+            env::prepaid_gas().as_gas() >= gas_needed,
+            format!("Attach at least {} gas to burn {} tokens", gas_needed, count)
+        );
+        for (i, token_id) in token_ids.iter().enumerate() {
+            require!(!token_ids[..i].contains(token_id), format!("Token {} is listed twice", token_id));
+            self.assert_token_owner(token_id, &owner);
+            self.assert_transferable(token_id);
+            self.assert_burnable(token_id);
+        }
+
+        let mut burned = Vec::new();
+        let mut withdrawals: Option<Promise> = None;
+        for token_id in token_ids {
+            if let (_, burned_vault, Some(withdrawal)) = self.internal_remove_burned(&token_id, &owner, None, None) {
+                burned.push(burned_vault);
+                withdrawals = Some(match withdrawals {
+                    Some(joined) => joined.and(withdrawal),
+                    None => withdrawal,
+                });
+            }
+        }
+        match withdrawals {
+            Some(withdrawals) => {
+                then_resolve_burn(withdrawals, &owner, burned);
+            }
+            None => {
+                Promise::new(owner).transfer(NearToken::from_yoctonear(1));
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::*;
+
+//tokens one nft_mint_many call mints at most unless the owner changes it
+pub const DEFAULT_MAX_MINTS_PER_TX: u64 = 10;
+//most gas one transaction can attach
+pub(crate) const MAX_TRANSACTION_GAS: Gas = Gas::from_tgas(1_000);
+//highest `max_mints_per_tx`: as many vaults as the gas of one transaction can deploy
+pub const MAX_MINTS_PER_TX_CEILING: u64 = MAX_TRANSACTION_GAS.as_gas() / GAS_FOR_NEAR_MINT.as_gas();
+
+#[near_bindgen]
+impl Contract {
+    /// Mint every `(token_id, owner, metadata)` of `tokens` outside a series, each with its own
+    /// vault, in a single `nft_mint` event. The whole batch must fit in the total supply, and any
+    /// NEAR left over after price, vault deposits and storage is refunded. `pay_with` picks the
+    /// currency as in `nft_mint`.
+    #[payable]
+    pub fn nft_mint_many(
+        &mut self,
+        tokens: Vec<(TokenId, AccountId, TokenMetadata)>,
+        pay_with: Option<AccountId>,
+    ) -> Vec<Token> {
+        self.assert_mint_not_paused();
+        let holder = env::predecessor_account_id();
+        self.assert_minter(&holder);
+        self.internal_use_mint_cooldown(&holder);
+        self.assert_pass_verified(&holder);
+        self.assert_sale_open(&holder);
+        require!(
+            (self.allowlist_signer_pk.is_none() && self.allowlist_root.is_none()) || holder == self.tokens.owner_id,
+            "Batch minting is closed while an allowlist is active"
+        );
+        require!(self.id_pool.is_empty(), "Token ids are drawn from the pool");
+        require!(
+            self.sale_phase != SalePhase::Presale || !self.presale_tiers.contains_key(&holder),
+            "Tiered accounts mint one token at a time during the presale"
+        );
+        require!(
+            self.usd_pricing.is_none() || pay_with.is_some(),
+            "Batch minting in NEAR is closed while the price is pegged to USD"
+        );
+        let count = tokens.len();
+        require!(count > 0, "Nothing to mint");
+        require!(
+            count as u64 <= self.max_mints_per_tx,
+            format!("At most {} tokens can be minted at once", self.max_mints_per_tx)
+        );
+        let gas_needed = self.gas_per_mint(0, pay_with.is_some()).as_gas() * count as u64;
+        require!(
+            env::prepaid_gas().as_gas() >= gas_needed,
+            format!("Attach at least {} gas to mint {} tokens", gas_needed, count)
+        );
+        for (_, token_owner_id, _) in tokens.iter() {
+            self.internal_count_wallet_mints(token_owner_id, 1);
+        }
+        self.assert_supply_left(count as u128);
+
+        // Tiers are resolved per token, so a batch can span two of them
+        let prices: Vec<u128> = (0..count as u128)
+            .map(|i| self.mint_price_in(&pay_with, self.mint_price_at(self.index + i)))
+            .collect();
+        let total_price: u128 = prices.iter().sum();
+        let storage_estimate: u128 = tokens.iter()
+            .map(|(_, _, token_metadata)| estimate_token_storage(Some(token_metadata)))
+            .sum();
+        let deposit = env::attached_deposit().as_yoctonear();
+        let vaults = prices.iter().filter(|price| self.mints_vault(**price)).count() as u128;
+        let near_price = self.internal_take_mint_payment(
+            &holder,
+            &pay_with,
+            total_price,
+            deposit,
+            self.vault_deposit() * vaults,
+            storage_estimate,
+        );
+
+        let mut minted = Vec::with_capacity(count);
+        let mut storage_cost = 0;
+        for ((token_id, token_owner_id, token_metadata), price) in tokens.into_iter().zip(prices) {
+            let token_metadata = self.minted_metadata(token_metadata);
+            let without_vault = !self.mints_vault(price);
+            let options = MintOptions { price: Some(price), currency: pay_with.clone(), without_vault, ..Default::default() };
+            let (token, proceeds, token_storage) =
+                self.internal_record_mint(token_id, token_owner_id, token_metadata, holder.clone(), options);
+            storage_cost += token_storage;
+            minted.push((token, proceeds, without_vault));
+        }
+        let required = near_price + storage_cost;
+        require!(
+            deposit >= required,
+            format!("Must attach {} yoctoNEAR more to cover token storage", required - deposit)
+        );
+
+        // One event, with an entry per owner in the order owners first appear, its memo totalling
+        // that owner's tokens
+        let mut owners: Vec<(&AccountId, Vec<&str>, Vec<&MintProceeds>)> = Vec::new();
+        for (token, proceeds, _) in minted.iter() {
+            match owners.iter_mut().find(|(owner_id, _, _)| *owner_id == &token.owner_id) {
+                Some((_, token_ids, owner_proceeds)) => {
+                    token_ids.push(&token.token_id);
+                    owner_proceeds.push(proceeds);
+                }
+                None => owners.push((&token.owner_id, vec![&token.token_id], vec![proceeds])),
+            }
+        }
+        let memos: Vec<String> = owners.iter().map(|(_, _, proceeds)| self.mint_memo(proceeds)).collect();
+        let events: Vec<NftMint> = owners.iter()
+            .zip(memos.iter())
+            .map(|((owner_id, token_ids, _), memo)| NftMint { owner_id, token_ids, memo: Some(memo) })
+            .collect();
+        NftMint::emit_many(&events);
+        for (token, proceeds, _) in minted.iter() {
+            emit_mint_paid(token, proceeds);
+        }
+
+        if deposit > required {
+            Promise::new(holder).transfer(NearToken::from_yoctonear(deposit - required));
+        }
+        minted.into_iter()
+            .map(|(token, proceeds, without_vault)| {
+                if without_vault {
+                    self.internal_pay_vaultless_proceeds(proceeds);
+                } else {
+                    self.internal_deploy_vault(&token.token_id, proceeds);
+                }
+                token
+            })
+            .collect()
+    }
+
+    /// Cap how many tokens one `nft_mint_many` call can mint, up to `MAX_MINTS_PER_TX_CEILING`.
+    pub fn set_max_mints_per_tx(&mut self, max_mints_per_tx: u64) {
+        self.assert_owner();
+        require!(max_mints_per_tx > 0, "Must allow at least one mint per transaction");
+        require!(
+            max_mints_per_tx <= MAX_MINTS_PER_TX_CEILING,
+            format!("Can't exceed {} mints per transaction", MAX_MINTS_PER_TX_CEILING)
+        );
+        self.max_mints_per_tx = max_mints_per_tx;
+    }
+
+    pub fn max_mints_per_tx(&self) -> u64 {
+        self.max_mints_per_tx
+    }
+}
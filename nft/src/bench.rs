@@ -0,0 +1,16 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Insert `accounts` into `holders` without minting, so the gas benchmarks can measure flows
+    /// that scale with the holder count. Only compiled with the `bench` feature.
+    pub fn dev_seed_holders(&mut self, accounts: Vec<AccountId>) -> u64 {
+        self.assert_owner();
+        for account in accounts.iter() {
+            if self.holders.insert(account) {
+                self.internal_update_reward_weight(account);
+            }
+        }
+        self.holders.len()
+    }
+}
@@ -0,0 +1,98 @@
+use crate::*;
+
+//the longest destination address a lock can name
+const MAX_DEST_ADDRESS_LEN: usize = 256;
+
+/// A token held in place while it is represented on another chain.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct BridgeLock {
+    pub owner_id: AccountId,
+    pub dest_chain_address: String,
+    pub nonce: U64,
+    pub locked_at: U64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BridgeStatus {
+    pub token_id: TokenId,
+    pub locked: bool,
+    pub lock: Option<BridgeLock>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Lock `token_id` for export to `dest_chain_address`. The token can't be transferred, listed
+    /// or burned until the bridge account unlocks it with the nonce of the emitted `bridge_lock`
+    /// event. The attached deposit pays for the lock record.
+    #[payable]
+    pub fn lock_for_bridge(&mut self, token_id: TokenId, dest_chain_address: String) -> U64 {
+        require!(self.bridge_account.is_some(), "No bridge account is configured");
+        let owner = env::predecessor_account_id();
+        self.assert_token_owner(&token_id, &owner);
+        self.assert_transferable(&token_id);
+        self.assert_no_approvals(&token_id);
+        require!(
+            !dest_chain_address.is_empty() && dest_chain_address.len() <= MAX_DEST_ADDRESS_LEN,
+            format!("Destination address must be 1 to {} bytes", MAX_DEST_ADDRESS_LEN)
+        );
+
+        let initial_storage_usage = env::storage_usage();
+        self.bridge_nonce += 1;
+        let nonce = U64(self.bridge_nonce);
+        self.bridge_locks.insert(&token_id, &BridgeLock {
+            owner_id: owner.clone(),
+            dest_chain_address: dest_chain_address.clone(),
+            nonce,
+            locked_at: U64(env::block_timestamp()),
+        });
+        refund_storage_deposit(initial_storage_usage);
+
+        Event::BridgeLock {
+            owner_id: &owner,
+            token_id: &token_id,
+            dest_chain_address: &dest_chain_address,
+            nonce
+        }.emit();
+        nonce
+    }
+
+    /// Release a token the bridge has brought back, proven by the nonce it was locked with.
+    pub fn unlock_from_bridge(&mut self, token_id: TokenId, proof_nonce: U64) {
+        require!(
+            self.bridge_account.as_ref() == Some(&env::predecessor_account_id()),
+            "Only the bridge account can unlock tokens"
+        );
+        let lock = self.bridge_locks.get(&token_id)
+            .unwrap_or_else(|| env::panic_str("Token is not locked for the bridge"));
+        require!(lock.nonce == proof_nonce, "Nonce doesn't match the lock");
+        self.bridge_locks.remove(&token_id);
+        Event::BridgeUnlock { token_id: &token_id, nonce: proof_nonce }.emit();
+    }
+
+    pub fn set_bridge_account(&mut self, bridge_account: Option<AccountId>) {
+        self.assert_owner();
+        self.bridge_account = bridge_account;
+    }
+
+    pub fn bridge_account(&self) -> Option<AccountId> {
+        self.bridge_account.clone()
+    }
+
+    pub fn bridge_status(&self, token_id: TokenId) -> BridgeStatus {
+        let lock = self.bridge_locks.get(&token_id);
+        BridgeStatus { token_id, locked: lock.is_some(), lock }
+    }
+
+    pub fn locked_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<BridgeStatus> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.bridge_locks
+            .iter()
+            .skip(start)
+            .take(page_limit(limit))
+            .map(|(token_id, lock)| BridgeStatus { token_id, locked: true, lock: Some(lock) })
+            .collect()
+    }
+}
@@ -0,0 +1,59 @@
+use crate::*;
+
+//basis points of a whole burn fee
+pub const MAX_BURN_FEE_BPS: u32 = 10_000;
+
+/// A burn fee that decays linearly from `initial_bps` at a token's mint down to `final_bps`
+/// `decay_duration` nanoseconds later, e.g. 5000 to 500 over a year to discourage early burns.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct BurnFeeSchedule {
+    pub initial_bps: u32,
+    pub final_bps: u32,
+    pub decay_duration: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Charge burns on `schedule` instead of the flat `burn_fee`, or with `None` go back to it.
+    /// Tokens minted before the schedule was set, and so without a mint timestamp, are charged
+    /// its final fee.
+    pub fn set_burn_fee_schedule(&mut self, schedule: Option<BurnFeeSchedule>) {
+        self.assert_owner();
+        if let Some(schedule) = &schedule {
+            require!(schedule.initial_bps <= MAX_BURN_FEE_BPS, "The burn fee can't exceed 100%");
+            require!(schedule.final_bps <= schedule.initial_bps, "The burn fee can only decay");
+        }
+        self.burn_fee_schedule = schedule;
+    }
+
+    pub fn burn_fee_schedule(&self) -> Option<BurnFeeSchedule> {
+        self.burn_fee_schedule
+    }
+
+    /// The fee burning `token_id` now would be charged, in basis points of its vault.
+    pub fn current_burn_fee(&self, token_id: TokenId) -> u32 {
+        require!(self.tokens.owner_by_id.contains_key(&token_id), "Token not found");
+        self.burn_fee_bps(&token_id)
+    }
+}
+
+impl Contract {
+    /// The burn fee of `token_id` at the current block, in basis points.
+    pub(crate) fn burn_fee_bps(&self, token_id: &TokenId) -> u32 {
+        let Some(schedule) = self.burn_fee_schedule else {
+            return self.burn_fee as u32 * 100;
+        };
+        let Some(minted_at) = self.minted_at.get(token_id) else {
+            return schedule.final_bps;
+        };
+        let elapsed = env::block_timestamp().saturating_sub(minted_at);
+        if elapsed >= schedule.decay_duration.0 {
+            return schedule.final_bps;
+        }
+        let decay = (schedule.initial_bps - schedule.final_bps) as u128 * elapsed as u128
+            / schedule.decay_duration.0 as u128;
+        schedule.initial_bps - decay as u32
+    }
+}
@@ -0,0 +1,41 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Keep tokens minted from now on from being burned for `burn_lock_duration` nanoseconds, so
+    /// minting and burning at once can't farm the holder rewards. Tokens already minted keep the
+    /// lock they were minted under.
+    pub fn set_burn_lock_duration(&mut self, burn_lock_duration: U64) {
+        self.assert_owner();
+        self.burn_lock_duration = burn_lock_duration.0;
+    }
+
+    pub fn burn_lock_duration(&self) -> U64 {
+        U64(self.burn_lock_duration)
+    }
+
+    /// When `token_id` can first be burned, if it was minted under a burn lock.
+    pub fn burn_unlocks_at(&self, token_id: TokenId) -> Option<U64> {
+        self.burn_unlocks_at.get(&token_id).map(U64)
+    }
+}
+
+impl Contract {
+    /// Start the burn lock of a token minted now, if one is set.
+    pub(crate) fn internal_lock_burn(&mut self, token_id: &TokenId) {
+        if self.burn_lock_duration > 0 {
+            let unlocks_at = env::block_timestamp().saturating_add(self.burn_lock_duration);
+            self.burn_unlocks_at.insert(token_id, &unlocks_at);
+        }
+    }
+
+    /// Panic if `token_id` is still inside the burn lock it was minted under.
+    pub(crate) fn assert_burnable(&self, token_id: &TokenId) {
+        if let Some(unlocks_at) = self.burn_unlocks_at.get(token_id) {
+            require!(
+                env::block_timestamp() >= unlocks_at,
+                format!("Token {} can't be burned until {}", token_id, unlocks_at)
+            );
+        }
+    }
+}
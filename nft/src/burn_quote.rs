@@ -0,0 +1,102 @@
+use crate::*;
+use crate::staking::BASE_REWARD_WEIGHT;
+
+//gas for reading a vault's amount and for turning it into a quote
+const GAS_FOR_VAULT_AMOUNT: Gas = Gas::from_tgas(5);
+const GAS_FOR_RESOLVE_BURN_QUOTE: Gas = Gas::from_tgas(10);
+
+/// What burning a token now would pay, from the same formulas as `burn`. The holders' share is
+/// spread by reward weight, so `per_holder` is what a holder without staked tokens would get.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BurnQuote {
+    pub vault_amount: U128,
+    pub burn_fee_bps: u32,
+    //what the vault pays the owner
+    pub owner_payout: U128,
+    //the half of the burn fee shared by every other holder, the other half goes to the treasury,
+    //as does this one for a vault funded in another currency than the base one
+    pub to_holders: U128,
+    pub per_holder: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Estimate what burning `token_id` now would pay, from what its mint locked in its vault.
+    /// See `burn_quote_exact` for a quote from the vault's actual amount.
+    pub fn burn_quote(&self, token_id: TokenId) -> BurnQuote {
+        let owner = self.tokens.owner_by_id.get(&token_id)
+            .unwrap_or_else(|| env::panic_str("Token not found"));
+        self.internal_burn_quote(&token_id, &owner, self.estimated_vault_amount(&token_id))
+    }
+
+    /// Quote what burning `token_id` now would pay from the amount its vault reports.
+    pub fn burn_quote_exact(&self, token_id: TokenId) -> Promise {
+        require!(self.tokens.owner_by_id.contains_key(&token_id), "Token not found");
+        require!(
+            !self.token_extra(&token_id).without_vault,
+            format!("Token {} has no vault, its burn pays nothing", token_id)
+        );
+        let vault_account_id: AccountId = format!("{}.{}", token_id, env::current_account_id()).parse().unwrap();
+        Promise::new(vault_account_id)
+            .function_call("vault_amount".to_string(), vec![], NearToken::from_yoctonear(0), GAS_FOR_VAULT_AMOUNT)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_BURN_QUOTE)
+                    .resolve_burn_quote(token_id),
+            )
+    }
+
+    #[private]
+    pub fn resolve_burn_quote(&self, token_id: TokenId) -> BurnQuote {
+        let vault_amount = match env::promise_result(0) {
+            PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<U128>(&value).ok(),
+            PromiseResult::Failed => None,
+        }.unwrap_or_else(|| env::panic_str(&format!("The vault of token {} didn't report its amount", token_id)));
+        let owner = self.tokens.owner_by_id.get(&token_id)
+            .unwrap_or_else(|| env::panic_str("Token not found"));
+        self.internal_burn_quote(&token_id, &owner, vault_amount.0)
+    }
+}
+
+impl Contract {
+    /// What the mint of `token_id` locked in its vault, or nothing for a token minted without a
+    /// vault. Tokens minted before the amount was recorded locked their split of the mint price,
+    /// which had no fee taken off.
+    pub(crate) fn estimated_vault_amount(&self, token_id: &TokenId) -> u128 {
+        let extra = self.token_extra(token_id);
+        if extra.without_vault {
+            return 0;
+        }
+        self.vault_amounts.get(token_id).unwrap_or_else(|| {
+            let terms = self.series_terms(extra.series_id);
+            terms.mint_price * self.token_split_percent(token_id, extra.series_id) / 100
+        })
+    }
+
+    fn internal_burn_quote(&self, token_id: &TokenId, owner: &AccountId, vault_amount: u128) -> BurnQuote {
+        let burn_fee_bps = self.burn_fee_bps(token_id);
+        let (burn_fee_applied, to_holders) = split_burn_fee(vault_amount, burn_fee_bps as u128);
+        // The owner doesn't share its own burn fee, whether it keeps other tokens or not
+        let eligible_weight = self.total_reward_weight - self.settled_reward_weight(owner);
+        let per_weight = if self.vault_currency(token_id) == self.base_currency {
+            to_holders.checked_div(eligible_weight).unwrap_or(0)
+        } else {
+            0
+        };
+        BurnQuote {
+            vault_amount: U128(vault_amount),
+            burn_fee_bps,
+            owner_payout: U128(vault_amount - burn_fee_applied),
+            to_holders: U128(to_holders),
+            per_holder: U128(per_weight * BASE_REWARD_WEIGHT),
+        }
+    }
+}
+
+/// The burn fee taken from `vault_amount` at `burn_fee_bps`, and the half of it the vault sends
+/// back to be shared by the holders.
+pub(crate) fn split_burn_fee(vault_amount: u128, burn_fee_bps: u128) -> (u128, u128) {
+    let burn_fee = vault_amount.checked_mul(burn_fee_bps).unwrap() / 10_000;
+    (burn_fee, burn_fee / 2)
+}
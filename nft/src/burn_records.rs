@@ -0,0 +1,79 @@
+use crate::*;
+
+/// A burned token, kept for provenance once the token is gone from every other view. `payout` is
+/// what its vault was asked to pay the owner, `None` for a token without a vault or a withdrawal
+/// that failed.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct BurnRecord {
+    pub token_id: TokenId,
+    pub owner_id: AccountId,
+    pub timestamp: U64,
+    pub payout: Option<U128>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Burn records in the order the tokens were burned. Tokens burned before records were kept
+    /// have none.
+    pub fn burned_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<BurnRecord> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.burn_records.iter().skip(start).take(page_limit(limit)).collect()
+    }
+
+    pub fn burn_record(&self, token_id: TokenId) -> Option<BurnRecord> {
+        self.burn_record_indexes
+            .get(&token_id)
+            .and_then(|index| self.burn_records.get(index))
+    }
+}
+
+impl Contract {
+    /// Record the burn of `token_id` owned by `owner`, whose removal left the storage at
+    /// `removed_storage_usage` and freed `freed` yoctoNEAR of it. The record is charged to
+    /// `burner`'s storage deposit, and what that doesn't cover to the freed storage. The rest of
+    /// the freed storage is refunded to `burner` too, the owner or the approved account that
+    /// burned the token and paid for its record.
+    pub(crate) fn internal_record_burn(
+        &mut self,
+        token_id: &TokenId,
+        owner: &AccountId,
+        burner: &AccountId,
+        payout: Option<U128>,
+        removed_storage_usage: u64,
+        freed: u128,
+    ) {
+        self.burn_record_indexes.insert(token_id, &self.burn_records.len());
+        self.burn_records.push(&BurnRecord {
+            token_id: token_id.clone(),
+            owner_id: owner.clone(),
+            timestamp: U64(env::block_timestamp()),
+            payout,
+        });
+        let cost = storage_cost_since(removed_storage_usage);
+        let balance = self.storage_deposits.get(burner).unwrap_or(0);
+        let from_deposit = cost.min(balance);
+        if from_deposit > 0 {
+            self.storage_deposits.insert(burner, &(balance - from_deposit));
+        }
+        let uncovered = cost - from_deposit;
+        require!(
+            uncovered <= freed,
+            format!("Recording the burn needs {} yoctoNEAR of storage deposit", uncovered - freed)
+        );
+        let refund = freed - uncovered;
+        if refund > 0 {
+            Promise::new(burner.clone()).transfer(NearToken::from_yoctonear(refund));
+        }
+    }
+
+    /// Clear the payout of burned `token_id`, whose vault withdrawal failed.
+    pub(crate) fn internal_clear_burn_payout(&mut self, token_id: &TokenId) {
+        if let Some(index) = self.burn_record_indexes.get(token_id) {
+            let mut record = self.burn_records.get(index).unwrap();
+            record.payout = None;
+            self.burn_records.replace(index, &record);
+        }
+    }
+}
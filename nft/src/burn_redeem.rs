@@ -0,0 +1,165 @@
+use crate::*;
+use crate::burn_quote::split_burn_fee;
+use crate::internal::vault_withdrawal;
+
+//gas to register a burned token's owner with the collection's FT before its vault pays out
+const GAS_FOR_FT_REGISTRATION: Gas = Gas::from_tgas(20);
+//gas withdraw_registered spends on its own before calling the vault
+const GAS_FOR_WITHDRAW_REGISTERED: Gas = Gas::from_tgas(5);
+//gas for resolve_redeem_to_deposit to credit a payout the owner couldn't be registered for
+const GAS_FOR_RESOLVE_REDEEM_TO_DEPOSIT: Gas = Gas::from_tgas(5);
+//NEAR attached to the registration, the FT refunds what its storage doesn't need
+const FT_REGISTRATION_DEPOSIT: NearToken = NearToken::from_millinear(100);
+//extra gas a burn needs when the vault holds an FT rather than NEAR
+pub(crate) const GAS_FOR_FT_REDEEM: Gas = Gas::from_gas(
+    GAS_FOR_FT_REGISTRATION.as_gas() + GAS_FOR_WITHDRAW_REGISTERED.as_gas() + GAS_FOR_RESOLVE_REDEEM_TO_DEPOSIT.as_gas()
+);
+
+#[near_bindgen]
+impl Contract {
+    /// Withdraw the vault of burned `token_id` once `owner` is registered with the FT the vault
+    /// holds, so the vault's transfer to it can't bounce. If the registration failed, the vault
+    /// pays the contract instead and `resolve_redeem_to_deposit` credits the owner's payout to its
+    /// deposits, from where `withdraw_ft_deposit` sends it once the owner is registered.
+    #[private]
+    pub fn withdraw_registered(
+        &mut self,
+        token_id: TokenId,
+        owner: AccountId,
+        ft_contract: AccountId,
+        burn_fee_bps: U128,
+        beneficiary: AccountId,
+    ) -> Promise {
+        if matches!(env::promise_result(0), PromiseResult::Successful(_)) {
+            return vault_withdrawal(&token_id, &owner, burn_fee_bps.0, &beneficiary);
+        }
+        vault_withdrawal(&token_id, &env::current_account_id(), burn_fee_bps.0, &beneficiary).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_RESOLVE_REDEEM_TO_DEPOSIT)
+                .resolve_redeem_to_deposit(token_id, owner, ft_contract, burn_fee_bps)
+        )
+    }
+
+    /// Credit `owner` the payout of the vault of burned `token_id`, which the vault sent to the
+    /// contract, and pass on the amount the vault reported for `resolve_burn`. Fails if the
+    /// withdrawal did, as the withdrawal itself would have.
+    #[private]
+    pub fn resolve_redeem_to_deposit(
+        &mut self,
+        token_id: TokenId,
+        owner: AccountId,
+        ft_contract: AccountId,
+        burn_fee_bps: U128,
+    ) -> U128 {
+        let withdrawn = match env::promise_result(0) {
+            PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<U128>(&value).ok(),
+            PromiseResult::Failed => None,
+        }.unwrap_or_else(|| env::panic_str(&format!("The vault of token {} was not withdrawn", token_id)));
+        let (burn_fee, _) = split_burn_fee(withdrawn.0, burn_fee_bps.0);
+        let payout = withdrawn.0 - burn_fee;
+        self.internal_credit_ft_deposit(&owner, &ft_contract, payout);
+        env::log_str(&format!(
+            "Couldn't register {} with {}, the payout of {} for token {} was credited to its deposits",
+            owner, ft_contract, payout, token_id
+        ));
+        withdrawn
+    }
+}
+
+impl Contract {
+    /// Currency the vault of `token_id` was funded in, `None` for NEAR: the one its mint was paid
+    /// in, which is the base currency unless recorded otherwise.
+    pub(crate) fn vault_currency(&self, token_id: &TokenId) -> Option<AccountId> {
+        self.vault_currencies.get(token_id).unwrap_or_else(|| self.base_currency.clone())
+    }
+
+    /// Pay out the vault of burned `token_id` in `ft_contract`, the FT it holds: register `owner`
+    /// with it from the contract's funds, then withdraw the vault. The vault only reports
+    /// success once every transfer was delivered, so `resolve_burn` credits the holders after that.
+    pub(crate) fn internal_redeem(
+        &self,
+        token_id: &TokenId,
+        owner: &AccountId,
+        ft_contract: &AccountId,
+        burn_fee_bps: u128,
+        beneficiary: AccountId,
+    ) -> Promise {
+        Promise::new(ft_contract.clone())
+            .function_call(
+                "storage_deposit".to_string(),
+                json!({
+                    "account_id": owner.to_string(),
+                    "registration_only": true,
+                }).to_string().into_bytes().to_vec(),
+                FT_REGISTRATION_DEPOSIT,
+                GAS_FOR_FT_REGISTRATION,
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(Gas::from_gas(
+                        GAS_FOR_WITHDRAW_REGISTERED.as_gas()
+                            + GAS_FOR_VAULT_WITHDRAW.as_gas()
+                            + GAS_FOR_RESOLVE_REDEEM_TO_DEPOSIT.as_gas()
+                    ))
+                    .withdraw_registered(
+                        token_id.clone(),
+                        owner.clone(),
+                        ft_contract.clone(),
+                        U128(burn_fee_bps),
+                        beneficiary,
+                    )
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{account, contract};
+    use near_sdk::mock::MockAction;
+    use near_sdk::test_utils::{get_created_receipts, VMContextBuilder};
+    use near_sdk::{testing_env, test_vm_config, RuntimeFeesConfig};
+
+    /// Call back into the contract as itself, with `result` as the result of the promise awaited.
+    fn callback(result: PromiseResult) {
+        testing_env!(
+            VMContextBuilder::new()
+                .current_account_id(account("nft.near"))
+                .predecessor_account_id(account("nft.near"))
+                .build(),
+            test_vm_config(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+    }
+
+    #[test]
+    fn test_failed_registration_withdraws_the_vault_to_the_contract() {
+        let mut contract = contract(Some("ft.near"));
+        callback(PromiseResult::Failed);
+        let _ = contract.withdraw_registered(
+            "a1".to_string(), account("alice.near"), account("ft.near"), U128(1000), account("treasury.near"),
+        );
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, account("a1.nft.near"));
+        let MockAction::FunctionCallWeight { method_name, args, .. } = &receipts[0].actions[0] else {
+            panic!("The vault was not called");
+        };
+        assert_eq!(method_name, b"withdraw");
+        let args: near_sdk::serde_json::Value = near_sdk::serde_json::from_slice(args).unwrap();
+        assert_eq!(args["owner"], "nft.near");
+        assert_eq!(receipts[1].receiver_id, account("nft.near"));
+    }
+
+    #[test]
+    fn test_resolve_redeem_to_deposit_credits_the_payout() {
+        let mut contract = contract(Some("ft.near"));
+        callback(PromiseResult::Successful(b"\"1000\"".to_vec()));
+        let withdrawn = contract.resolve_redeem_to_deposit(
+            "a1".to_string(), account("alice.near"), account("ft.near"), U128(1000),
+        );
+        assert_eq!(withdrawn, U128(1000));
+        assert_eq!(contract.ft_deposit(&account("alice.near"), &account("ft.near")), 900);
+    }
+}
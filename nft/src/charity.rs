@@ -0,0 +1,89 @@
+use crate::*;
+
+//gas for recording the outcome of a donation
+const GAS_FOR_RESOLVE_DONATION: Gas = Gas::from_tgas(10);
+
+#[near_bindgen]
+impl Contract {
+    /// Donate `bps` of the owner's amount of every mint to `account`, or stop donating with
+    /// `None`. Can't be changed once frozen.
+    pub fn set_charity(&mut self, charity: Option<(AccountId, u16)>) {
+        self.assert_owner();
+        require!(!self.charity_frozen, "The charity split is frozen");
+        assert_valid_charity(&charity);
+        self.charity = charity;
+    }
+
+    /// Fix the charity split for good.
+    pub fn freeze_charity(&mut self) {
+        self.assert_owner();
+        self.charity_frozen = true;
+    }
+
+    /// Send donations that failed to reach the charity again. Anyone can call this.
+    pub fn retry_charity_donation(&mut self) -> Promise {
+        let (charity, _) = self.charity.clone()
+            .unwrap_or_else(|| env::panic_str("No charity is configured"));
+        let amount = self.charity_undelivered;
+        require!(amount > 0, "No donation is undelivered");
+        self.charity_undelivered = 0;
+        self.internal_donate(&self.base_currency, &charity, amount)
+    }
+
+    /// Count a donation of `amount` once delivered, or keep it for `retry_charity_donation`.
+    #[private]
+    pub fn resolve_charity_donation(&mut self, amount: U128) -> bool {
+        let delivered = matches!(env::promise_result(0), PromiseResult::Successful(_));
+        if delivered {
+            self.charity_total_donated = self.charity_total_donated.checked_add(amount.0).unwrap();
+        } else {
+            self.charity_undelivered = self.charity_undelivered.checked_add(amount.0).unwrap();
+        }
+        delivered
+    }
+
+    pub fn charity_config(&self) -> Option<(AccountId, u16)> {
+        self.charity.clone()
+    }
+
+    pub fn charity_frozen(&self) -> bool {
+        self.charity_frozen
+    }
+
+    /// Everything the charity has received, in the base currency.
+    pub fn charity_total_donated(&self) -> U128 {
+        U128(self.charity_total_donated)
+    }
+
+    pub fn charity_undelivered(&self) -> U128 {
+        U128(self.charity_undelivered)
+    }
+}
+
+impl Contract {
+    /// The charity's share of `owner_amount`, or 0 when no charity is configured.
+    pub(crate) fn charity_share(&self, owner_amount: u128) -> u128 {
+        self.charity.as_ref().map_or(0, |(_, bps)| owner_amount * *bps as u128 / 10_000)
+    }
+
+    /// Send `amount` of `currency` to `charity`. Donations in the base currency are recorded once
+    /// they arrive, the totals don't count other currencies.
+    pub(crate) fn internal_donate(&self, currency: &Option<AccountId>, charity: &AccountId, amount: u128) -> Promise {
+        let donation = self.internal_send_funds(currency, charity, amount);
+        if *currency != self.base_currency {
+            return donation;
+        }
+        donation.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_RESOLVE_DONATION)
+                .resolve_charity_donation(U128(amount))
+        )
+    }
+}
+
+/// Panic unless `charity` donates at most the whole owner's amount.
+pub(crate) fn assert_valid_charity(charity: &Option<(AccountId, u16)>) {
+    if let Some((_, bps)) = charity {
+        require!(*bps <= 10_000, "Charity share can't exceed 10000 bps");
+    }
+}
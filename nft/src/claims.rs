@@ -0,0 +1,136 @@
+use crate::*;
+
+//gas for restoring the ledgers of a claim leg that failed
+const GAS_FOR_RESOLVE_CLAIM: Gas = Gas::from_tgas(10);
+
+/// What a `claim_all` call sent. Rewards travel in the base currency, so they are part of the FT
+/// transfer when the collection is priced in an FT and of the NEAR transfer otherwise.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ClaimSummary {
+    pub account_id: AccountId,
+    pub rewards: U128,
+    pub storage_deposit: U128,
+    pub ft_deposit: U128,
+    //NEAR sent in one transfer
+    pub near_amount: U128,
+    //FT sent in one transfer of `ft_contract`
+    pub ft_amount: U128,
+    pub ft_contract: Option<AccountId>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Withdraw the caller's rewards, storage deposit and unused FT deposits in the base currency
+    /// in one call, with at most one NEAR and one FT transfer. A transfer that fails puts back only
+    /// the balances it carried. Deposits in other currencies are withdrawn with
+    /// `withdraw_ft_deposit`.
+    #[payable]
+    pub fn claim_all(&mut self) -> ClaimSummary {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        self.internal_update_reward_weight(&account_id);
+        let rewards = self.balances_by_owner.get(&account_id).unwrap_or(0);
+        let storage_deposit = self.storage_deposits.get(&account_id).unwrap_or(0);
+        let ft_contract = self.base_currency.clone();
+        let ft_deposit = ft_contract.as_ref().map_or(0, |ft_contract| self.ft_deposit(&account_id, ft_contract));
+        require!(rewards + storage_deposit + ft_deposit > 0, "Nothing to claim");
+
+        self.balances_by_owner.remove(&account_id);
+        self.storage_deposits.remove(&account_id);
+        if let Some(ft_contract) = &ft_contract {
+            self.internal_take_ft_deposit(&account_id, ft_contract);
+        }
+        self.reward_pool = self.reward_pool.saturating_sub(rewards);
+
+        let (near_rewards, ft_rewards) = if ft_contract.is_some() { (0, rewards) } else { (rewards, 0) };
+        let near_amount = near_rewards + storage_deposit;
+        let ft_amount = ft_rewards + ft_deposit;
+        if near_amount > 0 {
+            Promise::new(account_id.clone())
+                .transfer(NearToken::from_yoctonear(near_amount))
+                .then(self.resolve_claim_leg_ext(&account_id, near_rewards, storage_deposit, 0, None));
+        }
+        if let Some(ft_id) = ft_contract.clone().filter(|_| ft_amount > 0) {
+            Promise::new(ft_id.clone())
+                .function_call(
+                    "ft_transfer".to_string(),
+                    json!({
+                        "receiver_id": account_id.to_string(),
+                        "amount": ft_amount.to_string(),
+                    }).to_string().into_bytes().to_vec(),
+                    NearToken::from_yoctonear(1),
+                    Gas::from_tgas(20),
+                )
+                .then(self.resolve_claim_leg_ext(&account_id, ft_rewards, 0, ft_deposit, Some(ft_id)));
+        }
+
+        ClaimSummary {
+            account_id,
+            rewards: U128(rewards),
+            storage_deposit: U128(storage_deposit),
+            ft_deposit: U128(ft_deposit),
+            near_amount: U128(near_amount),
+            ft_amount: U128(ft_amount),
+            ft_contract,
+        }
+    }
+
+    /// Withdraw the caller's unused deposits of `ft_contract`, e.g. a currency other than the base
+    /// currency. A failed transfer puts the deposits back.
+    #[payable]
+    pub fn withdraw_ft_deposit(&mut self, ft_contract: AccountId) -> U128 {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let amount = self.internal_take_ft_deposit(&account_id, &ft_contract);
+        require!(amount > 0, "Nothing to withdraw");
+        self.internal_send_funds(&Some(ft_contract.clone()), &account_id, amount)
+            .then(self.resolve_claim_leg_ext(&account_id, 0, 0, amount, Some(ft_contract)));
+        U128(amount)
+    }
+
+    /// Credit back the balances one leg of `claim_all` carried if its transfer failed, with the
+    /// FT deposits in `ft_contract`.
+    #[private]
+    pub fn resolve_claim_leg(
+        &mut self,
+        account_id: AccountId,
+        rewards: U128,
+        storage_deposit: U128,
+        ft_deposit: U128,
+        ft_contract: Option<AccountId>,
+    ) -> bool {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return true;
+        }
+        for (ledger, amount) in [
+            (&mut self.balances_by_owner, rewards.0),
+            (&mut self.storage_deposits, storage_deposit.0),
+        ] {
+            if amount > 0 {
+                let balance = ledger.get(&account_id).unwrap_or(0);
+                ledger.insert(&account_id, &balance.checked_add(amount).unwrap());
+            }
+        }
+        if let Some(ft_contract) = ft_contract.filter(|_| ft_deposit.0 > 0) {
+            self.internal_credit_ft_deposit(&account_id, &ft_contract, ft_deposit.0);
+        }
+        self.reward_pool = self.reward_pool.checked_add(rewards.0).unwrap();
+        false
+    }
+}
+
+impl Contract {
+    fn resolve_claim_leg_ext(
+        &self,
+        account_id: &AccountId,
+        rewards: u128,
+        storage_deposit: u128,
+        ft_deposit: u128,
+        ft_contract: Option<AccountId>,
+    ) -> Promise {
+        Self::ext(env::current_account_id())
+            .with_static_gas(GAS_FOR_RESOLVE_CLAIM)
+            .resolve_claim_leg(account_id.clone(), U128(rewards), U128(storage_deposit), U128(ft_deposit), ft_contract)
+    }
+}
@@ -0,0 +1,70 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Fuse two tokens the caller owns into a new one. Both inputs go through the full burn flow,
+    /// so their vaults, less the burn fees, are paid out to the caller rather than carried over,
+    /// and a token with a fresh id is minted to the caller under the same minter and sale checks
+    /// as `nft_mint`. The attached deposit pays the `combine_fee` to the treasury, the new vault
+    /// deposit and the new token's storage; the mint price is not charged.
+    #[payable]
+    pub fn combine(&mut self, token_id_a: TokenId, token_id_b: TokenId, new_metadata: TokenMetadata) -> Token {
+        self.assert_mint_not_paused();
+        let owner = env::predecessor_account_id();
+        self.assert_minter(&owner);
+        self.assert_pass_verified(&owner);
+        self.assert_sale_open(&owner);
+        require!(token_id_a != token_id_b, "Can't combine a token with itself");
+        for token_id in [&token_id_a, &token_id_b] {
+            let token_owner = self.tokens.owner_by_id.get(token_id)
+                .unwrap_or_else(|| env::panic_str(&format!("Token {} not found", token_id)));
+            require!(token_owner == owner, "You don't own this NFT");
+            self.assert_no_approvals(token_id);
+        }
+
+        let token_id = self.next_free_token_id("combined");
+
+        self.internal_burn(&token_id_a, &owner, None, None);
+        self.internal_burn(&token_id_b, &owner, None, None);
+
+        let fee_and_vault = self.combine_fee.checked_add(self.vault_deposit()).unwrap();
+        let token = self.internal_mint(
+            token_id,
+            owner.clone(),
+            new_metadata,
+            owner.clone(),
+            fee_and_vault,
+            env::attached_deposit().as_yoctonear(),
+            MintOptions { price: Some(0), currency: self.base_currency.clone(), ..Default::default() }
+        );
+        if self.combine_fee > 0 {
+            Promise::new(self.treasury.clone()).transfer(NearToken::from_yoctonear(self.combine_fee));
+        }
+
+        Event::Combine {
+            owner_id: &owner,
+            burned_token_ids: [&token_id_a, &token_id_b],
+            token_id: &token.token_id
+        }.emit();
+        token
+    }
+
+    pub fn set_combine_fee(&mut self, combine_fee: U128) {
+        self.assert_owner();
+        self.combine_fee = combine_fee.0;
+    }
+}
+
+impl Contract {
+    /// The first `{prefix}-{n}` id, counting from the current mint index, that no live token uses.
+    pub(crate) fn next_free_token_id(&self, prefix: &str) -> TokenId {
+        let mut n = self.index.checked_add(1).unwrap();
+        loop {
+            let token_id = format!("{}-{}", prefix, n);
+            if self.tokens.owner_by_id.get(&token_id).is_none() {
+                return token_id;
+            }
+            n += 1;
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::*;
+
+//storage of a minted token besides its metadata: the owner, enumeration and holder records with
+//their per-record overhead, for token and account ids of up to 64 bytes
+pub const TOKEN_STORAGE_OVERHEAD: u64 = 1_500;
+
+/// Everything a mint costs, itemized. NEAR items are in yoctoNEAR and the mint price is in the
+/// currency it is paid with.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CostBreakdown {
+    pub mint_price: U128,
+    pub mint_currency: Option<AccountId>,
+    //the vault contract's code, stored on its own account
+    pub vault_code_storage: U128,
+    //NEAR sent along for the vault's state
+    pub vault_state_buffer: U128,
+    //estimated storage of the token records for the given metadata
+    pub token_storage: U128,
+    //NEAR to attach to `nft_mint`, which includes the mint price unless it is paid in an FT
+    pub near_total: U128,
+    //FT to deposit before minting
+    pub ft_total: U128,
+}
+
+/// What `nft_mint` requires for the next token, from the same helper it checks the deposit with.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintCost {
+    //0 when the mint deploys no vault
+    pub vault_deposit: U128,
+    pub mint_price: U128,
+    pub mint_currency: Option<AccountId>,
+    //the vault deposit, the token's estimated storage and the mint price unless it is paid in an FT
+    pub total_attached_near_required: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// What minting the next token with `token_metadata` and paying with `pay_with` costs.
+    pub fn mint_cost(&self, token_metadata: Option<TokenMetadata>, pay_with: Option<AccountId>) -> MintCost {
+        let token_metadata = self.minted_metadata(token_metadata.unwrap_or_default());
+        let mint_price = self.mint_price_in(&pay_with, self.mint_price_at(self.index));
+        self.internal_mint_cost(pay_with, mint_price, estimate_token_storage(Some(&token_metadata)))
+    }
+
+    /// Itemize what minting a token with `token_metadata` and paying with `pay_with` costs.
+    /// Attaching `near_total` (after depositing `ft_total` of `pay_with`) covers the mint of a
+    /// token without attributes.
+    pub fn mint_cost_breakdown(&self, token_metadata: Option<TokenMetadata>, pay_with: Option<AccountId>) -> CostBreakdown {
+        let token_storage = estimate_token_storage(token_metadata.as_ref());
+        let mint_price = self.mint_price_in(&pay_with, self.mint_price_at(self.index));
+        let ft_total = if pay_with.is_some() { mint_price } else { 0 };
+        let cost = self.internal_mint_cost(pay_with, mint_price, token_storage);
+        let (vault_code_storage, vault_state_buffer) = if cost.vault_deposit.0 > 0 {
+            (self.near_per_storage * VAULT_CODE.len() as u128, self.vault_storage)
+        } else {
+            (0, 0)
+        };
+        CostBreakdown {
+            mint_price: U128(mint_price),
+            mint_currency: cost.mint_currency,
+            vault_code_storage: U128(vault_code_storage),
+            vault_state_buffer: U128(vault_state_buffer),
+            token_storage: U128(token_storage),
+            near_total: cost.total_attached_near_required,
+            ft_total: U128(ft_total),
+        }
+    }
+}
+
+impl Contract {
+    /// What a mint for `price` in `pay_with` requires, given the token's estimated storage. Free
+    /// mints and mints while vaults are disabled need no vault deposit.
+    pub(crate) fn internal_mint_cost(&self, pay_with: Option<AccountId>, price: u128, storage_estimate: u128) -> MintCost {
+        let vault_deposit = if self.mints_vault(price) { self.vault_deposit() } else { 0 };
+        let near_price = if pay_with.is_some() { 0 } else { price };
+        MintCost {
+            vault_deposit: U128(vault_deposit),
+            mint_price: U128(price),
+            mint_currency: pay_with,
+            total_attached_near_required: U128(near_price + vault_deposit + storage_estimate),
+        }
+    }
+}
+
+/// Cost in yoctoNEAR of the storage a token with `token_metadata` is estimated to use. `nft_mint`
+/// requires this up front, so the breakdown and the mint always agree.
+pub(crate) fn estimate_token_storage(token_metadata: Option<&TokenMetadata>) -> u128 {
+    let metadata_len = token_metadata
+        .map_or(0, |metadata| near_sdk::borsh::to_vec(metadata).unwrap().len() as u64);
+    env::storage_byte_cost().as_yoctonear() * (TOKEN_STORAGE_OVERHEAD + metadata_len) as u128
+}
@@ -0,0 +1,125 @@
+use crate::*;
+use crate::legacy::legacy_ft_deposits;
+
+#[near_bindgen]
+impl Contract {
+    /// Accept `ft_contract` for mints at `price`, or change its price. The base currency is priced
+    /// by `mint_price` and can't be added.
+    #[payable]
+    pub fn add_mint_currency(&mut self, ft_contract: AccountId, price: U128) {
+        self.assert_owner();
+        require!(
+            self.base_currency.as_ref() != Some(&ft_contract),
+            "The base currency is priced by mint_price"
+        );
+        let initial_storage_usage = env::storage_usage();
+        self.mint_currencies.insert(&ft_contract, &price.0);
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    /// Stop accepting `ft_contract` for mints. Deposits already made in it stay withdrawable.
+    pub fn remove_mint_currency(&mut self, ft_contract: AccountId) {
+        self.assert_owner();
+        require!(self.mint_currencies.remove(&ft_contract).is_some(), "Not a mint currency");
+    }
+
+    /// Accept NEAR for mints at `near_mint_price` in a collection priced in an FT, or stop
+    /// accepting it with `None`.
+    pub fn set_near_mint_price(&mut self, near_mint_price: Option<U128>) {
+        self.assert_owner();
+        require!(self.base_currency.is_some(), "NEAR is the base currency, priced by mint_price");
+        self.near_mint_price = near_mint_price.map(|price| price.0);
+    }
+
+    /// The FT contracts accepted for mints besides the base currency, with their prices.
+    pub fn mint_currencies(&self) -> Vec<(AccountId, U128)> {
+        self.mint_currencies.iter().map(|(ft_contract, price)| (ft_contract, U128(price))).collect()
+    }
+
+    pub fn near_mint_price(&self) -> Option<U128> {
+        self.near_mint_price.map(U128)
+    }
+
+    /// The currency the collection was created with, `None` for NEAR. `mint_price` and its tiers,
+    /// holder rewards, upgrades, vesting and vouchers are all in it.
+    pub fn base_currency(&self) -> Option<AccountId> {
+        self.base_currency.clone()
+    }
+}
+
+impl Contract {
+    /// Price of a mint paid with `pay_with`, `None` for NEAR, when it costs `base_price` in the
+    /// base currency. Panics unless the currency is accepted.
+    pub(crate) fn mint_price_in(&self, pay_with: &Option<AccountId>, base_price: u128) -> u128 {
+        if *pay_with == self.base_currency {
+            return base_price;
+        }
+        match pay_with {
+            Some(ft_contract) => self.mint_currencies.get(ft_contract)
+                .unwrap_or_else(|| env::panic_str(&format!("{} is not accepted for mints", ft_contract))),
+            None => self.near_mint_price
+                .unwrap_or_else(|| env::panic_str("NEAR is not accepted for mints")),
+        }
+    }
+
+    /// Take `price` from `payer` in `pay_with`: an FT from its deposits, or NEAR from `deposit`,
+    /// which must also cover `vault_deposits` and `storage_estimate`. Returns the NEAR the mint
+    /// consumes besides storage, the vault deposits plus the price when paid in NEAR.
+    pub(crate) fn internal_take_mint_payment(
+        &mut self,
+        payer: &AccountId,
+        pay_with: &Option<AccountId>,
+        price: u128,
+        deposit: u128,
+        vault_deposits: u128,
+        storage_estimate: u128,
+    ) -> u128 {
+        let minimum_needed = vault_deposits + storage_estimate;
+        if let Some(ft_contract) = pay_with {
+            let amount = self.ft_deposit(payer, ft_contract);
+            require!(deposit >= minimum_needed && amount >= price, "Insufficient price to mint");
+            self.internal_set_ft_deposit(payer, ft_contract, amount - price);
+            vault_deposits
+        } else {
+            require!(deposit >= price + minimum_needed, "Insufficient price to mint");
+            price + vault_deposits
+        }
+    }
+
+    /// FTs of `ft_contract` that `account_id` has deposited, with what it deposited in the base
+    /// currency before deposits were kept per FT, see `legacy_ft_deposits`.
+    pub(crate) fn ft_deposit(&self, account_id: &AccountId, ft_contract: &AccountId) -> u128 {
+        let deposit = self.ft_deposits.get(&(account_id.clone(), ft_contract.clone())).unwrap_or(0);
+        if self.base_currency.as_ref() == Some(ft_contract) {
+            deposit + legacy_ft_deposits().get(account_id).unwrap_or(0)
+        } else {
+            deposit
+        }
+    }
+
+    /// Set the deposits of `account_id` in `ft_contract` to `amount`, any legacy deposit included.
+    pub(crate) fn internal_set_ft_deposit(&mut self, account_id: &AccountId, ft_contract: &AccountId, amount: u128) {
+        self.internal_remove_legacy_ft_deposit(account_id, ft_contract);
+        self.ft_deposits.insert(&(account_id.clone(), ft_contract.clone()), &amount);
+    }
+
+    /// Take all the deposits of `account_id` in `ft_contract` and return how much they were.
+    pub(crate) fn internal_take_ft_deposit(&mut self, account_id: &AccountId, ft_contract: &AccountId) -> u128 {
+        let amount = self.ft_deposit(account_id, ft_contract);
+        self.internal_remove_legacy_ft_deposit(account_id, ft_contract);
+        self.ft_deposits.remove(&(account_id.clone(), ft_contract.clone()));
+        amount
+    }
+
+    /// Add `amount` of `ft_contract` to the deposits of `account_id`.
+    pub(crate) fn internal_credit_ft_deposit(&mut self, account_id: &AccountId, ft_contract: &AccountId, amount: u128) {
+        let balance = self.ft_deposit(account_id, ft_contract);
+        self.internal_set_ft_deposit(account_id, ft_contract, balance.checked_add(amount).unwrap());
+    }
+
+    fn internal_remove_legacy_ft_deposit(&mut self, account_id: &AccountId, ft_contract: &AccountId) {
+        if self.base_currency.as_ref() == Some(ft_contract) {
+            legacy_ft_deposits().remove(account_id);
+        }
+    }
+}
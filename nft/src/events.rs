@@ -0,0 +1,131 @@
+use crate::*;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+pub enum Event<'a> {
+    Combine {
+        owner_id: &'a AccountId,
+        burned_token_ids: [&'a TokenId; 2],
+        token_id: &'a TokenId,
+    },
+    NftMetadataUpdate {
+        token_ids: Vec<&'a TokenId>,
+    },
+    BridgeLock {
+        owner_id: &'a AccountId,
+        token_id: &'a TokenId,
+        dest_chain_address: &'a String,
+        nonce: U64,
+    },
+    HolderChanged {
+        account_id: &'a AccountId,
+        direction: &'a str,
+        token_count: U64,
+        total_holders: U64,
+    },
+    BridgeUnlock {
+        token_id: &'a TokenId,
+        nonce: U64,
+    },
+    TokenKvChanged {
+        token_id: &'a TokenId,
+        key: &'a String,
+        value: Option<&'a String>,
+    },
+    Migrated {
+        old_account_id: &'a AccountId,
+        new_account_id: &'a AccountId,
+        token_count: U64,
+        rewards: U128,
+        ft_deposits: Vec<(AccountId, U128)>,
+        storage_deposits: U128,
+    },
+    Referral {
+        referrer: &'a AccountId,
+        minter: &'a AccountId,
+        token_id: &'a TokenId,
+        amount: U128,
+    },
+    MintSplit {
+        token_id: &'a TokenId,
+        mint_fee: U128,
+        vault_amount: U128,
+        owner_amount: U128,
+    },
+    MintPaid {
+        token_id: &'a TokenId,
+        owner_id: &'a AccountId,
+        minter: &'a AccountId,
+        price: U128,
+        currency: &'a str,
+        vault_amount: U128,
+        owner_amount: U128,
+    },
+    BurnReceipt {
+        token_id: &'a TokenId,
+        owner_id: &'a AccountId,
+        vault_account: &'a AccountId,
+        estimated_payout: U128,
+        burn_fee_applied: U128,
+        holders_rewarded: U64,
+    },
+    RewardModeChanged {
+        reward_mode: RewardMode,
+    },
+    BurnSettled {
+        token_id: &'a TokenId,
+        owner_id: &'a AccountId,
+        vault_amount: U128,
+        //what the vault beneficiary keeps of the burn fee
+        fee_retained: U128,
+        holder_pool: U128,
+        //share of a holder without staked tokens
+        per_holder: U128,
+        //the holder pool's rounding dust, credited to the treasury, or the whole pool of a vault
+        //funded in another currency than the base one, sent to it
+        remainder: U128,
+    },
+    RewardWeightsMigrated {
+        processor_id: &'a AccountId,
+        //the holder indexes visited, counted from the start of `holders`; those below
+        //`from_index` are left
+        from_index: U64,
+        to_index: U64,
+        bounty: U128,
+    },
+    Upgrade {
+        owner_id: &'a AccountId,
+        burned_token_ids: Vec<&'a TokenId>,
+        token_id: &'a TokenId,
+        tier: u32,
+    },
+}
+
+impl Event<'_> {
+    pub fn emit(&self) {
+        emit_event(&self);
+    }
+}
+
+const EVENT_STANDARD: &str = "marketplace_nft";
+const EVENT_STANDARD_VERSION: &str = "1.0.0";
+
+// Emit event that follows NEP-297 standard: https://nomicon.io/Standards/EventsFormat
+// Arguments
+// * `standard`: name of standard, e.g. nep171
+// * `version`: e.g. 1.0.0
+// * `event`: type of the event, e.g. nft_mint
+// * `data`: associate event data. Strictly typed for each set {standard, version, event} inside corresponding NEP
+pub(crate) fn emit_event<T: ?Sized + Serialize>(data: &T) {
+    let result = json!(data);
+    let event_json = json!({
+        "standard": EVENT_STANDARD,
+        "version": EVENT_STANDARD_VERSION,
+        "event": result["event"],
+        "data": [result["data"]]
+    })
+    .to_string();
+    env::log_str(&format!("EVENT_JSON:{}", event_json));
+}
@@ -0,0 +1,23 @@
+use crate::*;
+
+/// Per-token data the contract keeps beside the NEP-177 metadata. Removed when the token burns.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct TokenExtra {
+    pub attributes: Vec<TraitAttribute>,
+    pub level: u32,
+    pub series_id: u32,
+    //set at mint and never shortened
+    pub transfer_locked_until: Option<u64>,
+    //mutable values written by the owner and its writers, see `token_kv`
+    pub kv: HashMap<String, String>,
+    //minted without a vault, for free or by `owner_mint`, so burning it withdraws nothing
+    pub without_vault: bool,
+}
+
+impl Contract {
+    /// The extra record of `token_id`, or an empty one if none was stored.
+    pub(crate) fn token_extra(&self, token_id: &TokenId) -> TokenExtra {
+        self.token_extras.get(token_id).unwrap_or_default()
+    }
+}
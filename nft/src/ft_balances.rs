@@ -1,26 +1,25 @@
 use crate::*;
-
-trait FungibleTokenReceiver {
-    fn ft_on_transfer(
-        &mut self,
-        sender_id: AccountId,
-        amount: U128,
-    ) -> U128;
-}
+use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
 
 #[near_bindgen]
 impl FungibleTokenReceiver for Contract {
+    /// Credit the transferred FTs to the sender's deposits, or pay a mint with them when `msg` is
+    /// `{"action":"mint", ...}` with the arguments of `nft_mint`. The NEAR side of such a mint
+    /// comes from the sender's storage balance, and the FTs it doesn't use are returned to the FT
+    /// contract for refund.
     fn ft_on_transfer(
         &mut self,
         sender_id: AccountId,
         amount: U128,
-    ) -> U128 {
+        msg: String,
+    ) -> PromiseOrValue<U128> {
         // get the contract ID which is the predecessor
         let ft_contract_id = env::predecessor_account_id();
-        if let Some(mint_currency) = self.mint_currency.clone() {
-            // Ensure only the specified FT can be used
+        if self.base_currency.is_some() || !self.mint_currencies.is_empty() {
+            // Ensure only the base currency or another mint currency can be used
             require!(
-                ft_contract_id == mint_currency,
+                self.base_currency.as_ref() == Some(&ft_contract_id)
+                    || self.mint_currencies.get(&ft_contract_id).is_some(),
                 "FT contract ID does not match"
             );
 
@@ -41,13 +40,35 @@ impl FungibleTokenReceiver for Contract {
                 "owner_id should be signer_id"
             );
 
+            if !msg.is_empty() {
+                let message: near_sdk::serde_json::Value = near_sdk::serde_json::from_str(&msg)
+                    .unwrap_or_else(|_| env::panic_str("Invalid ft_transfer_call message"));
+                require!(message["action"] == "mint", format!("Unknown action {}", message["action"]));
+                let args: MintArgs = near_sdk::serde_json::from_value(message)
+                    .unwrap_or_else(|error| env::panic_str(&format!("Invalid mint arguments: {}", error)));
+
+                // Only the transferred amount pays, whatever the sender had deposited before
+                let previous_deposit = self.ft_deposit(&signer_id, &ft_contract_id);
+                self.internal_set_ft_deposit(&signer_id, &ft_contract_id, amount.0);
+                let near_available = self.storage_deposits.remove(&signer_id).unwrap_or(0);
+                self.internal_nft_mint(
+                    signer_id.clone(),
+                    args,
+                    Some(ft_contract_id.clone()),
+                    near_available,
+                    Some(signer_id.clone()),
+                    None,
+                );
+                let unused = self.ft_deposit(&signer_id, &ft_contract_id);
+                self.internal_set_ft_deposit(&signer_id, &ft_contract_id, previous_deposit);
+                return PromiseOrValue::Value(U128(unused));
+            }
+
             // Add the amount to the user's current balance
-            let mut cur_bal = self.ft_deposits.get(&signer_id).unwrap_or(0);
-            cur_bal += amount.0;
-            self.ft_deposits.insert(&signer_id, &cur_bal);
+            self.internal_credit_ft_deposit(&signer_id, &ft_contract_id, amount.0);
 
         }
 
-        U128(0)
+        PromiseOrValue::Value(U128(0))
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,49 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Add `ids` to the pool `nft_mint` draws token ids from, in as many calls as the pool needs.
+    /// While the pool has ids left, minters can't pick their own. The pool can't outgrow what is
+    /// left of the total supply, and the caller pays for the storage it uses.
+    #[payable]
+    pub fn load_id_pool(&mut self, ids: Vec<TokenId>) {
+        self.assert_owner();
+        self.assert_supply_left(self.id_pool.len() as u128 + ids.len() as u128);
+        let initial_storage_usage = env::storage_usage();
+        for token_id in ids.iter() {
+            assert_valid_token_id(token_id);
+            assert_not_series_token_id(token_id);
+            self.assert_token_id_free(token_id);
+            self.id_pool.push(token_id);
+        }
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    /// How many ids are left in the pool.
+    pub fn remaining_pool(&self) -> U64 {
+        U64(self.id_pool.len())
+    }
+}
+
+impl Contract {
+    /// Id of the next token minted outside a series: drawn from the pool while it has ids left,
+    /// otherwise `token_id` or the next free one.
+    pub(crate) fn internal_assign_token_id(&mut self, token_id: Option<TokenId>) -> TokenId {
+        if self.id_pool.is_empty() {
+            return token_id.unwrap_or_else(|| self.internal_next_token_id());
+        }
+        require!(token_id.is_none(), "Token ids are drawn from the pool");
+        let index = pool_draw(self.id_pool.len());
+        self.id_pool.swap_remove(index)
+    }
+}
+
+/// Pseudo-random index below `len`, from the block's random seed, its height and the caller. A
+/// validator could bias it, which is fine for spreading rare ids but not for anything valuable.
+fn pool_draw(len: u64) -> u64 {
+    let mut seed = env::random_seed();
+    seed.extend_from_slice(&env::block_height().to_le_bytes());
+    seed.extend_from_slice(env::predecessor_account_id().as_bytes());
+    let hash = env::sha256(&seed);
+    u64::from_le_bytes(hash[..8].try_into().unwrap()) % len
+}
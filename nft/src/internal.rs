@@ -0,0 +1,1087 @@
+use crate::*;
+use near_contract_standards::non_fungible_token::events::NftTransfer;
+
+/// Optional parts of a mint that only some mint paths use.
+#[derive(Default)]
+pub(crate) struct MintOptions {
+    //code of the registered affiliate credited with `affiliate_bps` of the owner's share
+    pub affiliate_code: Option<String>,
+    //typed traits stored in the token's extra record, validated against the trait schema
+    pub attributes: Option<Vec<TraitAttribute>>,
+    //overrides `mint_price`, e.g. with the tier price or with `Some(0)` for a combine
+    pub price: Option<u128>,
+    //series the token belongs to, 0 for the collection's own configuration
+    pub series_id: u32,
+    //timestamp before which the token can't move, only set by the collection owner
+    pub transfer_locked_until: Option<u64>,
+    //skip deploying the token's vault, only for the collection owner's allocation
+    pub without_vault: bool,
+    //account paid `referral_bps` of the owner's share for bringing in the minter
+    pub referrer: Option<AccountId>,
+    //FT the price is paid in and the vault holds, None for NEAR
+    pub currency: Option<AccountId>,
+    //royalties in basis points paid on the token's sales instead of the contract-wide royalty
+    pub royalties: Option<HashMap<AccountId, u32>>,
+    //account whose storage balance paid the NEAR and gets the change back, instead of the caller
+    pub storage_payer: Option<AccountId>,
+    //percent of the price locked in the vault instead of the series' split
+    pub split_percent: Option<u128>,
+    //NEAR the vault account is created with instead of `vault_deposit`, e.g. a voucher's escrow
+    pub vault_deposit: Option<u128>,
+}
+
+/// What a burn destroyed and what it is expected to pay, assembled before the token is removed.
+/// The vault pays out asynchronously, so the amounts are estimates from the same formula that
+/// sets the holders' share.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BurnReceipt {
+    pub token: Token,
+    //derived from the token id, never deployed for a token minted without a vault
+    pub vault_account: AccountId,
+    //what the vault pays the owner, 0 without a vault
+    pub estimated_payout: U128,
+    //what the burn fee keeps from the vault, half of it for the holders and half for the treasury
+    pub burn_fee_applied: U128,
+    pub holders_rewarded: u64,
+}
+
+/// A burned token's vault withdrawal, settled by `resolve_burn` once it resolved.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BurnedVault {
+    pub token_id: TokenId,
+    //what the vault is expected to hold, for vaults that don't report what they withdrew
+    pub vault_amount: U128,
+    pub burn_fee_bps: u32,
+    //the currency the vault was funded in, `None` for NEAR
+    pub currency: Option<AccountId>,
+}
+
+/// Arguments of a public mint, from `nft_mint` or the message of an `ft_transfer_call`.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintArgs {
+    pub token_id: Option<TokenId>,
+    pub token_owner_id: AccountId,
+    pub token_metadata: TokenMetadata,
+    pub affiliate_code: Option<String>,
+    pub attributes: Option<Vec<TraitAttribute>>,
+    pub series_id: Option<u32>,
+    pub transfer_locked_until: Option<U64>,
+    pub allowlist_proof: Option<Base64VecU8>,
+    pub merkle_proof: Option<Vec<[u8; 32]>>,
+    pub referrer: Option<AccountId>,
+    pub perpetual_royalties: Option<HashMap<AccountId, u32>>,
+    pub split_percent_override: Option<U128>,
+}
+
+/// How the price of a minted token is paid out once its vault exists, in `resolve_create`.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintProceeds {
+    pub price: u128,
+    pub owner_amount: u128,
+    pub vault_amount: u128,
+    pub charity_amount: u128,
+    //protocol fee sent to the treasury
+    pub mint_fee: u128,
+    //shares carved out of the owner's amount and paid alongside it
+    pub payouts: Vec<(AccountId, U128)>,
+    //who paid for the mint, and the NEAR besides storage and FTs it paid, refunded if the vault
+    //can't deploy
+    pub minter: AccountId,
+    pub near_paid: u128,
+    pub ft_paid: u128,
+    //FT the price was paid in, None for NEAR
+    pub currency: Option<AccountId>,
+    pub without_vault: bool,
+    //NEAR the vault account is created with, part of `near_paid`
+    pub vault_deposit: u128,
+}
+
+impl MintProceeds {
+    /// Part of the price locked in the vault, 0 for a token without one.
+    pub fn vault_share(&self) -> u128 {
+        if self.without_vault { 0 } else { self.vault_amount }
+    }
+
+    /// Part of the price paid to the collection owner, including the vault's amount when the
+    /// token has no vault.
+    pub fn owner_share(&self) -> u128 {
+        self.owner_amount + self.vault_amount - self.vault_share()
+    }
+
+    /// `"near"`, or the account of the FT the price was paid in.
+    pub fn currency_name(&self) -> &str {
+        self.currency.as_ref().map_or("near", |currency| currency.as_str())
+    }
+}
+
+//gas for a vault's init call, and for resolve_create itself besides the calls it makes
+const GAS_FOR_VAULT_INIT: Gas = Gas::from_tgas(20);
+const GAS_FOR_RESOLVE_CREATE_BASE: Gas = Gas::from_tgas(15);
+//overhead of scheduling one promise, on top of the gas it is given
+const GAS_PER_PROMISE: Gas = Gas::from_tgas(5);
+//gas the mint call spends on a token's own state and on scheduling its vault
+const GAS_FOR_MINT_LOCAL: Gas = Gas::from_tgas(25);
+//gas for the vault deposit in resolve_create: deposit_near, or storage_deposit and ft_transfer_call
+const GAS_FOR_NEAR_VAULT_DEPOSIT: Gas = Gas::from_tgas(20);
+const GAS_FOR_FT_VAULT_DEPOSIT: Gas = Gas::from_tgas(70);
+//gas for a burned token's vault to pay out its owner and the burn fee
+pub(crate) const GAS_FOR_VAULT_WITHDRAW: Gas = Gas::from_tgas(100);
+//gas for resolve_burn to credit the holders once the withdrawals are known
+const GAS_FOR_RESOLVE_BURN: Gas = Gas::from_tgas(20);
+//gas of the cheapest mint, paid in NEAR without payouts or charity, see `gas_per_mint`
+pub(crate) const GAS_FOR_NEAR_MINT: Gas = Gas::from_gas(
+    GAS_FOR_MINT_LOCAL.as_gas()
+        + GAS_FOR_VAULT_INIT.as_gas()
+        + GAS_FOR_RESOLVE_CREATE_BASE.as_gas()
+        + GAS_FOR_NEAR_VAULT_DEPOSIT.as_gas()
+        + 3 * GAS_PER_PROMISE.as_gas()
+);
+
+impl Contract {
+    /// Mint `token_id` to `token_owner_id`, paid for by `holder`, and deploy its vault. A token minted
+    /// without a vault has its proceeds paid out right away instead.
+    ///
+    /// `near_required` is the NEAR the mint consumes besides token storage (the vault deposit, plus
+    /// the price when minting for NEAR) and `near_available` what the caller provided for it. Every
+    /// local state change and the storage accounting happen before any promise is created, so a
+    /// panic here rolls the whole call back and the deposit is returned in full. Whatever
+    /// `near_available` has left after the mint and its measured storage goes back to the caller.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn internal_mint(
+        &mut self,
+        token_id: TokenId,
+        token_owner_id: AccountId,
+        token_metadata: TokenMetadata,
+        holder: AccountId,
+        near_required: u128,
+        near_available: u128,
+        options: MintOptions
+    ) -> Token {
+        self.internal_mint_with_vault(token_id, token_owner_id, token_metadata, holder, near_required, near_available, options).0
+    }
+
+    /// `internal_mint`, also returning the promise that deploys the token's vault and ends with
+    /// `resolve_create`, or `None` for a token without a vault.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn internal_mint_with_vault(
+        &mut self,
+        token_id: TokenId,
+        token_owner_id: AccountId,
+        token_metadata: TokenMetadata,
+        holder: AccountId,
+        near_required: u128,
+        near_available: u128,
+        options: MintOptions
+    ) -> (Token, Option<Promise>) {
+        let without_vault = options.without_vault;
+        let storage_payer = options.storage_payer.clone();
+        let (token, proceeds, storage_cost) =
+            self.internal_record_mint(token_id, token_owner_id, token_metadata, holder, options);
+        require!(
+            near_available >= near_required.checked_add(storage_cost).unwrap(),
+            format!(
+                "Must attach {} yoctoNEAR more to cover token storage",
+                near_required + storage_cost - near_available
+            )
+        );
+        let memo = self.mint_memo(&[&proceeds]);
+        NftMint { owner_id: &token.owner_id, token_ids: &[&token.token_id], memo: Some(&memo) }.emit();
+        emit_mint_paid(&token, &proceeds);
+
+        // Only now, from a known-good state, schedule the vault deployment
+        let vault = if without_vault {
+            self.internal_pay_vaultless_proceeds(proceeds);
+            None
+        } else {
+            Some(self.internal_deploy_vault(&token.token_id, proceeds))
+        };
+
+        let refund = near_available - near_required - storage_cost;
+        if let Some(storage_payer) = storage_payer {
+            if refund > 0 {
+                let balance = self.storage_deposits.get(&storage_payer).unwrap_or(0);
+                self.storage_deposits.insert(&storage_payer, &(balance + refund));
+            }
+        } else if refund > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(NearToken::from_yoctonear(refund));
+        }
+        (token, vault)
+    }
+
+    /// The body of `nft_mint` and `nft_mint_eager`: mint `args` for the caller, paying in
+    /// `pay_with` with the attached deposit as the NEAR available. Unless `eager`, a token with a
+    /// vault is only returned once `resolve_mint` saw the vault funded.
+    pub(crate) fn internal_mint_call(&mut self, args: MintArgs, pay_with: Option<AccountId>, eager: bool) -> PromiseOrValue<Token> {
+        let deposit = env::attached_deposit().as_yoctonear();
+        if self.usd_pricing.is_some() && pay_with.is_none() && args.series_id.unwrap_or(0) == 0 {
+            return PromiseOrValue::Promise(self.internal_usd_mint(args, deposit));
+        }
+        match self.internal_nft_mint(env::predecessor_account_id(), args, pay_with, deposit, None, None) {
+            (token, Some(vault)) if !eager => PromiseOrValue::Promise(
+                vault.then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_RESOLVE_MINT)
+                        .resolve_mint(token)
+                )
+            ),
+            (token, _) => PromiseOrValue::Value(token),
+        }
+    }
+
+    /// Mint for `minter` paying in `pay_with`, with `deposit` as the NEAR available: the checks,
+    /// pricing and payment of `nft_mint`. With a `storage_payer`, the NEAR left over goes back to
+    /// its storage balance instead of the caller. A `quoted_price` in NEAR replaces the configured
+    /// price, e.g. one converted from USD. Returns the token and the promise deploying its vault,
+    /// see `internal_mint_with_vault`.
+    pub(crate) fn internal_nft_mint(
+        &mut self,
+        minter: AccountId,
+        args: MintArgs,
+        pay_with: Option<AccountId>,
+        deposit: u128,
+        storage_payer: Option<AccountId>,
+        quoted_price: Option<u128>,
+    ) -> (Token, Option<Promise>) {
+        self.assert_mint_not_paused();
+        if let Some(token_id) = &args.token_id {
+            assert_valid_token_id(token_id);
+        }
+        self.assert_minter(&minter);
+        self.internal_use_mint_cooldown(&minter);
+        self.assert_pass_verified(&minter);
+        self.assert_sale_open(&minter);
+        self.internal_use_allowlist_proof(&minter, args.allowlist_proof);
+        self.assert_in_allowlist_root(&minter, args.merkle_proof);
+        // The receiver's limit is charged, so paying for someone else's mint doesn't use the payer's
+        self.internal_count_wallet_mints(&args.token_owner_id, 1);
+        if args.transfer_locked_until.is_some() {
+            require!(minter == self.tokens.owner_id, "Only the collection owner can mint locked tokens");
+        }
+
+        let series_id = args.series_id.unwrap_or(0);
+        let token_id = if series_id == 0 {
+            self.internal_assign_token_id(args.token_id)
+        } else {
+            require!(args.token_id.is_none(), "Series token ids are assigned by the contract");
+            self.internal_next_series_token(series_id)
+        };
+        self.assert_token_id_free(&token_id);
+        let base_price = if series_id == 0 {
+            self.assert_supply_left(1);
+            let tier_price = self.internal_use_tier(&minter);
+            tier_price.unwrap_or_else(|| self.mint_price_at(self.index))
+        } else {
+            self.series_terms(series_id).mint_price
+        };
+        let mint_price = quoted_price.unwrap_or_else(|| self.mint_price_in(&pay_with, base_price));
+
+        let token_metadata = self.minted_metadata(args.token_metadata);
+        // The token's storage is measured during the mint, the estimate only gates the deposit
+        let storage_estimate = estimate_token_storage(Some(&token_metadata));
+        let cost = self.internal_mint_cost(pay_with.clone(), mint_price, storage_estimate);
+        let without_vault = !self.mints_vault(mint_price);
+        let near_price =
+            self.internal_take_mint_payment(&minter, &pay_with, mint_price, deposit, cost.vault_deposit.0, storage_estimate);
+
+        self.internal_mint_with_vault(
+            token_id,
+            args.token_owner_id,
+            token_metadata,
+            minter,
+            near_price,
+            deposit,
+            MintOptions {
+                affiliate_code: args.affiliate_code,
+                attributes: args.attributes,
+                series_id,
+                transfer_locked_until: args.transfer_locked_until.map(|locked_until| locked_until.0),
+                price: Some(mint_price),
+                referrer: args.referrer,
+                currency: pay_with,
+                without_vault,
+                royalties: args.perpetual_royalties,
+                storage_payer,
+                split_percent: args.split_percent_override.map(|split_percent| split_percent.0),
+                vault_deposit: None,
+            }
+        )
+    }
+
+    /// Record the mint of `token_id` in every token map without checking payment or creating any
+    /// promise. Returns the token, how its price is paid out and the cost of the storage it added.
+    pub(crate) fn internal_record_mint(
+        &mut self,
+        token_id: TokenId,
+        token_owner_id: AccountId,
+        token_metadata: TokenMetadata,
+        holder: AccountId,
+        options: MintOptions
+    ) -> (Token, MintProceeds, u128) {
+        assert_valid_token_id(&token_id);
+        // Before anything is counted or paid out, so a duplicate or a sold-out mint leaves no trace
+        self.assert_token_id_free(&token_id);
+        if options.series_id == 0 {
+            assert_not_series_token_id(&token_id);
+            self.assert_supply_left(1);
+        }
+        self.internal_claim_reservation(&token_id, &holder);
+        self.metadata_limits.assert_valid(&token_metadata);
+        if let Some(attributes) = &options.attributes {
+            self.assert_valid_attributes(attributes);
+        }
+        if let Some(royalties) = &options.royalties {
+            assert_valid_royalties(royalties);
+        }
+        if let Some(split_percent) = options.split_percent {
+            self.assert_split_in_range(split_percent);
+        }
+
+        let terms = self.series_terms(options.series_id);
+        let price = options.price.unwrap_or(terms.mint_price);
+        // The treasury's fee comes off the top, the rest is split as before
+        let mint_fee = price.checked_mul(self.mint_fee_bps).unwrap() / 10_000;
+        let split_percent = options.split_percent.unwrap_or(terms.payment_split_percent);
+        let vault_amount = (price - mint_fee).checked_mul(split_percent)
+            .unwrap().checked_div(100u128).unwrap();
+
+        let mut owner_amount = price.checked_sub(mint_fee + vault_amount).unwrap();
+
+        // Shares carved out of the owner's amount, paid alongside it in resolve_create
+        let mut payouts: Vec<(AccountId, U128)> = Vec::new();
+        if let Some(code) = options.affiliate_code {
+            let share = owner_amount * self.affiliate_bps as u128 / 10_000;
+            owner_amount -= share;
+            let affiliate = self.internal_credit_affiliate(&code, &holder, share);
+            payouts.push((affiliate, U128(share)));
+        }
+        if let Some(referrer) = options.referrer {
+            let share = self.referral_share(&referrer, &holder, owner_amount);
+            if share > 0 {
+                owner_amount -= share;
+                Event::Referral { referrer: &referrer, minter: &holder, token_id: &token_id, amount: U128(share) }.emit();
+                payouts.push((referrer, U128(share)));
+            }
+        }
+        // The charity's share has its own callback, so it is kept apart from the owner's amount
+        let charity_amount = self.charity_share(owner_amount);
+        owner_amount -= charity_amount;
+
+        let initial_storage_usage = env::storage_usage();
+        // The token's owner holds it, whoever paid for the mint
+        let joined = self.holders.insert(&token_owner_id);
+        self.internal_count_minted(&holder, true);
+        self.index = self.index.checked_add(1).unwrap();
+        self.minted_at.insert(&token_id, &env::block_timestamp());
+        self.internal_lock_burn(&token_id);
+
+        let token = self.tokens.internal_mint_with_refund(token_id.clone(), token_owner_id, Some(token_metadata), None);
+        self.internal_update_reward_weight(&token.owner_id);
+        if joined {
+            self.emit_holder_changed(&token.owner_id, true, self.owned_count(&token.owner_id));
+        }
+        if options.attributes.is_some()
+            || options.series_id != 0
+            || options.transfer_locked_until.is_some()
+            || options.without_vault
+        {
+            let mut extra = self.token_extra(&token_id);
+            if let Some(attributes) = options.attributes {
+                self.internal_add_trait_counts(&attributes);
+                extra.attributes = attributes;
+            }
+            extra.series_id = options.series_id;
+            extra.transfer_locked_until = options.transfer_locked_until;
+            extra.without_vault = options.without_vault;
+            self.token_extras.insert(&token_id, &extra);
+        }
+        if let Some(royalties) = &options.royalties {
+            self.token_royalties.insert(&token_id, royalties);
+        }
+        if let Some(split_percent) = options.split_percent {
+            self.token_vault_splits.insert(&token_id, &split_percent);
+        }
+        if !options.without_vault {
+            self.vault_amounts.insert(&token_id, &vault_amount);
+            if options.currency != self.base_currency {
+                self.vault_currencies.insert(&token_id, &options.currency);
+            }
+        }
+        let storage_cost = storage_cost_since(initial_storage_usage);
+        let vault_paid = if options.without_vault { 0 } else { options.vault_deposit.unwrap_or_else(|| self.vault_deposit()) };
+        let (near_paid, ft_paid) = if options.currency.is_some() {
+            (vault_paid, price)
+        } else {
+            (price + vault_paid, 0)
+        };
+        let proceeds = MintProceeds {
+            price,
+            owner_amount,
+            vault_amount,
+            charity_amount,
+            mint_fee,
+            payouts,
+            minter: holder,
+            near_paid,
+            ft_paid,
+            currency: options.currency,
+            without_vault: options.without_vault,
+            vault_deposit: vault_paid,
+        };
+        if price > 0 {
+            Event::MintSplit {
+                token_id: &token_id,
+                mint_fee: U128(mint_fee),
+                vault_amount: U128(proceeds.vault_share()),
+                owner_amount: U128(proceeds.owner_share()),
+            }.emit();
+        }
+        (token, proceeds, storage_cost)
+    }
+
+    /// Panic unless `count` more tokens fit in the collection's own supply. Sold vouchers hold
+    /// their place in it until they are redeemed or refunded.
+    pub(crate) fn assert_supply_left(&self, count: u128) {
+        if self.total_supply > 0 {
+            require!(
+                self.total_supply >= self.series_zero_minted() + self.vouchers_outstanding as u128 + count,
+                "Exceeded total supply"
+            );
+        }
+    }
+
+    /// The id of a token minted without one: the mint index, or the first number after it that
+    /// no explicitly named token has taken or burned. Always a valid vault subaccount name.
+    pub(crate) fn internal_next_token_id(&self) -> TokenId {
+        let mut index = self.index;
+        while self.tokens.owner_by_id.contains_key(&index.to_string())
+            || self.burned_tokens.contains(&index.to_string())
+            || self.is_reserved(&index.to_string())
+        {
+            index += 1;
+        }
+        index.to_string()
+    }
+
+    /// Memo of an `nft_mint` event for the paid mints of `proceeds`, all in one currency: what
+    /// they cost and how it was split, plus the charity's share when one is configured.
+    pub(crate) fn mint_memo(&self, proceeds: &[&MintProceeds]) -> String {
+        let total = |amount: fn(&MintProceeds) -> u128| -> String {
+            proceeds.iter().map(|proceeds| amount(proceeds)).sum::<u128>().to_string()
+        };
+        let mut memo = json!({
+            "price": total(|proceeds| proceeds.price),
+            "currency": proceeds.first().map_or("near", |proceeds| proceeds.currency_name()),
+            "vault_amount": total(MintProceeds::vault_share),
+            "owner_amount": total(MintProceeds::owner_share),
+        });
+        if let Some((charity, _)) = &self.charity {
+            memo["charity"] = json!(charity);
+            memo["charity_amount"] = json!(total(|proceeds| proceeds.charity_amount));
+        }
+        memo.to_string()
+    }
+
+    /// Gas a mint needs for its vault: the init call, `resolve_create` and scheduling both.
+    pub(crate) fn gas_per_mint(&self, payouts: usize, paid_in_ft: bool) -> Gas {
+        GAS_FOR_MINT_LOCAL
+            .saturating_add(GAS_FOR_VAULT_INIT)
+            .saturating_add(self.gas_for_resolve_create(payouts, paid_in_ft))
+    }
+
+    /// Gas for `resolve_create` to make every call it schedules for a mint with `payouts` shares.
+    fn gas_for_resolve_create(&self, payouts: usize, paid_in_ft: bool) -> Gas {
+        let transfer_gas = if paid_in_ft { Gas::from_tgas(20) } else { Gas::from_tgas(0) };
+        let vault_gas = if paid_in_ft { GAS_FOR_FT_VAULT_DEPOSIT } else { GAS_FOR_NEAR_VAULT_DEPOSIT };
+        let transfers = 1 + payouts as u64 + u64::from(self.mint_fee_bps > 0);
+        let mut promises = 2 + transfers;
+        let mut gas = GAS_FOR_RESOLVE_CREATE_BASE
+            .saturating_add(vault_gas)
+            .saturating_add(Gas::from_gas(transfer_gas.as_gas() * transfers));
+        if self.charity.is_some() {
+            promises += 2;
+            gas = gas.saturating_add(transfer_gas).saturating_add(Gas::from_tgas(10));
+        }
+        gas.saturating_add(Gas::from_gas(GAS_PER_PROMISE.as_gas() * promises))
+    }
+
+    /// Create and fund the `{token_id}.{current_account}` vault, then pay out the mint proceeds in
+    /// `resolve_create`. Must only be called once every local check of the mint has passed.
+    pub(crate) fn internal_deploy_vault(&self, token_id: &TokenId, proceeds: MintProceeds) -> Promise {
+        let collection_owner = self.tokens.owner_id.clone();
+        let current_id = env::current_account_id();
+
+        // Deploy the vault contract
+        let vault_account_id: AccountId = format!("{}.{}", token_id, current_id).parse().unwrap();
+        Promise::new(vault_account_id.clone())
+            .create_account()
+            .deploy_contract(VAULT_CODE.to_vec())
+            .transfer(NearToken::from_yoctonear(proceeds.vault_deposit))
+            .function_call(
+                // Init the vault contract
+                "init".to_string(),
+                if let Some(ft_id) = proceeds.currency.clone() {
+                    json!({
+                        "ft_contract": ft_id.to_string(),
+                        "treasury": self.treasury.to_string()
+                    })
+                } else {
+                    json!({
+                        "treasury": self.treasury.to_string()
+                    })
+                }.to_string().into_bytes().to_vec(),
+                NearToken::from_millinear(0),
+                GAS_FOR_VAULT_INIT
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                .with_static_gas(self.gas_for_resolve_create(proceeds.payouts.len(), proceeds.currency.is_some()))
+                .resolve_create(vault_account_id, collection_owner, token_id.clone(), proceeds)
+            )
+    }
+
+    /// Remove `token_id` owned by `owner` from every token map. Returns whether `owner` held its
+    /// last token and left the holders.
+    pub(crate) fn internal_remove_token(&mut self, token_id: &TokenId, owner: &AccountId) -> bool {
+        // Remove the NFT from the owner's account
+        self.tokens.owner_by_id.remove(token_id);
+        self.minted_at.remove(token_id);
+        self.burn_unlocks_at.remove(token_id);
+
+        // Remove token metadata (if applicable)
+        self.tokens
+            .token_metadata_by_id
+            .as_mut()
+            .and_then(|by_id| by_id.remove(token_id));
+        self.token_royalties.remove(token_id);
+        self.token_vault_splits.remove(token_id);
+        self.vault_currencies.remove(token_id);
+        self.vault_amounts.remove(token_id);
+        if let Some(extra) = self.token_extras.remove(token_id) {
+            self.internal_remove_trait_counts(&extra.attributes);
+        }
+        self.internal_remove_from_rarity_index(token_id);
+        self.internal_release_attachments(token_id, owner);
+        
+        // Remove the NFT from the tokens_per_owner map
+        let mut removed = false;
+        if let Some(tokens_per_owner) = &mut self.tokens.tokens_per_owner {
+            let mut owner_tokens = tokens_per_owner.get(owner).unwrap_or_else(|| {
+                env::panic_str("Unable to access tokens per owner in unguarded call.")
+            });
+            owner_tokens.remove(token_id);
+            if owner_tokens.is_empty() {
+                tokens_per_owner.remove(owner);
+                self.holders.remove(owner);
+                removed = true;
+            } else {
+                tokens_per_owner.insert(owner, &owner_tokens);
+            }
+        }
+        self.internal_update_reward_weight(owner);
+        if removed {
+            self.emit_holder_changed(owner, false, 0);
+        }
+        
+        // Remove any approvals associated with this NFT
+        self.tokens
+            .approvals_by_id
+            .as_mut()
+            .and_then(|by_id| by_id.remove(token_id));
+
+        // Remove next approval ID (if applicable)
+        self.tokens
+            .next_approval_id_by_id
+            .as_mut()
+            .and_then(|by_id| by_id.remove(token_id));
+
+        removed
+    }
+
+    /// Undo the mint of `token_id` after its vault failed to deploy: remove the token wherever it
+    /// went since, free its place in the supply, credit the `ft_paid` in `currency` for it back to
+    /// the deposits of `minter` and refund it the `near_paid` plus the storage the token released.
+    pub(crate) fn internal_rollback_mint(
+        &mut self,
+        token_id: &TokenId,
+        minter: &AccountId,
+        near_paid: u128,
+        ft_paid: u128,
+        currency: &Option<AccountId>,
+    ) {
+        let initial_storage_usage = env::storage_usage();
+        if let Some(owner) = self.tokens.owner_by_id.get(token_id) {
+            if self.token_extra(token_id).series_id != 0 {
+                self.series_minted -= 1;
+            }
+            self.internal_remove_token(token_id, &owner);
+            self.internal_count_minted(minter, false);
+            self.index -= 1;
+            NftBurn {
+                owner_id: &owner,
+                token_ids: &[token_id],
+                authorized_id: None,
+                memo: Some("vault deployment failed"),
+            }.emit();
+        }
+        let refund = near_paid + storage_freed_since(initial_storage_usage);
+        if let Some(ft_contract) = currency.as_ref().filter(|_| ft_paid > 0) {
+            // The mint debited the minter's entry, which is kept at zero rather than removed
+            self.internal_credit_ft_deposit(minter, ft_contract, ft_paid);
+        }
+        Promise::new(minter.clone()).transfer(NearToken::from_yoctonear(refund));
+    }
+
+    /// Burn `token_id` owned by `owner`: remove it from every token map, withdraw the token's vault
+    /// to `owner`, which then deletes itself in favor of the vault beneficiary, and, once the
+    /// withdrawal succeeded, credit the other holders their share of the burn fee in
+    /// `resolve_burn`. A token minted without a vault has no burn fee to share, so it is only
+    /// removed. Emits `nft_burn` with the amount the vault pays `owner` in its memo, and the
+    /// approved account that burned the token for `owner` as `authorized_id`. A `moderation` memo
+    /// marks a burn by the collection owner, see `internal_remove_burned`.
+    pub(crate) fn internal_burn(
+        &mut self,
+        token_id: &TokenId,
+        owner: &AccountId,
+        authorized_id: Option<&AccountId>,
+        moderation: Option<&str>,
+    ) -> (BurnReceipt, Option<Promise>) {
+        let (receipt, burned, withdrawal) =
+            self.internal_remove_burned(token_id, owner, authorized_id, moderation);
+        let withdrawal = withdrawal.map(|withdrawal| then_resolve_burn(withdrawal, owner, vec![burned]));
+        (receipt, withdrawal)
+    }
+
+    /// Burn `token_id`, sent to this contract by `nft_transfer_call` with the `burn` msg, as if
+    /// `previous_owner_id` had burned it: the token is handed back first, so the vault pays them
+    /// and they are excluded from their own burn fee. `sender_id` is the `authorized_id` when an
+    /// approved account sent the token.
+    pub(crate) fn internal_burn_sent_back(
+        &mut self,
+        sender_id: &AccountId,
+        previous_owner_id: &AccountId,
+        token_id: &TokenId,
+    ) {
+        let current_id = env::current_account_id();
+        self.assert_transferable(token_id);
+        self.assert_burnable(token_id);
+        self.tokens.internal_transfer_unguarded(token_id, &current_id, previous_owner_id);
+        NftTransfer {
+            old_owner_id: &current_id,
+            new_owner_id: previous_owner_id,
+            token_ids: &[token_id],
+            authorized_id: None,
+            memo: Some("burn"),
+        }.emit();
+        let authorized_id = Some(sender_id).filter(|sender_id| *sender_id != previous_owner_id);
+        self.internal_burn(token_id, previous_owner_id, authorized_id, None);
+    }
+
+    /// The state half of a burn: remove `token_id` owned by `owner`, record it in the burn
+    /// records, emit `nft_burn` and `burn_receipt` and schedule the vault withdrawal. Returns the
+    /// receipt and the withdrawal for `resolve_burn` to settle, which only credits the holders the
+    /// burn fee once the withdrawal succeeded. A burn with a `moderation` memo, recorded in `nft_burn`, ignores the burn lock,
+    /// charges no burn fee and deletes the vault in favor of `owner`, so none of the token's
+    /// value leaves its owner.
+    pub(crate) fn internal_remove_burned(
+        &mut self,
+        token_id: &TokenId,
+        owner: &AccountId,
+        authorized_id: Option<&AccountId>,
+        moderation: Option<&str>,
+    ) -> (BurnReceipt, BurnedVault, Option<Promise>) {
+        require!(
+            self.reward_weight_backfill.is_none(),
+            "Burns resume once migrate_reward_weights has visited every holder"
+        );
+        self.assert_transferable(token_id);
+        if moderation.is_none() {
+            self.assert_burnable(token_id);
+        }
+        let extra = self.token_extra(token_id);
+        let vault_currency = self.vault_currency(token_id);
+        let burn_fee_bps = if moderation.is_some() { 0 } else { self.burn_fee_bps(token_id) as u128 };
+        let vault_amount = self.estimated_vault_amount(token_id);
+        let (burn_fee_applied, _) = split_burn_fee(vault_amount, burn_fee_bps);
+        let current_id = env::current_account_id();
+        let vault_account_id: AccountId = format!("{}.{}", token_id, current_id).parse().unwrap();
+        let receipt = BurnReceipt {
+            token: self.nft_token(token_id.clone()).unwrap(),
+            vault_account: vault_account_id.clone(),
+            estimated_payout: U128(vault_amount - burn_fee_applied),
+            burn_fee_applied: U128(burn_fee_applied),
+            holders_rewarded: if burn_fee_applied > 0 && vault_currency == self.base_currency {
+                self.rewarded_holder_count(owner)
+            } else {
+                0
+            },
+        };
+
+        let initial_storage_usage = env::storage_usage();
+        self.internal_remove_token(token_id, owner);
+        let freed = storage_freed_since(initial_storage_usage);
+        let removed_storage_usage = env::storage_usage();
+        self.burned_tokens.insert(token_id);
+        self.burned_count = self.burned_count.checked_add(1).unwrap();
+        let payout = (!extra.without_vault).then_some(receipt.estimated_payout);
+        self.internal_record_burn(token_id, owner, authorized_id.unwrap_or(owner), payout, removed_storage_usage, freed);
+        let mut memo = json!({
+            "vault_withdrawal": receipt.estimated_payout,
+        });
+        if let Some(moderation) = moderation {
+            memo["moderation"] = json!(moderation);
+        }
+        let memo = memo.to_string();
+        NftBurn {
+            owner_id: owner,
+            token_ids: &[token_id],
+            authorized_id: authorized_id.map(|account_id| account_id.as_ref()),
+            memo: Some(&memo),
+        }.emit();
+        Event::BurnReceipt {
+            token_id,
+            owner_id: owner,
+            vault_account: &receipt.vault_account,
+            estimated_payout: receipt.estimated_payout,
+            burn_fee_applied: receipt.burn_fee_applied,
+            holders_rewarded: U64(receipt.holders_rewarded),
+        }.emit();
+        let burned = BurnedVault {
+            token_id: token_id.clone(),
+            vault_amount: U128(vault_amount),
+            burn_fee_bps: burn_fee_bps as u32,
+            currency: vault_currency.clone(),
+        };
+        if extra.without_vault {
+            return (receipt, burned, None);
+        }
+
+        let beneficiary = if moderation.is_some() { owner.clone() } else { self.vault_beneficiary_of(owner) };
+        let withdrawal = match &vault_currency {
+            Some(ft_contract) => self.internal_redeem(token_id, owner, ft_contract, burn_fee_bps, beneficiary),
+            None => vault_withdrawal(token_id, owner, burn_fee_bps, &beneficiary),
+        };
+        (receipt, burned, Some(withdrawal))
+    }
+
+    /// Panic unless `account_id` may burn `token_id` owned by `owner`: it is the owner, or approved
+    /// on the token with `approval_id` when one is given, as `nft_transfer` authorizes a transfer.
+    pub(crate) fn assert_burner(&self, token_id: &TokenId, owner: &AccountId, account_id: &AccountId, approval_id: Option<u64>) {
+        if account_id == owner {
+            return;
+        }
+        let approved_id = self.tokens.approvals_by_id.as_ref()
+            .and_then(|by_id| by_id.get(token_id))
+            .and_then(|approvals| approvals.get(account_id).copied())
+            .unwrap_or_else(|| env::panic_str("You don't own this NFT"));
+        require!(
+            approval_id.is_none_or(|approval_id| approval_id == approved_id),
+            "The approval ID doesn't match"
+        );
+    }
+
+    /// Panic unless `account_id` owns `token_id`.
+    pub(crate) fn assert_token_owner(&self, token_id: &TokenId, account_id: &AccountId) {
+        let token_owner = self.tokens.owner_by_id.get(token_id)
+            .unwrap_or_else(|| env::panic_str("Token not found"));
+        require!(&token_owner == account_id, "You don't own this NFT");
+    }
+
+    /// Panic if `token_id` is held in place: staked until it is released, or locked for the bridge
+    /// until the bridge account unlocks it. Such tokens can't be listed either.
+    pub(crate) fn assert_listable(&self, token_id: &TokenId) {
+        require!(self.staked.get(token_id).is_none(), "Token is staked");
+        require!(self.bridge_locks.get(token_id).is_none(), "Token is locked for the bridge");
+    }
+
+    /// Panic if `token_id` can't move: it is held in place, time-locked until a release date, or
+    /// its owner voted on a proposal that is still open. A time-locked token can still be
+    /// approved, the approval just can't execute before then.
+    pub(crate) fn assert_transferable(&self, token_id: &TokenId) {
+        self.assert_listable(token_id);
+        if let Some(locked_until) = self.token_extra(token_id).transfer_locked_until {
+            require!(
+                env::block_timestamp() >= locked_until,
+                format!("Token is locked until {}", locked_until)
+            );
+        }
+        let owner = self.tokens.owner_by_id.get(token_id);
+        if let Some(locked_until) = owner.and_then(|owner| self.vote_locks.get(&owner)) {
+            require!(
+                env::block_timestamp() >= locked_until,
+                format!("Token is locked by its owner's votes until {}", locked_until)
+            );
+        }
+    }
+
+    /// Panic if any account is approved on `token_id`. Marketplace listings work through
+    /// approvals, so this keeps listed tokens out of flows that change or consume them.
+    pub(crate) fn assert_no_approvals(&self, token_id: &TokenId) {
+        let approved = self.tokens.approvals_by_id.as_ref()
+            .and_then(|by_id| by_id.get(token_id))
+            .is_some_and(|approvals| !approvals.is_empty());
+        require!(!approved, format!("Token {} has approvals, revoke them first", token_id));
+    }
+
+    /// Update `holders` for a token moving from `sender_id` to `receiver_id`, before the transfer:
+    /// the sender leaves when this is its last token and the receiver joins with its first. The
+    /// reward weights follow in `internal_update_reward_weights` once the token moved.
+    pub(crate) fn internal_update_holders(&mut self, sender_id: &AccountId, receiver_id: &AccountId) {
+        if let Some(tokens_per_owner) = &self.tokens.tokens_per_owner {
+            let sender_tokens = tokens_per_owner.get(sender_id).unwrap_or_else(|| {
+                env::panic_str("Unable to access tokens per owner in unguarded call.")
+            });
+            let receiver_count = tokens_per_owner.get(receiver_id).map_or(0, |tokens| tokens.len());
+            if sender_tokens.len() == 1 && self.holders.remove(sender_id) {
+                self.emit_holder_changed(sender_id, false, 0);
+            }
+            if receiver_count == 0 && self.holders.insert(receiver_id) {
+                self.emit_holder_changed(receiver_id, true, 1);
+            }
+        }
+    }
+
+    /// Bring the reward weights of both sides of a token transfer up to date, once it happened.
+    pub(crate) fn internal_update_reward_weights(&mut self, sender_id: &AccountId, receiver_id: &AccountId) {
+        self.internal_update_reward_weight(sender_id);
+        self.internal_update_reward_weight(receiver_id);
+    }
+
+    /// Emit `holder_changed` for `account_id` joining or leaving `holders`, with the token count it
+    /// has once the change that triggered it completes.
+    pub(crate) fn emit_holder_changed(&self, account_id: &AccountId, added: bool, token_count: u64) {
+        Event::HolderChanged {
+            account_id,
+            direction: if added { "added" } else { "removed" },
+            token_count: U64(token_count),
+            total_holders: U64(self.holders.len()),
+        }.emit();
+    }
+
+    /// Send `amount` of `currency`, NEAR or an FT, to `receiver_id`.
+    pub(crate) fn internal_send_funds(&self, currency: &Option<AccountId>, receiver_id: &AccountId, amount: u128) -> Promise {
+        if let Some(ft_id) = currency.clone() {
+            Promise::new(ft_id).function_call(
+                "ft_transfer".to_string(),
+                json!({
+                    "receiver_id": receiver_id.to_string(),
+                    "amount": amount.to_string(),
+                }).to_string().into_bytes().to_vec(),
+                NearToken::from_yoctonear(1),
+                Gas::from_tgas(20),
+            )
+        } else {
+            Promise::new(receiver_id.clone()).transfer(NearToken::from_yoctonear(amount))
+        }
+    }
+
+    /// Pay out the shares of a mint's price in `currency` besides the vault's: the treasury's
+    /// fee, the `payouts`, the charity's share and the owner's amount.
+    pub(crate) fn internal_pay_mint_shares(
+        &mut self,
+        currency: &Option<AccountId>,
+        collection_owner: &AccountId,
+        owner_amount: u128,
+        charity_amount: u128,
+        mint_fee: u128,
+        payouts: &[(AccountId, U128)],
+    ) {
+        if mint_fee > 0 {
+            self.internal_send_funds(currency, &self.treasury, mint_fee);
+        }
+        for (receiver_id, amount) in payouts.iter() {
+            if amount.0 > 0 {
+                self.internal_send_funds(currency, receiver_id, amount.0);
+            }
+        }
+        if charity_amount > 0 {
+            if let Some((charity, _)) = self.charity.clone() {
+                self.internal_donate(currency, &charity, charity_amount);
+            } else {
+                // The charity was removed since the mint, so its share goes back to the owner
+                self.internal_pay_owner(currency, collection_owner, charity_amount);
+            }
+        }
+        if owner_amount > 0 {
+            self.internal_pay_owner(currency, collection_owner, owner_amount);
+        }
+    }
+
+    /// Pay out the proceeds of a token minted without a vault, whose share goes to the owner.
+    pub(crate) fn internal_pay_vaultless_proceeds(&mut self, proceeds: MintProceeds) {
+        let collection_owner = self.tokens.owner_id.clone();
+        self.internal_pay_mint_shares(
+            &proceeds.currency,
+            &collection_owner,
+            proceeds.owner_amount + proceeds.vault_amount,
+            proceeds.charity_amount,
+            proceeds.mint_fee,
+            &proceeds.payouts,
+        );
+    }
+
+    /// Pay the collection owner's share of a mint paid in `currency`, or credit it to the vesting
+    /// ledger when a vesting schedule is configured. Only the base currency vests.
+    pub(crate) fn internal_pay_owner(&mut self, currency: &Option<AccountId>, collection_owner: &AccountId, owner_amount: u128) {
+        if self.vesting.is_some() && *currency == self.base_currency {
+            self.owner_vesting.total = self.owner_vesting.total.checked_add(owner_amount).unwrap();
+        } else {
+            self.internal_send_funds(currency, collection_owner, owner_amount);
+        }
+    }
+
+    /// Panic if a token named `token_id` exists or was burned, its vault account may still exist.
+    pub(crate) fn assert_token_id_free(&self, token_id: &TokenId) {
+        require!(
+            !self.tokens.owner_by_id.contains_key(token_id),
+            format!("Token {} already exists", token_id)
+        );
+        require!(
+            !self.moderated_tokens.contains(token_id),
+            format!("Token {} was removed by moderation and can't be minted again", token_id)
+        );
+        require!(
+            !self.burned_tokens.contains(token_id),
+            format!("Token {} was burned and can't be minted again", token_id)
+        );
+    }
+
+    /// Panic unless the predecessor is the collection owner.
+    pub(crate) fn assert_owner(&self) {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.tokens.owner_id,
+            "Unauthorized"
+        );
+    }
+}
+
+/// Panic unless `token_id` names a valid vault subaccount: one lowercase part of letters, digits,
+/// `-` and `_`, short enough that `{token_id}.{contract}` fits in 64 characters.
+pub(crate) fn assert_valid_token_id(token_id: &TokenId) {
+    require!(!token_id.is_empty(), "Token id can't be empty");
+    require!(!token_id.contains('.'), "Token id can't contain '.'");
+    let vault_account_id = format!("{}.{}", token_id, env::current_account_id());
+    require!(
+        vault_account_id.len() <= 64,
+        format!("Token id {} makes the vault account longer than 64 characters", token_id)
+    );
+    require!(
+        vault_account_id.parse::<AccountId>().is_ok(),
+        format!("Token id {} is not a valid account name part", token_id)
+    );
+}
+
+/// Resolve the page size of a paginated view, defaulting to `DEFAULT_PAGE_LIMIT` and never
+/// exceeding `MAX_PAGE_LIMIT`.
+pub(crate) fn page_limit(limit: Option<u64>) -> usize {
+    limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize
+}
+
+/// Have the vault of burned `token_id` pay `owner`, less the burn fee in basis points, and
+/// delete itself in favor of `beneficiary`.
+pub(crate) fn vault_withdrawal(token_id: &TokenId, owner: &AccountId, burn_fee_bps: u128, beneficiary: &AccountId) -> Promise {
+    let vault_account_id: AccountId = format!("{}.{}", token_id, env::current_account_id()).parse().unwrap();
+    Promise::new(vault_account_id).function_call(
+        "withdraw".to_string(),
+        json!({
+            "owner": owner.to_string(),
+            // Vaults deployed before the fee was in basis points only read the whole percent
+            "burn_fee": (burn_fee_bps / 100).to_string(),
+            "burn_fee_bps": burn_fee_bps.to_string(),
+            "beneficiary": beneficiary.to_string(),
+        }).to_string().into_bytes().to_vec(),
+        NearToken::from_yoctonear(1),
+        GAS_FOR_VAULT_WITHDRAW
+    )
+}
+
+/// Chain `resolve_burn` for `owner` after `withdrawals`, the joined vault withdrawals of the
+/// `burned` tokens, in the same order.
+pub(crate) fn then_resolve_burn(withdrawals: Promise, owner: &AccountId, burned: Vec<BurnedVault>) -> Promise {
+    withdrawals.then(
+        Contract::ext(env::current_account_id())
+            .with_static_gas(GAS_FOR_RESOLVE_BURN)
+            .resolve_burn(owner.clone(), burned)
+    )
+}
+
+/// Emit `mint_paid` for `token`, bought for `proceeds`, so indexers can track volume. Free mints
+/// emit nothing.
+pub(crate) fn emit_mint_paid(token: &Token, proceeds: &MintProceeds) {
+    if proceeds.price > 0 {
+        Event::MintPaid {
+            token_id: &token.token_id,
+            owner_id: &token.owner_id,
+            minter: &proceeds.minter,
+            price: U128(proceeds.price),
+            currency: proceeds.currency_name(),
+            vault_amount: U128(proceeds.vault_share()),
+            owner_amount: U128(proceeds.owner_share()),
+        }.emit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    fn with_contract_account(account_id: &str) {
+        testing_env!(VMContextBuilder::new().current_account_id(account_id.parse().unwrap()).build());
+    }
+
+    #[test]
+    fn accepts_subaccount_names() {
+        with_contract_account("nft.near");
+        for token_id in ["1", "42", "gold-edition", "team_allocation"] {
+            assert_valid_token_id(&token_id.to_string());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Token id Token#1 is not a valid account name part")]
+    fn rejects_uppercase_and_symbols() {
+        with_contract_account("nft.near");
+        assert_valid_token_id(&"Token#1".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Token id can't be empty")]
+    fn rejects_an_empty_id() {
+        with_contract_account("nft.near");
+        assert_valid_token_id(&String::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "Token id can't contain '.'")]
+    fn rejects_a_nested_account() {
+        with_contract_account("nft.near");
+        assert_valid_token_id(&"a.b".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "makes the vault account longer than 64 characters")]
+    fn rejects_a_60_character_id() {
+        with_contract_account("nft.near");
+        assert_valid_token_id(&"a".repeat(60));
+    }
+
+    #[test]
+    fn accepts_the_longest_id_that_fits() {
+        // `.nft.near` takes 9 of the 64 characters
+        with_contract_account("nft.near");
+        assert_valid_token_id(&"a".repeat(55));
+    }
+
+    #[test]
+    #[should_panic(expected = "makes the vault account longer than 64 characters")]
+    fn rejects_one_character_more() {
+        with_contract_account("nft.near");
+        assert_valid_token_id(&"a".repeat(56));
+    }
+}
@@ -0,0 +1,76 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Check the global consistency of the holder, supply and reward bookkeeping.
+    ///
+    /// Returns a description of every violation found, so an empty vector means the state is
+    /// consistent. Collection-wide checks run on every call, while the per-account and per-token
+    /// checks only cover the `[from_index, from_index + limit)` window of `holders` and of the
+    /// token enumeration, so large collections are checked by paging through the windows. A
+    /// window covers `DEFAULT_INVARIANTS_LIMIT` entries by default and `MAX_INVARIANTS_LIMIT` at most.
+    /// The reward check compares the balances of the holders in the window against the tracked
+    /// reward pool, which is a lower bound of the full sum.
+    pub fn check_invariants(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<String> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        let limit = limit.unwrap_or(DEFAULT_INVARIANTS_LIMIT).min(MAX_INVARIANTS_LIMIT) as usize;
+        let mut violations = Vec::new();
+
+        let live_supply = self.tokens.owner_by_id.len() as u128;
+        if self.index != live_supply + self.burned_count {
+            violations.push(format!(
+                "index {} is not live supply {} plus burned count {}",
+                self.index, live_supply, self.burned_count
+            ));
+        }
+        if self.burned_count != self.burned_tokens.len() as u128 + self.legacy_burned_count {
+            violations.push(format!(
+                "burned count {} doesn't match the {} burned token ids and {} burned before the upgrade",
+                self.burned_count, self.burned_tokens.len(), self.legacy_burned_count
+            ));
+        }
+        if self.total_supply != 0 && self.series_zero_minted() > self.total_supply {
+            violations.push(format!(
+                "{} collection tokens minted, more than the total supply {}",
+                self.series_zero_minted(), self.total_supply
+            ));
+        }
+
+        let mut holder_balances: u128 = 0;
+        for holder in self.holders.iter().skip(start).take(limit) {
+            if self.owned_count(&holder) == 0 {
+                violations.push(format!("holder {} owns no tokens", holder));
+            }
+            holder_balances = holder_balances.saturating_add(self.balance_of(holder.clone()));
+        }
+        if holder_balances > self.reward_pool {
+            violations.push(format!(
+                "holder balances {} exceed the tracked reward pool {}",
+                holder_balances, self.reward_pool
+            ));
+        }
+
+        for (token_id, token_owner) in self.tokens.owner_by_id.iter().skip(start).take(limit) {
+            if !self.holders.contains(&token_owner) {
+                violations.push(format!(
+                    "owner {} of token {} is not in holders",
+                    token_owner, token_id
+                ));
+            }
+        }
+
+        violations
+    }
+}
+
+impl Contract {
+    /// Number of tokens currently owned by `account_id`.
+    pub(crate) fn owned_count(&self, account_id: &AccountId) -> u64 {
+        self.tokens
+            .tokens_per_owner
+            .as_ref()
+            .and_then(|by_owner| by_owner.get(account_id))
+            .map(|tokens| tokens.len())
+            .unwrap_or(0)
+    }
+}
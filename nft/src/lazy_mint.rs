@@ -0,0 +1,86 @@
+use crate::*;
+use near_sdk::CurveType;
+
+/// A mint the collection owner signed off-chain for a buyer to redeem, see `mint_with_voucher`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintVoucher {
+    pub token_id: TokenId,
+    //sha256 of the borsh serialized token metadata
+    pub metadata_hash: [u8; 32],
+    pub price: U128,
+    //timestamp in nanoseconds after which the voucher can't be redeemed
+    pub expiry: U64,
+    //unique per voucher, so each can only be redeemed once
+    pub nonce: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Accept vouchers signed by `signer_pk`, or stop accepting them with `None`.
+    pub fn set_voucher_signer(&mut self, signer_pk: Option<PublicKey>) {
+        self.assert_owner();
+        if let Some(signer_pk) = &signer_pk {
+            require!(signer_pk.curve_type() == CurveType::ED25519, "The signer key must be ed25519");
+        }
+        self.voucher_signer_pk = signer_pk;
+    }
+
+    pub fn voucher_signer(&self) -> Option<PublicKey> {
+        self.voucher_signer_pk.clone()
+    }
+
+    pub fn is_voucher_used(&self, nonce: U64) -> bool {
+        self.used_vouchers.get(&nonce.0).unwrap_or(false)
+    }
+
+    /// Mint the token `voucher` describes to the caller at the voucher's price, in the base
+    /// currency. `signature` is the
+    /// voucher signer's ed25519 signature over `sha256(borsh(voucher))`, and `token_metadata` must
+    /// hash to `voucher.metadata_hash`.
+    #[payable]
+    pub fn mint_with_voucher(
+        &mut self,
+        voucher: MintVoucher,
+        token_metadata: TokenMetadata,
+        signature: Base64VecU8,
+    ) -> Token {
+        self.assert_mint_not_paused();
+        assert_valid_token_id(&voucher.token_id);
+        let signer_pk = self.voucher_signer_pk.as_ref()
+            .unwrap_or_else(|| env::panic_str("No voucher signer is set"));
+        let signature: [u8; 64] = signature.0.as_slice().try_into()
+            .unwrap_or_else(|_| env::panic_str("Voucher signature must be 64 bytes"));
+        let public_key: [u8; 32] = signer_pk.as_bytes()[1..].try_into().unwrap();
+        let message = env::sha256(&near_sdk::borsh::to_vec(&voucher).unwrap());
+        require!(env::ed25519_verify(&signature, &message, &public_key), "Invalid voucher signature");
+        require!(env::block_timestamp() < voucher.expiry.0, "Voucher has expired");
+        require!(
+            env::sha256_array(&near_sdk::borsh::to_vec(&token_metadata).unwrap()) == voucher.metadata_hash,
+            "Token metadata doesn't match the voucher"
+        );
+        require!(!self.is_voucher_used(voucher.nonce), "Voucher was already used");
+        self.used_vouchers.insert(&voucher.nonce.0, &true);
+
+        let buyer = env::predecessor_account_id();
+        self.internal_count_wallet_mints(&buyer, 1);
+        let price = voucher.price.0;
+        let currency = self.base_currency.clone();
+        let storage_estimate = estimate_token_storage(Some(&token_metadata));
+        let deposit = env::attached_deposit().as_yoctonear();
+        let without_vault = !self.mints_vault(price);
+        let vault_deposits = if without_vault { 0 } else { self.vault_deposit() };
+        let near_price = self.internal_take_mint_payment(&buyer, &currency, price, deposit, vault_deposits, storage_estimate);
+
+        self.internal_mint(
+            voucher.token_id,
+            buyer.clone(),
+            token_metadata,
+            buyer,
+            near_price,
+            deposit,
+            MintOptions { price: Some(price), currency, without_vault, ..Default::default() }
+        )
+    }
+}
@@ -0,0 +1,133 @@
+use crate::*;
+
+/// State of the contract as first deployed, which `migrate` upgrades. Frozen: it must keep
+/// reading what that version wrote, whatever the current `Contract` looks like.
+#[derive(BorshDeserialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct OldContract {
+    pub tokens: NonFungibleToken,
+    pub metadata: LazyOption<NFTContractMetadata>,
+    pub index: u128,
+    pub total_supply: u128,
+    pub mint_price: u128,
+    pub mint_currency: Option<AccountId>,
+    pub payment_split_percent: u128,
+    pub storage_deposits: LookupMap<AccountId, u128>,
+    // Read in place through `legacy_ft_deposits`
+    #[allow(dead_code)]
+    pub ft_deposits: LookupMap<AccountId, Balance>,
+    pub burn_fee: u128,
+    pub balances_by_owner: LookupMap<AccountId, Balance>,
+    pub holders: UnorderedSet<AccountId>,
+    pub treasury: AccountId,
+    pub royalty: u128,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Upgrade the state of a deployment of the original contract. Its tokens, holders, balances
+    /// and deposits carry over with the configuration it was initialized with, and everything
+    /// added since starts as `new` sets it. The ids of the tokens burned before were never kept,
+    /// so they are only counted, in `legacy_burned_count`. Burns are blocked until
+    /// `migrate_reward_weights` has had every holder carried over accrue burn fees.
+    ///
+    /// The original never tracked its reward pool, and the balances it kept can't be listed
+    /// on chain, so `reward_pool` is the sum of every one of them, those of accounts that no
+    /// longer hold tokens included, as read from the state before the upgrade.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate(reward_pool: U128) -> Self {
+        let state = env::storage_read(b"STATE").unwrap_or_else(|| env::panic_str("Contract is not initialized"));
+        let old = OldContract::try_from_slice(&state).unwrap_or_else(|_| env::panic_str("The state is already migrated"));
+        let burned = old.index.saturating_sub(old.tokens.owner_by_id.len() as u128);
+        let mut contract = Self::initial_state(
+            old.tokens,
+            old.metadata,
+            U128(old.mint_price),
+            old.mint_currency,
+            U128(old.payment_split_percent),
+            U128(old.total_supply),
+            U128(old.burn_fee),
+            old.treasury,
+            U128(old.royalty),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        contract.index = old.index;
+        contract.storage_deposits = old.storage_deposits;
+        contract.balances_by_owner = old.balances_by_owner;
+        contract.reward_pool = reward_pool.0;
+        contract.holders = old.holders;
+        contract.burned_count = burned;
+        contract.legacy_burned_count = burned;
+        if !contract.holders.is_empty() {
+            contract.reward_weight_backfill = Some(contract.holders.len());
+        }
+        contract
+    }
+
+    /// Have up to `limit` of the holders carried over by `migrate` accrue burn fees from now on,
+    /// and return how many are left. Rewards credited before the upgrade stay in their balances.
+    ///
+    /// Anyone can call it, since burns wait on it, and a call that visits holders earns the
+    /// caller `processor_bounty` per `MAX_PAGE_LIMIT` of them, pro rata, so smaller pages earn
+    /// nothing more. The bounty comes out of `treasury_balance`, as far as it goes. Each call
+    /// moves the cursor past the holders it visits before returning, so no two calls visit the
+    /// same holder or are paid for it.
+    pub fn migrate_reward_weights(&mut self, limit: Option<u64>) -> u64 {
+        let Some(remaining) = self.reward_weight_backfill else {
+            return 0;
+        };
+        // Removing a holder moves the last one into its slot, so going from the end down never
+        // skips one. Holders added since are at the end and already accrue.
+        let end = remaining.min(self.holders.len());
+        let start = end.saturating_sub(page_limit(limit) as u64);
+        for index in start..end {
+            let holder = self.holders.as_vector().get(index).unwrap();
+            self.internal_update_reward_weight(&holder);
+        }
+        self.reward_weight_backfill = Some(start).filter(|start| *start > 0);
+
+        let processor_id = env::predecessor_account_id();
+        let earned = self.processor_bounty.checked_mul((end - start) as u128).unwrap() / MAX_PAGE_LIMIT as u128;
+        let bounty = earned.min(self.treasury_balance);
+        if bounty > 0 {
+            self.treasury_balance -= bounty;
+            Promise::new(processor_id.clone()).transfer(NearToken::from_yoctonear(bounty));
+        }
+        Event::RewardWeightsMigrated {
+            processor_id: &processor_id,
+            from_index: U64(start),
+            to_index: U64(end),
+            bounty: U128(bounty),
+        }.emit();
+        start
+    }
+
+    pub fn reward_weights_to_migrate(&self) -> u64 {
+        self.reward_weight_backfill.unwrap_or(0)
+    }
+
+    pub fn set_processor_bounty(&mut self, processor_bounty: U128) {
+        self.assert_owner();
+        self.processor_bounty = processor_bounty.0;
+    }
+
+    pub fn processor_bounty(&self) -> U128 {
+        U128(self.processor_bounty)
+    }
+}
+
+/// Deposits the original contract took, all in its mint currency, which became the base currency.
+/// They stay under the prefix of `Contract::ft_deposits`, keyed by the account alone, which can't
+/// collide with the `(account, FT contract)` keys: a key's leading length prefix tells them apart.
+/// Each is folded into the account's deposit in the base currency the next time that changes.
+pub(crate) fn legacy_ft_deposits() -> LookupMap<AccountId, Balance> {
+    LookupMap::new(StorageKey::FTDeposits)
+}
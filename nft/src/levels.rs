@@ -0,0 +1,68 @@
+use crate::*;
+
+//the highest level a token can be upgraded to
+pub const MAX_LEVEL: u32 = 100;
+
+#[near_bindgen]
+impl Contract {
+    /// Raise `token_id` by `levels`, paying the cost of every level passed from the caller's FT
+    /// deposits in the base currency. The spent FTs stay on the contract. The attached deposit pays for any new storage.
+    #[payable]
+    pub fn upgrade_token(&mut self, token_id: TokenId, levels: u32) -> u32 {
+        let ft_contract = self.base_currency.clone()
+            .unwrap_or_else(|| env::panic_str("Upgrades are paid in the mint FT"));
+        require!(levels > 0, "Must upgrade by at least one level");
+        let owner = env::predecessor_account_id();
+        let token_owner = self.tokens.owner_by_id.get(&token_id)
+            .unwrap_or_else(|| env::panic_str("Token not found"));
+        require!(token_owner == owner, "You don't own this NFT");
+        self.assert_no_approvals(&token_id);
+
+        let costs = self.level_costs.get().unwrap_or_default();
+        let mut extra = self.token_extra(&token_id);
+        let target = extra.level.checked_add(levels).unwrap();
+        require!(
+            target as usize <= costs.len(),
+            format!("Token can't be upgraded past level {}", costs.len())
+        );
+        let cost = costs[extra.level as usize..target as usize]
+            .iter()
+            .fold(0u128, |total, cost| total.checked_add(*cost).unwrap());
+        let balance = self.ft_deposit(&owner, &ft_contract);
+        require!(
+            balance >= cost,
+            format!("Upgrade costs {} but only {} is deposited", cost, balance)
+        );
+
+        let initial_storage_usage = env::storage_usage();
+        self.internal_set_ft_deposit(&owner, &ft_contract, balance - cost);
+        extra.level = target;
+        self.token_extras.insert(&token_id, &extra);
+        refund_storage_deposit(initial_storage_usage);
+
+        Event::NftMetadataUpdate { token_ids: vec![&token_id] }.emit();
+        target
+    }
+
+    /// Replace the per-level upgrade costs, one entry per level up to at most `MAX_LEVEL`. The
+    /// attached deposit pays for their storage.
+    #[payable]
+    pub fn set_level_costs(&mut self, level_costs: Vec<U128>) {
+        self.assert_owner();
+        require!(
+            level_costs.len() <= MAX_LEVEL as usize,
+            format!("At most {} levels can be configured", MAX_LEVEL)
+        );
+        let initial_storage_usage = env::storage_usage();
+        self.level_costs.set(&level_costs.iter().map(|cost| cost.0).collect());
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    pub fn token_level(&self, token_id: TokenId) -> u32 {
+        self.token_extra(&token_id).level
+    }
+
+    pub fn level_costs(&self) -> Vec<U128> {
+        self.level_costs.get().unwrap_or_default().into_iter().map(U128).collect()
+    }
+}
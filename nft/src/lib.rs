@@ -24,21 +24,130 @@ use near_contract_standards::non_fungible_token::metadata::{
     NFTContractMetadata, NonFungibleTokenMetadataProvider, TokenMetadata,
 };
 use near_contract_standards::non_fungible_token::NonFungibleToken;
-use near_contract_standards::non_fungible_token::events::NftMint;
+use near_contract_standards::non_fungible_token::events::{NftBurn, NftMint};
 use near_contract_standards::non_fungible_token::{Token, TokenId};
-use near_contract_standards::fungible_token::{receiver, Balance};
+use near_contract_standards::fungible_token::Balance;
 use near_sdk::assert_one_yocto;
 use near_sdk::serde::{Serialize, Deserialize};
 use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LazyOption, LookupMap, UnorderedSet};
-use near_sdk::json_types::U128;
+use near_sdk::collections::{LazyOption, LookupMap, TreeMap, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
 use near_sdk::{
-    env, near_bindgen, require, AccountId, BorshStorageKey, PanicOnDefault, Promise, PromiseOrValue, NearToken, Gas, 
+    env, near_bindgen, require, AccountId, BorshStorageKey, PanicOnDefault, Promise, PromiseOrValue, PromiseResult, NearToken, Gas, PublicKey,
     serde_json::json,
 };
 use std::collections::HashMap;
 
+#[cfg(feature = "bench")]
+mod bench;
+mod affiliates;
+mod airdrop;
+mod allowlist;
+mod attachments;
+mod batch_burn;
+mod batch_mint;
+mod bridge;
+mod burn_fee;
+mod burn_lock;
+mod burn_quote;
+mod burn_redeem;
+mod burn_records;
+mod charity;
+mod claims;
+mod combine;
+mod costs;
+mod currencies;
+mod events;
+mod extra;
 mod ft_balances;
+mod id_pool;
+mod internal;
+mod invariants;
+mod lazy_mint;
+mod legacy;
+mod levels;
+mod locks;
+mod merkle;
+mod metadata;
+mod migration;
+mod minter_role;
+mod minters;
+mod mint_pass;
+mod moderation;
+mod owner_mint;
+mod pricing;
+mod raffle;
+mod rarity;
+mod redemptions;
+mod referrals;
+mod reservations;
+mod reveal;
+mod rewards;
+mod royalties;
+mod sale_phase;
+mod series;
+mod snapshots;
+mod staking;
+#[cfg(test)]
+mod test_utils;
+mod token_kv;
+mod traits;
+mod unsold;
+mod upgrades;
+mod usd_pricing;
+mod vault_funding;
+mod vault_mode;
+mod vault_split;
+mod vesting;
+mod voting;
+mod vouchers;
+mod wallet_limits;
+
+pub use crate::affiliates::AffiliateInfo;
+pub use crate::attachments::AttachedNft;
+pub use crate::bridge::{BridgeLock, BridgeStatus};
+pub use crate::burn_fee::BurnFeeSchedule;
+pub use crate::burn_quote::BurnQuote;
+pub use crate::burn_records::BurnRecord;
+pub use crate::claims::ClaimSummary;
+pub use crate::costs::{CostBreakdown, MintCost};
+pub use crate::extra::TokenExtra;
+pub use crate::internal::{BurnReceipt, BurnedVault, MintArgs, MintProceeds};
+pub use crate::lazy_mint::MintVoucher;
+pub use crate::reservations::Reservation;
+pub use crate::rewards::RewardMode;
+pub use crate::metadata::{MetadataLimits, MetadataTemplate};
+pub use crate::migration::Migration;
+pub use crate::raffle::Raffle;
+pub use crate::rarity::RarityScore;
+pub use crate::pricing::DutchAuctionConfig;
+pub use crate::redemptions::{Redemption, RedemptionStatus};
+pub use crate::sale_phase::{SalePhase, SaleWindow, Tier};
+pub use crate::series::{Series, SeriesConfig, SeriesStats};
+pub use crate::snapshots::{Snapshot, SnapshotEntry, SnapshotView};
+pub use crate::staking::StakeInfo;
+pub use crate::traits::{TraitAttribute, TraitDefinition};
+pub use crate::upgrades::UpgradeRule;
+pub use crate::usd_pricing::UsdPricing;
+pub use crate::vault_funding::VaultFunding;
+pub use crate::vault_mode::VaultBeneficiary;
+pub use crate::vesting::{VestingLedger, VestingSchedule};
+pub use crate::vouchers::VoucherEscrow;
+pub use crate::voting::{GovernanceConfig, Proposal, ProposalKind, ProposalView, Vote};
+use crate::airdrop::AIRDROP_EVENT_BATCH;
+use crate::allowlist::DEFAULT_ALLOWLIST_WALLET_LIMIT;
+use crate::batch_mint::{DEFAULT_MAX_MINTS_PER_TX, MAX_TRANSACTION_GAS};
+use crate::burn_quote::split_burn_fee;
+use crate::charity::assert_valid_charity;
+use crate::costs::estimate_token_storage;
+use crate::sale_phase::assert_valid_sale_window;
+use crate::series::assert_not_series_token_id;
+use crate::events::Event;
+use crate::internal::{assert_valid_token_id, emit_mint_paid, page_limit, then_resolve_burn, MintOptions, GAS_FOR_NEAR_MINT, GAS_FOR_VAULT_WITHDRAW};
+use crate::mint_pass::DEFAULT_PASS_VERIFICATION_TTL;
+use crate::royalties::{assert_valid_royalties, royalty_payout};
+use crate::staking::{BASE_REWARD_WEIGHT, DEFAULT_STAKE_MULTIPLIER_BPS};
+use crate::wallet_limits::DEFAULT_MINT_COOLDOWN_BLOCKS;
 
 #[derive(Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
@@ -47,6 +156,22 @@ pub struct Payout {
 }
 
 
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ConfigInfo {
+    pub owner_id: AccountId,
+    pub treasury: AccountId,
+    pub mint_price: U128,
+    pub base_currency: Option<AccountId>,
+    pub payment_split_percent: U128,
+    pub total_supply: U128,
+    pub burn_fee: U128,
+    pub royalty: U128,
+    pub metadata_limits: MetadataLimits,
+    pub combine_fee: U128,
+    pub min_payout_balance: U128
+}
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 #[borsh(crate = "near_sdk::borsh")]
@@ -61,16 +186,23 @@ pub struct Contract {
 
     pub mint_price: u128,
     
-    //which fungible token can be used to purchase NFTs
-    pub mint_currency: Option<AccountId>, 
+    //the fungible token the collection is priced in, or None for NEAR
+    pub base_currency: Option<AccountId>,
+
+    //other fungible tokens that can be used to purchase NFTs, and the mint price in each
+    pub mint_currencies: UnorderedMap<AccountId, u128>,
+
+    //price in NEAR when the collection is priced in a fungible token, if NEAR is accepted
+    pub near_mint_price: Option<u128>,
     
     pub payment_split_percent: u128,
 
     //keep track of the storage that accounts have payed
     pub storage_deposits: LookupMap<AccountId, u128>,
 
-    //keep track of how many FTs each account has deposited in order to purchase NFTs with
-    pub ft_deposits: LookupMap<AccountId, Balance>,
+    //keep track of how many FTs each account has deposited in order to purchase NFTs with,
+    //keyed by the account and the FT contract
+    pub ft_deposits: LookupMap<(AccountId, AccountId), Balance>,
 
     pub burn_fee: u128,
 
@@ -80,13 +212,293 @@ pub struct Contract {
 
     pub treasury: AccountId,
 
-    pub royalty: u128
+    pub royalty: u128,
+
+    //number of tokens destroyed through burn
+    pub burned_count: u128,
+
+    //holder rewards credited to balances_by_owner and not yet withdrawn
+    pub reward_pool: u128,
+
+    //size limits applied to per-token metadata at mint and update
+    pub metadata_limits: MetadataLimits,
+
+    //unredeemed mint vouchers per account and the funds escrowed for them
+    pub vouchers: LookupMap<AccountId, u64>,
+    pub voucher_escrow: LookupMap<AccountId, VoucherEscrow>,
+    pub vouchers_outstanding: u64,
+    pub total_vouchers_sold: u64,
+    pub redemption_open: bool,
+
+    //holder snapshots taken for external airdrops
+    pub snapshots: UnorderedMap<u64, Snapshot>,
+    pub next_snapshot_id: u64,
+
+    //accounts allowed to withdraw rewards on behalf of a holder
+    pub claim_delegates: LookupMap<AccountId, AccountId>,
+
+    //when set, the collection owner's mint proceeds are released linearly over this schedule
+    pub vesting: Option<VestingSchedule>,
+    pub owner_vesting: VestingLedger,
+
+    //registered affiliates by code, and the share of the owner's amount they earn per mint
+    pub affiliates: UnorderedMap<String, AffiliateInfo>,
+    pub affiliate_bps: u32,
+
+    //per-token data kept next to the standard metadata
+    pub token_extras: LookupMap<TokenId, TokenExtra>,
+
+    //trait types (and optionally values) that minted attributes must follow
+    pub trait_schema: LazyOption<Vec<TraitDefinition>>,
+
+    //live tokens carrying each trait value, keyed by `rarity::trait_key`
+    pub trait_counts: LookupMap<String, u64>,
+    //tokens ordered by the rarity score computed at the last rebuild, and the score each was indexed at
+    pub rarity_index: TreeMap<(u128, TokenId), ()>,
+    pub rarity_index_scores: LookupMap<TokenId, u128>,
+    pub rarity_rebuild_cursor: u64,
+
+    //NEAR paid to the treasury for every combine
+    pub combine_fee: u128,
+
+    //FT cost of each level upgrade, the cost at index `n` takes a token from level `n` to `n + 1`
+    pub level_costs: LazyOption<Vec<u128>>,
+
+    //staked tokens, the number each holder has staked and earns boosted rewards on, and the boost
+    pub staked: LookupMap<TokenId, StakeInfo>,
+    pub staked_counts: LookupMap<AccountId, u64>,
+    pub total_staked: u64,
+    pub stake_multiplier_bps: u32,
+    //how long an unstaked token stays locked before it can be released, in nanoseconds
+    pub unstake_cooldown: u64,
+
+    //holder proposals and the vote each account cast on them
+    pub proposals: UnorderedMap<u64, Proposal>,
+    pub next_proposal_id: u64,
+    pub votes: LookupMap<(u64, AccountId), Vote>,
+    pub governance: GovernanceConfig,
+    //NEAR set aside for treasury spend proposals
+    pub treasury_balance: u128,
+
+    //tokens burned for their physical item, and the treasury credit a rejected redeemer receives
+    pub redemptions: LookupMap<TokenId, Redemption>,
+    pub redemptions_by_status: LookupMap<RedemptionStatus, UnorderedSet<TokenId>>,
+    pub redemption_consolation: u128,
+
+    //custodian allowed to release bridged tokens, the tokens locked for it and the last lock nonce
+    pub bridge_account: Option<AccountId>,
+    pub bridge_locks: UnorderedMap<TokenId, BridgeLock>,
+    pub bridge_nonce: u64,
+
+    //NFTs of other collections held as part of a token
+    pub attachments: UnorderedMap<TokenId, Vec<AttachedNft>>,
+
+    //series minted next to the collection's own tokens, and how many tokens they minted in total
+    pub series: UnorderedMap<u32, Series>,
+    pub series_minted: u64,
+
+    //collection whose holders may mint while set, and when each verified holder's check expires
+    pub mint_pass_contract: Option<AccountId>,
+    pub pass_verifications: LookupMap<AccountId, u64>,
+    pub pass_verification_ttl: u64,
+
+    //account migrations that still have tokens to move, keyed by the account migrating away
+    pub migrations: LookupMap<AccountId, Migration>,
+
+    //when set, mints need a proof signed by this key for the active phase, and each account may
+    //use proofs for at most `allowlist_wallet_limit` mints per phase
+    pub allowlist_signer_pk: Option<PublicKey>,
+    pub allowlist_phase_id: u32,
+    pub allowlist_wallet_limit: u32,
+    pub allowlist_mints: LookupMap<(AccountId, u32), u32>,
+
+    //charity receiving a share of the owner's amount of every mint, in basis points, and its donations
+    pub charity: Option<(AccountId, u16)>,
+    pub charity_frozen: bool,
+    pub charity_total_donated: u128,
+    //donations that failed to arrive, sent again by `retry_charity_donation`
+    pub charity_undelivered: u128,
+
+    //accounts besides the collection owner allowed to write token values
+    pub kv_writers: UnorderedSet<AccountId>,
+
+    //smallest sale balance `nft_transfer_payout` accepts
+    pub min_payout_balance: u128,
+
+    //who may mint, and the accounts allowed to during the presale
+    pub sale_phase: SalePhase,
+    pub presale_allowlist: UnorderedSet<AccountId>,
+
+    //root of a Merkle tree of `sha256(account_id)` leaves allowed to mint
+    pub allowlist_root: Option<[u8; 32]>,
+
+    //tokens an account can mint, and how many each account has minted
+    pub max_mints_per_wallet: Option<u64>,
+    pub mints_by_wallet: LookupMap<AccountId, u64>,
+
+    //stops every mint while set
+    pub mint_paused: bool,
+
+    //`(up_to_index, price)` tiers pricing the collection's own tokens by mint index
+    pub price_tiers: Vec<(u128, u128)>,
+    //falling price overriding both while set
+    pub dutch_auction: Option<DutchAuctionConfig>,
+
+    //tokens the collection owner can mint for free with `owner_mint`, and how many it has
+    pub owner_allocation: u64,
+    pub owner_mints: u64,
+
+    //share of the owner's amount paid to the referrer named on a mint
+    pub referral_bps: u32,
+
+    //tokens each account has minted, kept through transfers and burns
+    pub minted_counts: UnorderedMap<AccountId, u64>,
+
+    //key signing lazy mint vouchers, and the nonces of the vouchers already redeemed
+    pub voucher_signer_pk: Option<PublicKey>,
+    pub used_vouchers: LookupMap<u64, bool>,
+
+    //token ids held for the accounts that reserved them
+    pub reservations: LookupMap<TokenId, Reservation>,
+
+    //tokens one batch mint can create, bounded by the gas of a transaction
+    pub max_mints_per_tx: u64,
+
+    //whether paid mints deploy a vault, free mints never do
+    pub vaults_enabled: bool,
+
+    //whether anyone can mint, or only the owner and the approved minters
+    pub public_minting: bool,
+    pub approved_minters: UnorderedSet<AccountId>,
+
+    //royalties in basis points set at mint, overriding `royalty` in the token's payouts
+    pub token_royalties: LookupMap<TokenId, HashMap<AccountId, u32>>,
+
+    //generates the metadata of minted tokens from the mint index when set
+    pub metadata_template: Option<MetadataTemplate>,
+
+    //token ids left for `nft_mint` to draw at random, see `load_id_pool`
+    pub id_pool: Vector<TokenId>,
+
+    //metadata every token shows until the owner reveals the collection
+    pub revealed: bool,
+    pub placeholder_metadata: LazyOption<TokenMetadata>,
+
+    //ids of burned tokens, never minted again since their vault accounts may still exist
+    pub burned_tokens: UnorderedSet<TokenId>,
+
+    //protocol fee taken off every mint price for the treasury, in basis points
+    pub mint_fee_bps: u128,
+
+    //nanosecond timestamps minting opens and closes at, see `assert_sale_open`
+    pub sale_start: Option<u64>,
+    pub sale_end: Option<u64>,
+
+    //range minters can pick a token's vault split in, None while overrides are disabled
+    pub vault_split_range: Option<(u128, u128)>,
+    //vault split of the tokens minted with an override, instead of their series' split
+    pub token_vault_splits: LookupMap<TokenId, u128>,
+
+    //what a vault is funded with, see `vault_deposit`, and the minimum `storage_deposit`
+    pub near_per_storage: u128,
+    pub vault_storage: u128,
+    pub storage_per_sale: u128,
+
+    //prices the collection's own NEAR mints in USD through an oracle when set
+    pub usd_pricing: Option<UsdPricing>,
+
+    //presale tiers of accounts, and how many tokens each has minted from its tier
+    pub presale_tiers: LookupMap<AccountId, Tier>,
+    pub presale_tier_mints: LookupMap<AccountId, u64>,
+
+    //blocks an account waits between mints, and the block each account last minted at
+    pub mint_cooldown_blocks: u64,
+    pub last_mint_blocks: LookupMap<AccountId, u64>,
+
+    //the collection's raffle, its entrants not drawn yet, its winners and the deposits to refund
+    pub raffle: Option<Raffle>,
+    pub raffle_entries: Vector<AccountId>,
+    pub raffle_winners: UnorderedMap<AccountId, TokenId>,
+    pub raffle_refunds: UnorderedMap<AccountId, u128>,
+
+    //who gets the balance of a burned token's vault when it deletes itself
+    pub vault_beneficiary: VaultBeneficiary,
+
+    //burn fees accrued per unit of reward weight since launch in yoctos, the total weight of the
+    //holders and each holder's accrual when it was last settled
+    pub reward_per_weight: u128,
+    pub total_reward_weight: u128,
+    pub reward_debts: LookupMap<AccountId, u128>,
+
+    //the decaying burn fee, when set, and when each token was minted to decay it from
+    pub burn_fee_schedule: Option<BurnFeeSchedule>,
+    pub minted_at: LookupMap<TokenId, u64>,
+
+    //how long new tokens can't be burned for, and when each locked token can
+    pub burn_lock_duration: u64,
+    pub burn_unlocks_at: LookupMap<TokenId, u64>,
+
+    //ids of tokens the owner burned for violating policy, never minted again
+    pub moderated_tokens: UnorderedSet<TokenId>,
+
+    //whether burn fees are shared per holder or per token, and the weight each holder accrues
+    //with since it was last settled
+    pub reward_mode: RewardMode,
+    pub reward_weights: LookupMap<AccountId, u128>,
+
+    //accounts that hold tokens without sharing in burn fees, e.g. the treasury's unsold inventory
+    pub excluded_from_rewards: UnorderedSet<AccountId>,
+
+    //every burn recorded since, in order, and each burned token's index in it
+    pub burn_records: Vector<BurnRecord>,
+    pub burn_record_indexes: LookupMap<TokenId, u64>,
+
+    //how many tokens of each tier upgrade burns into one of the next
+    pub upgrade_rules: UnorderedMap<u32, UpgradeRule>,
+
+    //tokens burned before the upgrade that started recording burned ids, see `migrate`
+    pub legacy_burned_count: u128,
+    //holders whose reward weight `migrate_reward_weights` has yet to set, counted from the start
+    //of `holders`, None once every holder accrues
+    pub reward_weight_backfill: Option<u64>,
+
+    //currency the vault of each token minted in another than the base currency was funded in
+    pub vault_currencies: LookupMap<TokenId, Option<AccountId>>,
+    //what the mint of each token with a vault locked in it, at the price actually paid
+    pub vault_amounts: LookupMap<TokenId, u128>,
+    //when the last open proposal each voter voted on ends, its tokens can't move until then
+    pub vote_locks: LookupMap<AccountId, u64>,
+    //paid out of `treasury_balance` to whoever runs `migrate_reward_weights`, per full page
+    pub processor_bounty: u128
 }
 
-const NEAR_PER_STORAGE: u128 = 10_000_000_000_000_000_000;
+//initial funding values, until the owner changes them
+const DEFAULT_NEAR_PER_STORAGE: u128 = 10_000_000_000_000_000_000;
 //the minimum storage to have a sale on the contract.
-const STORAGE_PER_SALE: u128 = 1000 * NEAR_PER_STORAGE;
-const VAULT_STORAGE: u128 = 19_800_000_000_000_000_000_000;
+const DEFAULT_STORAGE_PER_SALE: u128 = 1000 * DEFAULT_NEAR_PER_STORAGE;
+const DEFAULT_VAULT_STORAGE: u128 = 19_800_000_000_000_000_000_000;
+//highest protocol fee on a mint, in basis points
+const MAX_MINT_FEE_BPS: u128 = 1_000;
+//the vault contract deployed for every minted token
+const VAULT_CODE: &[u8] = include_bytes!("./vault/vault.wasm");
+//gas for `resolve_mint` to hand the token back once the vault is funded
+const GAS_FOR_RESOLVE_MINT: Gas = Gas::from_tgas(10);
+//gas for `resolve_withdraw` to credit back a failed reward withdrawal
+const GAS_FOR_RESOLVE_WITHDRAW: Gas = Gas::from_tgas(10);
+
+//caps on how many entries a single call may iterate, so growth of a collection can't brick a method
+//the page size used by paginated views when no limit is given, and the largest page they return
+pub const DEFAULT_PAGE_LIMIT: u64 = 50;
+pub const MAX_PAGE_LIMIT: u64 = 200;
+//the window check_invariants covers when no limit is given, and the largest it covers
+pub const DEFAULT_INVARIANTS_LIMIT: u64 = 100;
+pub const MAX_INVARIANTS_LIMIT: u64 = 500;
+//the most holders recorded into a snapshot per call
+pub const SNAPSHOT_PAGE_SIZE: u64 = 200;
+//the most affiliates that can be registered, which bounds the leaderboard sort
+pub const MAX_AFFILIATES: u64 = 1_000;
+//the most tokens a single migrate_account call transfers
+pub const MIGRATION_BATCH_SIZE: u64 = 50;
 
 #[derive(BorshSerialize, BorshStorageKey)]
 #[borsh(crate = "near_sdk::borsh")]
@@ -100,8 +512,66 @@ enum StorageKey {
     FTDeposits,
     BalancesByOwner,
     Holders,
+    Vouchers,
+    VoucherEscrow,
+    Snapshots,
+    SnapshotEntries { snapshot_id: u64 },
+    ClaimDelegates,
+    Affiliates,
+    TokenExtras,
+    TraitSchema,
+    TraitCounts,
+    RarityIndex,
+    RarityIndexScores,
+    LevelCosts,
+    Staked,
+    StakedCounts,
+    Proposals,
+    Votes,
+    Redemptions,
+    RedemptionStatuses,
+    RedemptionsByStatus { status: RedemptionStatus },
+    BridgeLocks,
+    Attachments,
+    Series,
+    PassVerifications,
+    Migrations,
+    AllowlistMints,
+    KvWriters,
+    PresaleAllowlist,
+    MintsByWallet,
+    MintedCounts,
+    UsedVouchers,
+    Reservations,
+    MintCurrencies,
+    ApprovedMinters,
+    TokenRoyalties,
+    IdPool,
+    PlaceholderMetadata,
+    BurnedTokens,
+    TokenVaultSplits,
+    PresaleTiers,
+    PresaleTierMints,
+    LastMintBlocks,
+    RaffleEntries,
+    RaffleWinners,
+    RaffleRefunds,
+    RewardDebts,
+    MintedAt,
+    BurnUnlocksAt,
+    ModeratedTokens,
+    RewardWeights,
+    ExcludedFromRewards,
+    BurnRecords,
+    BurnRecordIndexes,
+    UpgradeRules,
+    VaultCurrencies,
+    VaultAmounts,
+    VoteLocks,
 }
 
+// The init and mint methods take their JSON arguments flat, one parameter each
+#[allow(clippy::too_many_arguments)]
 #[near_bindgen]
 impl Contract {
     #[init]
@@ -114,124 +584,205 @@ impl Contract {
         total_supply: U128,
         burn_fee: U128,
         treasury: AccountId,
-        royalty: U128
+        royalty: U128,
+        metadata_limits: Option<MetadataLimits>,
+        vesting: Option<VestingSchedule>,
+        charity: Option<(AccountId, u16)>,
+        max_mints_per_wallet: Option<U64>,
+        sale_start: Option<U64>,
+        sale_end: Option<U64>,
+        owner_allocation: Option<U64>,
+        mint_fee_bps: Option<U128>
     ) -> Self {
         require!(!env::state_exists(), "Already initialized");
         metadata.assert_valid();
-        Self {
-            tokens: NonFungibleToken::new(
+        require!(
+            mint_fee_bps.map_or(0, |bps| bps.0) <= MAX_MINT_FEE_BPS,
+            format!("The mint fee can't exceed {} basis points", MAX_MINT_FEE_BPS)
+        );
+        assert_valid_charity(&charity);
+        assert_valid_sale_window(sale_start, sale_end);
+        if let Some(vesting) = &vesting {
+            require!(vesting.duration.0 > 0, "Vesting duration must be positive");
+        }
+        Self::initial_state(
+            NonFungibleToken::new(
                 StorageKey::NonFungibleToken,
                 owner_id,
                 Some(StorageKey::TokenMetadata),
                 Some(StorageKey::Enumeration),
                 Some(StorageKey::Approval),
             ),
-            metadata: LazyOption::new(StorageKey::Metadata, Some(&metadata)),
-            index: 0,
-            total_supply: total_supply.0,
-            mint_price: mint_price.0,
+            LazyOption::new(StorageKey::Metadata, Some(&metadata)),
+            mint_price,
             mint_currency,
-            payment_split_percent: payment_split_percent.0,
-            storage_deposits: LookupMap::new(StorageKey::StorageDeposits),
-            ft_deposits: LookupMap::new(StorageKey::FTDeposits),
-            burn_fee: burn_fee.0,
-            balances_by_owner: LookupMap::new(StorageKey::BalancesByOwner),
-            holders: UnorderedSet::new(StorageKey::Holders),
-            treasury: treasury,
-            royalty: royalty.0
-        }
+            payment_split_percent,
+            total_supply,
+            burn_fee,
+            treasury,
+            royalty,
+            metadata_limits,
+            vesting,
+            charity,
+            max_mints_per_wallet,
+            sale_start,
+            sale_end,
+            owner_allocation,
+            mint_fee_bps,
+        )
     }
 
     /// Mint a new token with ID=`token_id` belonging to `token_owner_id`.
     ///
     /// Since this example implements metadata, it also requires per-token metadata to be provided
     /// in this call. `self.tokens.mint` will also require it to be Some, since
-    /// `StorageKey::TokenMetadata` was provided at initialization.
+    /// `StorageKey::TokenMetadata` was provided at initialization. While a metadata template is
+    /// set, the given metadata is ignored and generated from the mint index instead.
     ///
     /// `self.tokens.mint` will enforce `predecessor_account_id` to equal the `owner_id` given in
     /// initialization call to `new`.
+    ///
+    /// Tokens of a series other than 0 are numbered by the contract, so `token_id` is only given
+    /// when minting the collection's own tokens. Without one, the token is named after the mint
+    /// index, e.g. `"42"`, and its vault is `42.{contract}`.
+    ///
+    /// Only the collection owner can set `transfer_locked_until`, e.g. for team allocations. The
+    /// token can't be transferred, listed or burned before that timestamp, and the lock can't be
+    /// changed afterwards.
+    ///
+    /// The sale window and phase decide who can mint, see `assert_sale_open`. During the presale,
+    /// an account with a tier pays the tier's price and uses up its quota, see `set_tier`. While
+    /// an allowlist signer is set, `allowlist_proof` must also be its signature for the caller and
+    /// the active phase, see `internal_use_allowlist_proof`. While an allowlist root is set,
+    /// `merkle_proof` must show the caller is in its tree.
+    ///
+    /// A `referrer` other than the caller earns `referral_bps` of the owner's amount.
+    ///
+    /// The caller pays for the mint and `token_owner_id` receives it: the token owner joins the
+    /// holders and is charged against `max_mints_per_wallet`, while the caller keeps the minter's
+    /// credit, e.g. for affiliates and referrals.
+    ///
+    /// `split_percent_override` locks that percent of the price in the token's vault instead of the
+    /// series' split, within the range the owner allows, see `set_vault_split_range`. Burning the
+    /// token shares its fee by the same split.
+    ///
+    /// `pay_with` picks the FT the price is paid in from the caller's deposits, see
+    /// `mint_currencies`, or NEAR from the attached deposit when `None`. A free mint, or any mint
+    /// while vaults are disabled, deploys no vault and only pays for the token's storage.
+    ///
+    /// While the price is pegged to USD, a NEAR mint of the collection's own tokens first asks the
+    /// oracle for the NEAR price and mints in `resolve_usd_mint`, see `UsdPricing`. The call then
+    /// returns the token, or `null` when the deposit didn't cover the price and was refunded.
+    ///
+    /// A mint with a vault only returns the token once `resolve_create` has funded the vault, see
+    /// `resolve_mint`, so a calling contract can await it. `nft_mint_eager` returns it right away.
     #[payable]
     pub fn nft_mint(
         &mut self,
-        token_id: TokenId,
+        token_id: Option<TokenId>,
         token_owner_id: AccountId,
         token_metadata: TokenMetadata,
-    ) -> Token {
-        let collection_owner = &self.tokens.owner_id;
-        let owner = env::predecessor_account_id(); 
-        self.holders.insert(&owner);
-        // assert_eq!(owner, self.tokens.owner_id, "Unauthorized");
-
-        let code = include_bytes!("./vault/vault.wasm").to_vec();
-        let contract_bytes = code.len() as u128;
-        let minimum_needed = NEAR_PER_STORAGE * contract_bytes + VAULT_STORAGE;
+        affiliate_code: Option<String>,
+        attributes: Option<Vec<TraitAttribute>>,
+        series_id: Option<u32>,
+        transfer_locked_until: Option<U64>,
+        allowlist_proof: Option<Base64VecU8>,
+        merkle_proof: Option<Vec<[u8; 32]>>,
+        referrer: Option<AccountId>,
+        pay_with: Option<AccountId>,
+        perpetual_royalties: Option<HashMap<AccountId, u32>>,
+        split_percent_override: Option<U128>,
+    ) -> PromiseOrValue<Token> {
+        let args = MintArgs {
+            token_id,
+            token_owner_id,
+            token_metadata,
+            affiliate_code,
+            attributes,
+            series_id,
+            transfer_locked_until,
+            allowlist_proof,
+            merkle_proof,
+            referrer,
+            perpetual_royalties,
+            split_percent_override,
+        };
+        self.internal_mint_call(args, pay_with, false)
+    }
 
-        let deposit: u128 = env::attached_deposit().as_yoctonear();
-        if let Some(_) = self.mint_currency.clone() {
-            let amount = self.ft_deposits_of(owner.clone());
-            require!(deposit >= minimum_needed && amount >= self.mint_price, "Insufficient price to mint");
-        } else {
-            require!(deposit >= self.mint_price + minimum_needed, "Insufficient price to mint");
-        }
+    /// `nft_mint`, returning the token as soon as it is recorded while its vault is still being
+    /// deployed. A failed deployment then rolls the mint back after the call has returned.
+    #[payable]
+    pub fn nft_mint_eager(
+        &mut self,
+        token_id: Option<TokenId>,
+        token_owner_id: AccountId,
+        token_metadata: TokenMetadata,
+        affiliate_code: Option<String>,
+        attributes: Option<Vec<TraitAttribute>>,
+        series_id: Option<u32>,
+        transfer_locked_until: Option<U64>,
+        allowlist_proof: Option<Base64VecU8>,
+        merkle_proof: Option<Vec<[u8; 32]>>,
+        referrer: Option<AccountId>,
+        pay_with: Option<AccountId>,
+        perpetual_royalties: Option<HashMap<AccountId, u32>>,
+        split_percent_override: Option<U128>,
+    ) -> PromiseOrValue<Token> {
+        let args = MintArgs {
+            token_id,
+            token_owner_id,
+            token_metadata,
+            affiliate_code,
+            attributes,
+            series_id,
+            transfer_locked_until,
+            allowlist_proof,
+            merkle_proof,
+            referrer,
+            perpetual_royalties,
+            split_percent_override,
+        };
+        self.internal_mint_call(args, pay_with, true)
+    }
 
-        let current_id = env::current_account_id();
-
-        let vault_amount = self.mint_price.checked_mul(self.payment_split_percent)
-            .unwrap().checked_div(100u128).unwrap();
-
-        let owner_amount = self.mint_price.checked_sub(vault_amount).unwrap();
-
-        // Deploy the vault contract
-        let vault_account_id: AccountId = format!("{}.{}", token_id, current_id).parse().unwrap();
-        Promise::new(vault_account_id.clone())
-            .create_account()
-            .deploy_contract(code)
-            .transfer(NearToken::from_yoctonear(minimum_needed))
-            .function_call(
-                // Init the vault contract
-                "init".to_string(),
-                if let Some(ft_id) = self.mint_currency.clone() {
-                    json!({
-                        "ft_contract": ft_id.to_string(),
-                        "treasury": self.treasury.to_string()
-                    })
-                } else {
-                    json!({
-                        "treasury": self.treasury.to_string()
-                    })
-                }.to_string().into_bytes().to_vec(),
-                NearToken::from_millinear(0),
-                Gas::from_tgas(20)
-            )
-            .then(
-                Self::ext(env::current_account_id())
-                .with_static_gas(Gas::from_tgas(150))
-                .resolve_create(
-                    vault_account_id,
-                    collection_owner,
-                    owner_amount,
-                    vault_amount
-                )
-            );
-        self.index = self.index.checked_add(1).unwrap();
-        if self.total_supply > 0 {
-            require!(self.total_supply >= self.index, "Exceeded total supply");
+    /// Hand `token` back to the `nft_mint` caller once its vault is live. Fails when
+    /// `resolve_create` rolled the mint back or the vault deposit failed.
+    #[private]
+    pub fn resolve_mint(&mut self, token: Token) -> Token {
+        match env::promise_result(0) {
+            PromiseResult::Successful(result) if result == b"false" => env::panic_str(&format!(
+                "The vault of token {} could not be created, the mint was rolled back",
+                token.token_id
+            )),
+            PromiseResult::Successful(_) => token,
+            PromiseResult::Failed => env::panic_str(&format!(
+                "The deposit into the vault of token {} failed",
+                token.token_id
+            )),
         }
-
-        let token = self.tokens.internal_mint_with_refund(token_id, token_owner_id, Some(token_metadata), None);
-        NftMint { owner_id: &token.owner_id, token_ids: &[&token.token_id], memo: None }.emit();
-        token
     }
+
+    /// Pay out the `proceeds` of minting `token_id` once its vault was created, then fund the vault
+    /// with its share. Rolls the mint back when the vault could not be created.
     #[private]
     pub fn resolve_create(
         &mut self,
-        vault_account_id:AccountId,
-        collection_owner:&AccountId,
-        owner_amount: u128,
-        vault_amount: u128
-    ) -> Promise {
+        vault_account_id: AccountId,
+        collection_owner: AccountId,
+        token_id: TokenId,
+        proceeds: MintProceeds,
+    ) -> PromiseOrValue<bool> {
+        let MintProceeds { owner_amount, vault_amount, charity_amount, mint_fee, payouts, minter, near_paid, ft_paid, currency, .. } = proceeds;
+        if !matches!(env::promise_result(0), PromiseResult::Successful(_)) {
+            // Nothing was paid out yet, and the vault deposit came back with the failed receipt
+            self.internal_rollback_mint(&token_id, &minter, near_paid, ft_paid, &currency);
+            return PromiseOrValue::Value(false);
+        }
+        self.internal_pay_mint_shares(&currency, &collection_owner, owner_amount, charity_amount, mint_fee, &payouts);
+
         // Deposit ft or near
-        if let Some(ft_id) = self.mint_currency.clone() {
+        if let Some(ft_id) = currency.clone() {
             Promise::new(ft_id.clone()).function_call(
                 "storage_deposit".to_string(), 
                 json!({
@@ -240,7 +791,7 @@ impl Contract {
                 NearToken::from_millinear(100), 
                 Gas::from_tgas(20)
             );
-            Promise::new(ft_id.clone()).function_call(
+            let vault_transfer = Promise::new(ft_id.clone()).function_call(
                 "ft_transfer_call".to_string(), 
                 json!({
                     "receiver_id": vault_account_id.to_string(),
@@ -250,24 +801,14 @@ impl Contract {
                 NearToken::from_yoctonear(1),
                 Gas::from_tgas(50),
             );
-            Promise::new(ft_id.clone()).function_call(
-                "ft_transfer".to_string(), 
-                json!({
-                    "receiver_id": collection_owner.clone().to_string(),
-                    "amount": owner_amount.to_string(),
-                    "msg": "",
-                }).to_string().into_bytes().to_vec(),
-                NearToken::from_yoctonear(1),
-                Gas::from_tgas(50),
-            )
+            PromiseOrValue::Promise(vault_transfer)
         } else {
-            Promise::new(collection_owner.clone()).transfer(NearToken::from_yoctonear(owner_amount));
-            Promise::new(vault_account_id.clone()).function_call(
+            PromiseOrValue::Promise(Promise::new(vault_account_id.clone()).function_call(
                 "deposit_near".to_string(),
                 json!({}).to_string().into_bytes().to_vec(),
                 NearToken::from_yoctonear(vault_amount),
                 Gas::from_tgas(20),
-            )
+            ))
         }
     }
     //Allows users to deposit storage. This is to cover the cost of storing sale objects on the contract
@@ -286,9 +827,9 @@ impl Contract {
 
         //make sure the deposit is greater than or equal to the minimum storage for a sale
         assert!(
-            deposit >= STORAGE_PER_SALE,
+            deposit >= self.storage_per_sale,
             "Requires minimum deposit of {}",
-            STORAGE_PER_SALE
+            self.storage_per_sale
         );
 
         //get the balance of the account (if the account isn't in the map we default to a balance of 0)
@@ -299,115 +840,135 @@ impl Contract {
         self.storage_deposits.insert(&storage_account_id, &balance);
     }
 
-    // Burn an NFT by its token ID
+    /// Burn an NFT by its token ID. An account approved on the token can burn it for its owner,
+    /// with `approval_id` checked as in `nft_transfer`, and the vault is still withdrawn to the
+    /// owner. Requires exactly one yoctoNEAR, which travels with the vault withdrawal, or is
+    /// refunded to the caller when the token has no vault. Returns what was burned and the payout
+    /// expected from the vault.
     #[payable]
-    pub fn burn(&mut self, token_id: TokenId) {
-        let owner = env::predecessor_account_id();
-
-        let token_owner = self.tokens.owner_by_id.get(&token_id).unwrap();
-
-        assert_eq!(owner.clone(), token_owner, "You don't own this NFT");
-
-        // Remove the NFT from the owner's account
-        self.tokens.owner_by_id.remove(&token_id);
-
-        // Remove token metadata (if applicable)
-        self.tokens
-            .token_metadata_by_id
-            .as_mut()
-            .and_then(|by_id| by_id.remove(&token_id));
-        
-        // Remove the NFT from the tokens_per_owner map
-        let mut removed = false;
-        if let Some(tokens_per_owner) = &mut self.tokens.tokens_per_owner {
-            let mut owner_tokens = tokens_per_owner.get(&owner).unwrap_or_else(|| {
-                env::panic_str("Unable to access tokens per owner in unguarded call.")
-            });
-            owner_tokens.remove(&token_id);
-            if owner_tokens.is_empty() {
-                tokens_per_owner.remove(&owner);
-                self.holders.remove(&owner);
-                removed = true;
-            } else {
-                tokens_per_owner.insert(&owner, &owner_tokens);
-            }
-        }
-        
-        // Remove any approvals associated with this NFT
-        self.tokens
-            .approvals_by_id
-            .as_mut()
-            .and_then(|by_id| by_id.remove(&token_id.clone()));
-
-        // Remove next approval ID (if applicable)
-        self.tokens
-            .next_approval_id_by_id
-            .as_mut()
-            .and_then(|by_id| by_id.remove(&token_id.clone()));
-
-        // Update Balance for holders
-        let mut holders_count: u128 = self.holders.len() as u128;
-        if removed == false {
-            holders_count -= 1;
+    pub fn burn(&mut self, token_id: TokenId, approval_id: Option<u64>) -> BurnReceipt {
+        assert_one_yocto();
+        let caller = env::predecessor_account_id();
+        let owner = self.tokens.owner_by_id.get(&token_id)
+            .unwrap_or_else(|| env::panic_str("Token not found"));
+        self.assert_burner(&token_id, &owner, &caller, approval_id);
+        let authorized_id = Some(&caller).filter(|caller| *caller != &owner);
+        let (receipt, withdrawal) = self.internal_burn(&token_id, &owner, authorized_id, None);
+        if withdrawal.is_none() {
+            Promise::new(caller).transfer(NearToken::from_yoctonear(1));
         }
-        let amount_to_holder: u128 = if holders_count == 0 {
-            0u128
-        } else { 
-            self.mint_price
-                .checked_mul(self.payment_split_percent).unwrap()
-                .checked_mul(self.burn_fee).unwrap()
-                .checked_div(20000u128).unwrap()
-                .checked_div(holders_count).unwrap()
-        };
-
-        env::log_str(&format!("Total holders count: {}", holders_count));
-        env::log_str(&format!("Amount to each holder: {}", amount_to_holder));
+        receipt
+    }
 
-        for other in self.holders.iter() {
-            if other != owner {
-                let mut balance = self.balances_by_owner.get(&other).unwrap_or(0);
-                balance = balance.checked_add(amount_to_holder).unwrap();
-                self.balances_by_owner.insert(&other, &balance);
-            }
+    /// Credit the holders the burn fees of `burned` tokens whose vault withdrawal succeeded, in the
+    /// order the withdrawals were joined, each emitting `burn_settled` with the amount its vault
+    /// reported withdrawing, or the expected one for vaults that don't report it. Every fee is
+    /// shared on its own, so burning tokens together credits exactly what burning them one by one
+    /// would. The holders' share of a vault funded in another currency than the base one is sent
+    /// to the treasury instead, as its `remainder`. A failed withdrawal left the funds in the
+    /// vault, so its fee is not credited and the failure is logged for the owner to recover.
+    #[private]
+    pub fn resolve_burn(&mut self, owner: AccountId, burned: Vec<BurnedVault>) {
+        for (i, burned) in burned.into_iter().enumerate() {
+            let vault_amount = match env::promise_result(i as u64) {
+                PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<U128>(&value)
+                    .map_or(burned.vault_amount.0, |amount| amount.0),
+                PromiseResult::Failed => {
+                    let (_, to_holders) = split_burn_fee(burned.vault_amount.0, burned.burn_fee_bps as u128);
+                    env::log_str(&format!(
+                        "Withdrawal from the vault of token {} failed, its burn fee of {} was not credited",
+                        burned.token_id, to_holders
+                    ));
+                    self.internal_clear_burn_payout(&burned.token_id);
+                    continue;
+                }
+            };
+            let (burn_fee, to_holders) = split_burn_fee(vault_amount, burned.burn_fee_bps as u128);
+            // Rewards are paid out in the base currency, so a share in another goes to the treasury
+            let (per_weight, remainder) = if burned.currency == self.base_currency {
+                self.internal_distribute_rewards(&owner, to_holders)
+            } else {
+                if to_holders > 0 {
+                    self.internal_send_funds(&burned.currency, &self.treasury, to_holders);
+                }
+                (0, to_holders)
+            };
+            Event::BurnSettled {
+                token_id: &burned.token_id,
+                owner_id: &owner,
+                vault_amount: U128(vault_amount),
+                fee_retained: U128(burn_fee - to_holders),
+                holder_pool: U128(to_holders),
+                per_holder: U128(per_weight * BASE_REWARD_WEIGHT),
+                remainder: U128(remainder),
+            }.emit();
         }
-
-        let current_id = env::current_account_id();
-        let vault_account_id: AccountId = format!("{}.{}", token_id, current_id).parse().unwrap();
-
-        Promise::new(vault_account_id.clone()).function_call(
-            "withdraw".to_string(),
-            json!({
-                "owner": owner.to_string(),
-                "burn_fee": self.burn_fee.to_string(),
-            }).to_string().into_bytes().to_vec(),
-            NearToken::from_yoctonear(1),
-            Gas::from_tgas(100)
-        );
     }
 
+    /// Pay out the caller's accrued holder rewards. A claim delegate can withdraw for the holder
+    /// that set it with `on_behalf_of`, the rewards are still paid to that holder. The rewards
+    /// leave the balance while the transfer is in flight, and are credited back by
+    /// `resolve_withdraw` if it fails.
     #[payable]
-    pub fn withdraw(&mut self) {
-        let owner = env::predecessor_account_id();
+    pub fn withdraw(&mut self, on_behalf_of: Option<AccountId>) {
+        let owner = if let Some(holder) = on_behalf_of {
+            require!(
+                self.claim_delegates.get(&holder) == Some(env::predecessor_account_id()),
+                format!("Not the claim delegate of {}", holder)
+            );
+            holder
+        } else {
+            env::predecessor_account_id()
+        };
+        self.internal_update_reward_weight(&owner);
         let balance: u128 = self.balances_by_owner.get(&owner).unwrap_or(0);
 
         if balance > 0 {
+            self.balances_by_owner.insert(&owner, &0u128);
+            self.reward_pool = self.reward_pool.saturating_sub(balance);
             // Deposit ft or near
-            if let Some(ft_id) = self.mint_currency.clone() {
-                Promise::new(ft_id.clone()).function_call(
-                    "ft_transfer".to_string(), 
-                    json!({
-                        "receiver_id": owner.to_string(),
-                        "amount": balance.to_string(),
-                    }).to_string().into_bytes().to_vec(),
-                    NearToken::from_yoctonear(1),
-                    Gas::from_tgas(20),
-                );
-            } else {
-                Promise::new(owner.clone()).transfer(NearToken::from_yoctonear(balance));
-            }
+            self.internal_send_funds(&self.base_currency, &owner, balance).then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_WITHDRAW)
+                    .resolve_withdraw(owner, U128(balance))
+            );
+        }
+    }
 
-            self.balances_by_owner.insert(&owner, &0u128).unwrap();
+    /// Credit `amount` back to the rewards of `account_id` if its withdrawal failed, e.g. to an
+    /// account not registered with the FT. Returns whether the withdrawal went through.
+    #[private]
+    pub fn resolve_withdraw(&mut self, account_id: AccountId, amount: U128) -> bool {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return true;
         }
+        let balance = self.balances_by_owner.get(&account_id).unwrap_or(0);
+        self.balances_by_owner.insert(&account_id, &balance.checked_add(amount.0).unwrap());
+        self.reward_pool = self.reward_pool.checked_add(amount.0).unwrap();
+        env::log_str(&format!(
+            "Withdrawal of {} to {} failed, the rewards were credited back",
+            amount.0, account_id
+        ));
+        false
+    }
+
+    /// Let `delegate` withdraw the caller's rewards on their behalf, replacing any previous one.
+    #[payable]
+    pub fn set_claim_delegate(&mut self, delegate: AccountId) {
+        assert_one_yocto();
+        let holder = env::predecessor_account_id();
+        require!(delegate != holder, "Can't delegate to yourself");
+        self.claim_delegates.insert(&holder, &delegate);
+    }
+
+    #[payable]
+    pub fn clear_claim_delegate(&mut self) {
+        assert_one_yocto();
+        self.claim_delegates.remove(&env::predecessor_account_id());
+    }
+
+    pub fn claim_delegate_of(&self, account_id: AccountId) -> Option<AccountId> {
+        self.claim_delegates.get(&account_id)
     }
 
     #[payable]
@@ -421,49 +982,46 @@ impl Contract {
         assert_one_yocto();
         let previous_owner_id =
             self.tokens.owner_by_id.get(&token_id).unwrap_or_else(|| env::panic_str("Token not found"));
-        if let Some(tokens_per_owner) = &mut self.tokens.tokens_per_owner {
-            let sender_tokens = tokens_per_owner.get(&previous_owner_id).unwrap_or_else(|| {
-                env::panic_str("Unable to access tokens per owner in unguarded call.")
-            });
-            if sender_tokens.len()==1 {
-                self.holders.remove(&previous_owner_id);
-            };
-            let receiver_tokens = tokens_per_owner.get(&receiver_id);
-            if receiver_tokens.is_none() {
-                self.holders.insert(&receiver_id);
-            } else {
-                let receiver_tokens = receiver_tokens.unwrap();
-                if receiver_tokens.len() == 0 {
-                    self.holders.insert(&receiver_id);
-                }
-            }
-        }
-        self.tokens.nft_transfer(receiver_id, token_id, approval_id, None);
+        self.assert_transferable(&token_id);
+        // Computed before the transfer so a sale that would pay nobody fails with the token in place
+        let payout = balance.map(|balance| self.internal_payout(&token_id, &previous_owner_id, balance.0));
+        self.internal_update_holders(&previous_owner_id, &receiver_id);
+        self.tokens.nft_transfer(receiver_id.clone(), token_id, approval_id, None);
+        self.internal_update_reward_weights(&previous_owner_id, &receiver_id);
+        payout
+    }
 
-        let payout = if let Some(balance) = balance {
-            let balance_u128: u128 = u128::from(balance);
-            let mut payout: Payout = Payout {
-                payout: HashMap::new(),
-            };
-            payout.payout.insert(self.tokens.owner_id.clone(), royalty_to_payout(self.royalty, balance_u128));
-            payout.payout.insert(previous_owner_id, royalty_to_payout(10000-self.royalty, balance_u128));
-            Some(payout)
-        } else {
-            None
-        };
+    /// How a sale of `token_id` for `balance` would be split between its royalties and the
+    /// token's owner.
+    pub fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: Option<u32>) -> Payout {
+        let owner_id =
+            self.tokens.owner_by_id.get(&token_id).unwrap_or_else(|| env::panic_str("Token not found"));
+        let payout = self.internal_payout(&token_id, &owner_id, balance.0);
+        if let Some(max_len_payout) = max_len_payout {
+            require!(payout.payout.len() <= max_len_payout as usize, "Payout exceeds max_len_payout");
+        }
         payout
     }
+
+    /// Refuse sales for less than this, so a tiny balance can't give a token away for nothing.
+    pub fn set_min_payout_balance(&mut self, min_payout_balance: U128) {
+        self.assert_owner();
+        self.min_payout_balance = min_payout_balance.0;
+    }
     //return how much storage an account has paid for
     pub fn storage_balance_of(&self, account_id: AccountId) -> U128 {
         U128(self.storage_deposits.get(&account_id).unwrap_or(0))
     }
 
-    /// Get the amount of FTs the user has deposited into the contract
+    /// Get the amount of FTs the user has deposited into the contract, in `ft_contract` or by
+    /// default the base currency
     pub fn ft_deposits_of(
         &self,
-        account_id: AccountId
+        account_id: AccountId,
+        ft_contract: Option<AccountId>
     ) -> u128 {
-        self.ft_deposits.get(&account_id).unwrap_or(0)
+        ft_contract.or_else(|| self.base_currency.clone())
+            .map_or(0, |ft_contract| self.ft_deposit(&account_id, &ft_contract))
     }
 
     pub fn index(&self) -> u128 {
@@ -474,13 +1032,64 @@ impl Contract {
         self.total_supply
     }
 
+    /// Holder rewards `owner` can withdraw, including what it was credited before it was
+    /// excluded from rewards and the treasury's reward dust.
     pub fn balance_of(&self, owner: AccountId) -> u128 {
-        self.balances_by_owner.get(&owner).unwrap_or(0)
+        self.balances_by_owner.get(&owner).unwrap_or(0) + self.pending_rewards(&owner)
     }
 
     pub fn total_holders(&self) -> u64 {
         self.holders.len()
     }
+
+    /// Share of every mint price sent to the treasury, in basis points.
+    pub fn mint_fee_bps(&self) -> U128 {
+        U128(self.mint_fee_bps)
+    }
+
+    /// How many tokens have been burned, before the upgrade that started recording them included.
+    pub fn burned_supply(&self) -> U64 {
+        U64(self.burned_tokens.len() + self.legacy_burned_count as u64)
+    }
+
+    /// How many tokens have been minted, burned ones included: the mint index.
+    pub fn minted(&self) -> U128 {
+        U128(self.index)
+    }
+
+    /// How many minted tokens have not been burned.
+    pub fn circulating_supply(&self) -> U128 {
+        U128(self.index - self.burned_count)
+    }
+
+    /// How many more of the collection's own tokens can be minted, see `unsold_remaining`, or
+    /// `"unlimited"` without a total supply.
+    pub fn mintable_remaining(&self) -> String {
+        if self.total_supply == 0 {
+            return "unlimited".to_string();
+        }
+        self.unsold_remaining().0.to_string()
+    }
+
+    pub fn is_burned(&self, token_id: TokenId) -> bool {
+        self.burned_tokens.contains(&token_id)
+    }
+
+    pub fn get_config(&self) -> ConfigInfo {
+        ConfigInfo {
+            owner_id: self.tokens.owner_id.clone(),
+            treasury: self.treasury.clone(),
+            mint_price: U128(self.mint_price),
+            base_currency: self.base_currency.clone(),
+            payment_split_percent: U128(self.payment_split_percent),
+            total_supply: U128(self.total_supply),
+            burn_fee: U128(self.burn_fee),
+            royalty: U128(self.royalty),
+            metadata_limits: self.metadata_limits.clone(),
+            combine_fee: U128(self.combine_fee),
+            min_payout_balance: U128(self.min_payout_balance)
+        }
+    }
 }
 
 #[near_bindgen]
@@ -495,24 +1104,10 @@ impl NonFungibleTokenCore for Contract {
     ) {
         let owner_id =
             self.tokens.owner_by_id.get(&token_id).unwrap_or_else(|| env::panic_str("Token not found"));
-        if let Some(tokens_per_owner) = &mut self.tokens.tokens_per_owner {
-            let sender_tokens = tokens_per_owner.get(&owner_id).unwrap_or_else(|| {
-                env::panic_str("Unable to access tokens per owner in unguarded call.")
-            });
-            if sender_tokens.len()==1 {
-                self.holders.remove(&owner_id);
-            };
-            let receiver_tokens = tokens_per_owner.get(&receiver_id);
-            if receiver_tokens.is_none() {
-                self.holders.insert(&receiver_id);
-            } else {
-                let receiver_tokens = receiver_tokens.unwrap();
-                if receiver_tokens.len() == 0 {
-                    self.holders.insert(&receiver_id);
-                }
-            }
-        }
-        self.tokens.nft_transfer(receiver_id, token_id, approval_id, memo);
+        self.assert_transferable(&token_id);
+        self.internal_update_holders(&owner_id, &receiver_id);
+        self.tokens.nft_transfer(receiver_id.clone(), token_id, approval_id, memo);
+        self.internal_update_reward_weights(&owner_id, &receiver_id);
     }
 
     #[payable]
@@ -526,35 +1121,247 @@ impl NonFungibleTokenCore for Contract {
     ) -> PromiseOrValue<bool> {
         let owner_id =
             self.tokens.owner_by_id.get(&token_id).unwrap_or_else(|| env::panic_str("Token not found"));
-        if let Some(tokens_per_owner) = &mut self.tokens.tokens_per_owner {
-            let sender_tokens = tokens_per_owner.get(&owner_id).unwrap_or_else(|| {
-                env::panic_str("Unable to access tokens per owner in unguarded call.")
-            });
-            if sender_tokens.len()==1 {
-                self.holders.remove(&owner_id);
-            };
-            let receiver_tokens = tokens_per_owner.get(&receiver_id);
-            if receiver_tokens.is_none() {
-                self.holders.insert(&receiver_id);
-            } else {
-                let receiver_tokens = receiver_tokens.unwrap();
-                if receiver_tokens.len() == 0 {
-                    self.holders.insert(&receiver_id);
-                }
-            }
+        self.assert_transferable(&token_id);
+        // A token sent to this contract is only held until it is burned or returned, so its owner
+        // stays a holder meanwhile
+        let to_self = receiver_id == env::current_account_id();
+        if !to_self {
+            self.internal_update_holders(&owner_id, &receiver_id);
         }
-        self.tokens.nft_transfer_call(receiver_id, token_id, approval_id, memo, msg)
+        let transferred = self.tokens.nft_transfer_call(receiver_id.clone(), token_id, approval_id, memo, msg);
+        if !to_self {
+            self.internal_update_reward_weights(&owner_id, &receiver_id);
+        }
+        transferred
     }
 
     fn nft_token(&self, token_id: TokenId) -> Option<Token> {
-        self.tokens.nft_token(token_id)
+        self.tokens.nft_token(token_id).map(|token| self.displayed_token(token))
     }
 }
 
-fn royalty_to_payout(a: u128, b: Balance) -> U128 {
+pub(crate) fn royalty_to_payout(a: u128, b: Balance) -> U128 {
     U128(a as u128 * b / 10_000u128)
 }
 
+impl Contract {
+    /// State of a collection with no tokens yet, owned by `tokens.owner_id`. Shared by `new` and
+    /// `migrate`, which pass in the token and metadata storage they already have.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn initial_state(
+        tokens: NonFungibleToken,
+        metadata: LazyOption<NFTContractMetadata>,
+        mint_price: U128,
+        mint_currency: Option<AccountId>,
+        payment_split_percent: U128,
+        total_supply: U128,
+        burn_fee: U128,
+        treasury: AccountId,
+        royalty: U128,
+        metadata_limits: Option<MetadataLimits>,
+        vesting: Option<VestingSchedule>,
+        charity: Option<(AccountId, u16)>,
+        max_mints_per_wallet: Option<U64>,
+        sale_start: Option<U64>,
+        sale_end: Option<U64>,
+        owner_allocation: Option<U64>,
+        mint_fee_bps: Option<U128>
+    ) -> Self {
+        let mut excluded_from_rewards = UnorderedSet::new(StorageKey::ExcludedFromRewards);
+        excluded_from_rewards.insert(&tokens.owner_id);
+        excluded_from_rewards.insert(&treasury);
+        Self {
+            tokens,
+            metadata,
+            index: 0,
+            total_supply: total_supply.0,
+            mint_price: mint_price.0,
+            base_currency: mint_currency,
+            mint_currencies: UnorderedMap::new(StorageKey::MintCurrencies),
+            near_mint_price: None,
+            payment_split_percent: payment_split_percent.0,
+            storage_deposits: LookupMap::new(StorageKey::StorageDeposits),
+            ft_deposits: LookupMap::new(StorageKey::FTDeposits),
+            burn_fee: burn_fee.0,
+            balances_by_owner: LookupMap::new(StorageKey::BalancesByOwner),
+            holders: UnorderedSet::new(StorageKey::Holders),
+            treasury: treasury,
+            royalty: royalty.0,
+            burned_count: 0,
+            reward_pool: 0,
+            metadata_limits: metadata_limits.unwrap_or_default(),
+            vouchers: LookupMap::new(StorageKey::Vouchers),
+            voucher_escrow: LookupMap::new(StorageKey::VoucherEscrow),
+            vouchers_outstanding: 0,
+            total_vouchers_sold: 0,
+            redemption_open: false,
+            snapshots: UnorderedMap::new(StorageKey::Snapshots),
+            next_snapshot_id: 0,
+            claim_delegates: LookupMap::new(StorageKey::ClaimDelegates),
+            vesting,
+            owner_vesting: VestingLedger::default(),
+            affiliates: UnorderedMap::new(StorageKey::Affiliates),
+            affiliate_bps: 0,
+            token_extras: LookupMap::new(StorageKey::TokenExtras),
+            trait_schema: LazyOption::new(StorageKey::TraitSchema, None),
+            trait_counts: LookupMap::new(StorageKey::TraitCounts),
+            rarity_index: TreeMap::new(StorageKey::RarityIndex),
+            rarity_index_scores: LookupMap::new(StorageKey::RarityIndexScores),
+            rarity_rebuild_cursor: 0,
+            combine_fee: 0,
+            level_costs: LazyOption::new(StorageKey::LevelCosts, None),
+            staked: LookupMap::new(StorageKey::Staked),
+            staked_counts: LookupMap::new(StorageKey::StakedCounts),
+            total_staked: 0,
+            stake_multiplier_bps: DEFAULT_STAKE_MULTIPLIER_BPS,
+            unstake_cooldown: 0,
+            proposals: UnorderedMap::new(StorageKey::Proposals),
+            next_proposal_id: 0,
+            votes: LookupMap::new(StorageKey::Votes),
+            governance: GovernanceConfig::default(),
+            treasury_balance: 0,
+            redemptions: LookupMap::new(StorageKey::Redemptions),
+            redemptions_by_status: LookupMap::new(StorageKey::RedemptionStatuses),
+            redemption_consolation: 0,
+            bridge_account: None,
+            bridge_locks: UnorderedMap::new(StorageKey::BridgeLocks),
+            bridge_nonce: 0,
+            attachments: UnorderedMap::new(StorageKey::Attachments),
+            series: UnorderedMap::new(StorageKey::Series),
+            series_minted: 0,
+            mint_pass_contract: None,
+            pass_verifications: LookupMap::new(StorageKey::PassVerifications),
+            pass_verification_ttl: DEFAULT_PASS_VERIFICATION_TTL,
+            migrations: LookupMap::new(StorageKey::Migrations),
+            allowlist_signer_pk: None,
+            allowlist_phase_id: 0,
+            allowlist_wallet_limit: DEFAULT_ALLOWLIST_WALLET_LIMIT,
+            allowlist_mints: LookupMap::new(StorageKey::AllowlistMints),
+            charity,
+            charity_frozen: false,
+            charity_total_donated: 0,
+            charity_undelivered: 0,
+            kv_writers: UnorderedSet::new(StorageKey::KvWriters),
+            min_payout_balance: 0,
+            sale_phase: SalePhase::Public,
+            presale_allowlist: UnorderedSet::new(StorageKey::PresaleAllowlist),
+            allowlist_root: None,
+            max_mints_per_wallet: max_mints_per_wallet.map(|max| max.0),
+            mints_by_wallet: LookupMap::new(StorageKey::MintsByWallet),
+            mint_paused: false,
+            price_tiers: Vec::new(),
+            dutch_auction: None,
+            owner_allocation: owner_allocation.map_or(0, |allocation| allocation.0),
+            owner_mints: 0,
+            referral_bps: 0,
+            minted_counts: UnorderedMap::new(StorageKey::MintedCounts),
+            voucher_signer_pk: None,
+            used_vouchers: LookupMap::new(StorageKey::UsedVouchers),
+            reservations: LookupMap::new(StorageKey::Reservations),
+            max_mints_per_tx: DEFAULT_MAX_MINTS_PER_TX,
+            vaults_enabled: true,
+            public_minting: true,
+            approved_minters: UnorderedSet::new(StorageKey::ApprovedMinters),
+            token_royalties: LookupMap::new(StorageKey::TokenRoyalties),
+            metadata_template: None,
+            id_pool: Vector::new(StorageKey::IdPool),
+            revealed: true,
+            placeholder_metadata: LazyOption::new(StorageKey::PlaceholderMetadata, None),
+            burned_tokens: UnorderedSet::new(StorageKey::BurnedTokens),
+            mint_fee_bps: mint_fee_bps.map_or(0, |bps| bps.0),
+            sale_start: sale_start.map(|start| start.0),
+            sale_end: sale_end.map(|end| end.0),
+            vault_split_range: None,
+            token_vault_splits: LookupMap::new(StorageKey::TokenVaultSplits),
+            near_per_storage: DEFAULT_NEAR_PER_STORAGE,
+            vault_storage: DEFAULT_VAULT_STORAGE,
+            storage_per_sale: DEFAULT_STORAGE_PER_SALE,
+            usd_pricing: None,
+            presale_tiers: LookupMap::new(StorageKey::PresaleTiers),
+            presale_tier_mints: LookupMap::new(StorageKey::PresaleTierMints),
+            mint_cooldown_blocks: DEFAULT_MINT_COOLDOWN_BLOCKS,
+            last_mint_blocks: LookupMap::new(StorageKey::LastMintBlocks),
+            raffle: None,
+            raffle_entries: Vector::new(StorageKey::RaffleEntries),
+            raffle_winners: UnorderedMap::new(StorageKey::RaffleWinners),
+            raffle_refunds: UnorderedMap::new(StorageKey::RaffleRefunds),
+            vault_beneficiary: VaultBeneficiary::Owner,
+            reward_per_weight: 0,
+            total_reward_weight: 0,
+            reward_debts: LookupMap::new(StorageKey::RewardDebts),
+            burn_fee_schedule: None,
+            minted_at: LookupMap::new(StorageKey::MintedAt),
+            burn_lock_duration: 0,
+            burn_unlocks_at: LookupMap::new(StorageKey::BurnUnlocksAt),
+            moderated_tokens: UnorderedSet::new(StorageKey::ModeratedTokens),
+            reward_mode: RewardMode::PerHolder,
+            reward_weights: LookupMap::new(StorageKey::RewardWeights),
+            excluded_from_rewards,
+            burn_records: Vector::new(StorageKey::BurnRecords),
+            burn_record_indexes: LookupMap::new(StorageKey::BurnRecordIndexes),
+            upgrade_rules: UnorderedMap::new(StorageKey::UpgradeRules),
+            legacy_burned_count: 0,
+            reward_weight_backfill: None,
+            vault_currencies: LookupMap::new(StorageKey::VaultCurrencies),
+            vault_amounts: LookupMap::new(StorageKey::VaultAmounts),
+            vote_locks: LookupMap::new(StorageKey::VoteLocks),
+            processor_bounty: 0
+        }
+    }
+}
+
+impl Contract {
+    /// Split `balance` into the royalties of `token_id` and the share of `owner_id`. Panics when
+    /// `balance` is under `min_payout_balance` or too small for any share to be nonzero.
+    pub(crate) fn internal_payout(&self, token_id: &TokenId, owner_id: &AccountId, balance: u128) -> Payout {
+        require!(
+            balance >= self.min_payout_balance,
+            format!("Balance must be at least {}", self.min_payout_balance)
+        );
+        let mut payout: Payout = Payout {
+            payout: HashMap::new(),
+        };
+        if let Some(royalties) = self.token_royalties.get(token_id) {
+            payout.payout = royalty_payout(&royalties, owner_id, balance);
+        } else {
+            payout.payout.insert(self.tokens.owner_id.clone(), royalty_to_payout(self.royalty, balance));
+            payout.payout.insert(owner_id.clone(), royalty_to_payout(10000-self.royalty, balance));
+        }
+        require!(
+            payout.payout.values().any(|amount| amount.0 > 0),
+            "Balance is too small to pay anyone"
+        );
+        payout
+    }
+}
+
+/// Cost in yoctoNEAR of the storage added since `initial_storage_usage` was measured.
+pub(crate) fn storage_cost_since(initial_storage_usage: u64) -> u128 {
+    let used = env::storage_usage().saturating_sub(initial_storage_usage);
+    env::storage_byte_cost().as_yoctonear() * used as u128
+}
+
+/// Cost in yoctoNEAR of the storage released since `initial_storage_usage` was measured.
+pub(crate) fn storage_freed_since(initial_storage_usage: u64) -> u128 {
+    let freed = initial_storage_usage.saturating_sub(env::storage_usage());
+    env::storage_byte_cost().as_yoctonear() * freed as u128
+}
+
+/// Require the attached deposit to cover the storage added since `initial_storage_usage` and
+/// refund whatever is left to the predecessor.
+pub(crate) fn refund_storage_deposit(initial_storage_usage: u64) {
+    let required = storage_cost_since(initial_storage_usage);
+    let attached = env::attached_deposit().as_yoctonear();
+    require!(
+        attached >= required,
+        format!("Must attach {} yoctoNEAR to cover storage", required)
+    );
+    let refund = attached - required;
+    if refund > 1 {
+        Promise::new(env::predecessor_account_id()).transfer(NearToken::from_yoctonear(refund));
+    }
+}
+
 #[near_bindgen]
 impl NonFungibleTokenResolver for Contract {
     #[private]
@@ -565,12 +1372,23 @@ impl NonFungibleTokenResolver for Contract {
         token_id: TokenId,
         approved_account_ids: Option<HashMap<AccountId, u64>>,
     ) -> bool {
-        self.tokens.nft_resolve_transfer(
-            previous_owner_id,
-            receiver_id,
+        let transferred = self.tokens.nft_resolve_transfer(
+            previous_owner_id.clone(),
+            receiver_id.clone(),
             token_id,
             approved_account_ids,
-        )
+        );
+        // A token sent to another account and returned undoes the holder changes of its transfer
+        if !transferred && receiver_id != env::current_account_id() {
+            if self.owned_count(&receiver_id) == 0 && self.holders.remove(&receiver_id) {
+                self.emit_holder_changed(&receiver_id, false, 0);
+            }
+            if self.holders.insert(&previous_owner_id) {
+                self.emit_holder_changed(&previous_owner_id, true, self.owned_count(&previous_owner_id));
+            }
+            self.internal_update_reward_weights(&receiver_id, &previous_owner_id);
+        }
+        transferred
     }
 }
 
@@ -583,6 +1401,12 @@ impl NonFungibleTokenApproval for Contract {
         account_id: AccountId,
         msg: Option<String>,
     ) -> Option<Promise> {
+        // A message notifies the approved account, which is how marketplaces list the token
+        if msg.is_some() {
+            self.assert_transferable(&token_id);
+        } else {
+            self.assert_listable(&token_id);
+        }
         self.tokens.nft_approve(token_id, account_id, msg)
     }
 
@@ -612,8 +1436,16 @@ impl NonFungibleTokenEnumeration for Contract {
         self.tokens.nft_total_supply()
     }
 
+    /// Burned tokens are removed from the enumeration with their owner, so a page can start past
+    /// the end after burns: it is empty rather than out of bounds.
     fn nft_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
-        self.tokens.nft_tokens(from_index, limit)
+        if from_index.is_some_and(|from_index| from_index.0 >= self.nft_total_supply().0) {
+            return vec![];
+        }
+        self.tokens.nft_tokens(from_index, Some(page_limit(limit) as u64))
+            .into_iter()
+            .map(|token| self.displayed_token(token))
+            .collect()
     }
 
     fn nft_supply_for_owner(&self, account_id: AccountId) -> U128 {
@@ -626,7 +1458,13 @@ impl NonFungibleTokenEnumeration for Contract {
         from_index: Option<U128>,
         limit: Option<u64>,
     ) -> Vec<Token> {
-        self.tokens.nft_tokens_for_owner(account_id, from_index, limit)
+        if from_index.is_some_and(|from_index| from_index.0 >= self.nft_supply_for_owner(account_id.clone()).0) {
+            return vec![];
+        }
+        self.tokens.nft_tokens_for_owner(account_id, from_index, Some(page_limit(limit) as u64))
+            .into_iter()
+            .map(|token| self.displayed_token(token))
+            .collect()
     }
 }
 
@@ -0,0 +1,30 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// When the transfer lock `token_id` was minted with ends, if it had one. The token can move
+    /// again from this timestamp on.
+    pub fn unlock_time(&self, token_id: TokenId) -> Option<U64> {
+        self.token_extra(&token_id).transfer_locked_until.map(U64)
+    }
+
+    /// Tokens of `account_id` whose transfer lock hasn't passed yet, or all of them while it has
+    /// votes on an open proposal, paged over the tokens it owns.
+    pub fn locked_tokens_of(&self, account_id: AccountId, from_index: Option<U128>, limit: Option<u64>) -> Vec<TokenId> {
+        let owned = match self.tokens.tokens_per_owner.as_ref().and_then(|by_owner| by_owner.get(&account_id)) {
+            Some(owned) => owned,
+            None => return vec![],
+        };
+        let now = env::block_timestamp();
+        let vote_locked = self.vote_locks.get(&account_id).is_some_and(|locked_until| now < locked_until);
+        owned
+            .iter()
+            .skip(from_index.map_or(0, |index| index.0 as usize))
+            .take(page_limit(limit))
+            .filter(|token_id| {
+                vote_locked
+                    || self.token_extra(token_id).transfer_locked_until.is_some_and(|locked_until| now < locked_until)
+            })
+            .collect()
+    }
+}
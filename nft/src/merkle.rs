@@ -0,0 +1,141 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Only let accounts under the Merkle root `root` mint, see `merkle_leaf` for the leaves.
+    /// `None` turns the check off.
+    pub fn set_allowlist_root(&mut self, root: Option<[u8; 32]>) {
+        self.assert_owner();
+        self.allowlist_root = root;
+    }
+
+    pub fn allowlist_root(&self) -> Option<[u8; 32]> {
+        self.allowlist_root
+    }
+}
+
+impl Contract {
+    /// When an allowlist root is set, panic unless `proof` shows `account_id` is one of its leaves.
+    pub(crate) fn assert_in_allowlist_root(&self, account_id: &AccountId, proof: Option<Vec<[u8; 32]>>) {
+        if let Some(root) = self.allowlist_root {
+            let proof = proof.unwrap_or_else(|| env::panic_str("A Merkle proof is required to mint"));
+            require!(
+                verify_merkle_proof(merkle_leaf(account_id), &proof, root),
+                "Account is not in the allowlist"
+            );
+        }
+    }
+}
+
+/// Leaf of `account_id` in an allowlist tree: `sha256(account_id)`.
+pub(crate) fn merkle_leaf(account_id: &AccountId) -> [u8; 32] {
+    env::sha256_array(account_id.as_bytes())
+}
+
+/// Check `proof` leads from `leaf` to `root`. Each pair of nodes is hashed in sorted order, so the
+/// proof is just the sibling of every node on the path. A tree of one leaf has an empty proof.
+pub(crate) fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: [u8; 32]) -> bool {
+    let node = proof.iter().fold(leaf, |node, sibling| {
+        let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        env::sha256_array(&[left, right].concat())
+    });
+    node == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        env::sha256_array(&[left, right].concat())
+    }
+
+    /// Root of a sorted-pair tree over `leaves` and the proof of every leaf. The odd node of a
+    /// level is carried up unchanged, so its proof has no sibling for that level.
+    fn build_tree(leaves: &[[u8; 32]]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+        let mut proofs = vec![vec![]; leaves.len()];
+        // The leaves each node of the current level covers
+        let mut level: Vec<([u8; 32], Vec<usize>)> =
+            leaves.iter().enumerate().map(|(i, leaf)| (*leaf, vec![i])).collect();
+        while level.len() > 1 {
+            let mut next = vec![];
+            for pair in level.chunks(2) {
+                if let [(left, left_leaves), (right, right_leaves)] = pair {
+                    left_leaves.iter().for_each(|&i| proofs[i].push(*right));
+                    right_leaves.iter().for_each(|&i| proofs[i].push(*left));
+                    next.push((hash_pair(*left, *right), [left_leaves.clone(), right_leaves.clone()].concat()));
+                } else {
+                    next.push(pair[0].clone());
+                }
+            }
+            level = next;
+        }
+        (level.first().map_or([0; 32], |(root, _)| *root), proofs)
+    }
+
+    fn leaves(count: usize) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|i| merkle_leaf(&format!("account{}.near", i).parse().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn every_leaf_of_a_full_tree_verifies() {
+        let leaves = leaves(4);
+        let (root, proofs) = build_tree(&leaves);
+        for (leaf, proof) in leaves.iter().zip(&proofs) {
+            assert_eq!(proof.len(), 2);
+            assert!(verify_merkle_proof(*leaf, proof, root));
+        }
+    }
+
+    #[test]
+    fn a_single_leaf_is_its_own_root() {
+        let leaves = leaves(1);
+        let (root, proofs) = build_tree(&leaves);
+        assert_eq!(root, leaves[0]);
+        assert!(proofs[0].is_empty());
+        assert!(verify_merkle_proof(leaves[0], &[], root));
+        assert!(!verify_merkle_proof(leaves[0], &[leaves[0]], root));
+    }
+
+    #[test]
+    fn the_odd_leaf_of_a_level_verifies() {
+        let leaves = leaves(5);
+        let (root, proofs) = build_tree(&leaves);
+        // The fifth leaf skips the first two levels and only meets the root of the other four
+        assert_eq!(proofs[4].len(), 1);
+        for (leaf, proof) in leaves.iter().zip(&proofs) {
+            assert!(verify_merkle_proof(*leaf, proof, root));
+        }
+    }
+
+    #[test]
+    fn a_tampered_sibling_fails() {
+        let leaves = leaves(4);
+        let (root, proofs) = build_tree(&leaves);
+        for position in 0..proofs[0].len() {
+            let mut proof = proofs[0].clone();
+            proof[position][0] ^= 1;
+            assert!(!verify_merkle_proof(leaves[0], &proof, root));
+        }
+    }
+
+    #[test]
+    fn a_leaf_outside_the_tree_fails() {
+        let leaves = leaves(4);
+        let (root, proofs) = build_tree(&leaves[..3]);
+        assert!(!verify_merkle_proof(leaves[3], &proofs[2], root));
+        // Nor does a leaf pass with the proof of another
+        assert!(!verify_merkle_proof(leaves[0], &proofs[1], root));
+    }
+
+    #[test]
+    fn nothing_verifies_against_an_empty_tree() {
+        let (root, _) = build_tree(&[]);
+        let leaf = leaves(1)[0];
+        assert!(!verify_merkle_proof(leaf, &[], root));
+        assert!(!verify_merkle_proof(leaf, &[leaf], root));
+    }
+}
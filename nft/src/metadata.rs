@@ -0,0 +1,126 @@
+use crate::*;
+
+/// Byte-length limits for the free-form fields of per-token metadata.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct MetadataLimits {
+    pub max_title_len: u32,
+    pub max_description_len: u32,
+    pub max_media_len: u32,
+    pub max_extra_len: u32,
+}
+
+impl Default for MetadataLimits {
+    fn default() -> Self {
+        Self {
+            max_title_len: 256,
+            max_description_len: 2048,
+            max_media_len: 512,
+            max_extra_len: 4096,
+        }
+    }
+}
+
+impl MetadataLimits {
+    pub fn assert_valid(&self, metadata: &TokenMetadata) {
+        assert_field_len("title", &metadata.title, self.max_title_len);
+        assert_field_len("description", &metadata.description, self.max_description_len);
+        assert_field_len("media", &metadata.media, self.max_media_len);
+        assert_field_len("extra", &metadata.extra, self.max_extra_len);
+    }
+}
+
+/// Metadata generated for every `nft_mint` from the mint index, instead of taking the minter's.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct MetadataTemplate {
+    pub base_uri: String,
+    pub title_prefix: String,
+    pub copies: Option<U64>,
+}
+
+fn assert_field_len(field: &str, value: &Option<String>, max_len: u32) {
+    if let Some(value) = value {
+        require!(
+            value.len() <= max_len as usize,
+            format!(
+                "Token metadata {} is {} bytes, the maximum is {}",
+                field,
+                value.len(),
+                max_len
+            )
+        );
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Replace the metadata of an existing token. Only the collection owner can update metadata,
+    /// and any storage growth must be covered by the attached deposit.
+    #[payable]
+    pub fn update_token_metadata(&mut self, token_id: TokenId, token_metadata: TokenMetadata) {
+        self.assert_owner();
+        self.metadata_limits.assert_valid(&token_metadata);
+        require!(
+            self.tokens.owner_by_id.get(&token_id).is_some(),
+            "Token not found"
+        );
+
+        let initial_storage_usage = env::storage_usage();
+        if let Some(by_id) = self.tokens.token_metadata_by_id.as_mut() {
+            by_id.insert(&token_id, &token_metadata);
+        }
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    #[payable]
+    pub fn set_metadata_limits(&mut self, metadata_limits: MetadataLimits) {
+        self.assert_owner();
+        self.metadata_limits = metadata_limits;
+    }
+
+    /// Generate the metadata of every token minted from now on, ignoring what the minter passes,
+    /// or take the minter's again with `None`. The template's `base_uri` becomes the contract's.
+    #[payable]
+    pub fn set_metadata_template(&mut self, metadata_template: Option<MetadataTemplate>) {
+        self.assert_owner();
+        let initial_storage_usage = env::storage_usage();
+        if let Some(template) = &metadata_template {
+            let mut metadata = self.metadata.get().unwrap();
+            metadata.base_uri = Some(template.base_uri.clone());
+            self.metadata.set(&metadata);
+        }
+        self.metadata_template = metadata_template;
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    pub fn metadata_template(&self) -> Option<MetadataTemplate> {
+        self.metadata_template.clone()
+    }
+
+    /// Metadata the template generates for the token minted at `index`, `None` without one.
+    pub fn preview_metadata(&self, index: U128) -> Option<TokenMetadata> {
+        self.metadata_template.as_ref().map(|template| template_metadata(template, index.0))
+    }
+}
+
+impl Contract {
+    /// Metadata of the token minted next: generated when a template is set, otherwise `supplied`.
+    pub(crate) fn minted_metadata(&self, supplied: TokenMetadata) -> TokenMetadata {
+        match &self.metadata_template {
+            Some(template) => template_metadata(template, self.index),
+            None => supplied,
+        }
+    }
+}
+
+fn template_metadata(template: &MetadataTemplate, index: u128) -> TokenMetadata {
+    TokenMetadata {
+        title: Some(format!("{} #{}", template.title_prefix, index)),
+        media: Some(format!("{}/{}.json", template.base_uri.trim_end_matches('/'), index)),
+        copies: template.copies.map(|copies| copies.0),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,93 @@
+use crate::*;
+
+/// An account migration that moved part of the account's tokens and continues on the next call.
+#[derive(BorshDeserialize, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct Migration {
+    pub new_account: AccountId,
+    pub tokens_moved: u64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Move the caller's whole position to `new_account`: every token it owns, then its reward
+    /// balance, FT deposits and storage deposits. At most `MIGRATION_BATCH_SIZE` tokens move per
+    /// call, so an account owning more calls again until this returns 0, the number of tokens left.
+    /// A started migration can only continue to the same account. Staked, bridged and time-locked
+    /// tokens can't move, so they must be released first.
+    #[payable]
+    pub fn migrate_account(&mut self, new_account: AccountId) -> u64 {
+        assert_one_yocto();
+        let old_account = env::predecessor_account_id();
+        require!(new_account != old_account, "Can't migrate an account to itself");
+        let mut migration = match self.migrations.get(&old_account) {
+            Some(migration) => {
+                require!(
+                    migration.new_account == new_account,
+                    format!("Migration to {} is in progress", migration.new_account)
+                );
+                migration
+            }
+            None => Migration { new_account: new_account.clone(), tokens_moved: 0 },
+        };
+
+        let owned: Vec<TokenId> = self.tokens.tokens_per_owner.as_ref()
+            .and_then(|by_owner| by_owner.get(&old_account))
+            .map_or(vec![], |owned| owned.iter().take(MIGRATION_BATCH_SIZE as usize).collect());
+        for token_id in &owned {
+            // Each token moves together with the holder bookkeeping it implies
+            self.assert_transferable(token_id);
+            self.internal_update_holders(&old_account, &new_account);
+            self.tokens.internal_transfer(&old_account, &new_account, token_id, None, None);
+        }
+        self.internal_update_reward_weights(&old_account, &new_account);
+        migration.tokens_moved += owned.len() as u64;
+
+        let remaining = self.tokens.nft_supply_for_owner(old_account.clone()).0 as u64;
+        if remaining > 0 {
+            self.migrations.insert(&old_account, &migration);
+            return remaining;
+        }
+
+        // Every token has moved, so the ledgers follow and the migration completes
+        self.migrations.remove(&old_account);
+        let rewards = self.balances_by_owner.remove(&old_account).unwrap_or(0);
+        let storage_deposits = self.storage_deposits.remove(&old_account).unwrap_or(0);
+        for (ledger, amount) in [
+            (&mut self.balances_by_owner, rewards),
+            (&mut self.storage_deposits, storage_deposits),
+        ] {
+            if amount > 0 {
+                let balance = ledger.get(&new_account).unwrap_or(0);
+                ledger.insert(&new_account, &balance.checked_add(amount).unwrap());
+            }
+        }
+        // Deposits follow in every currency still accepted, the rest stay withdrawable
+        let ft_contracts: Vec<AccountId> = self.base_currency.iter().cloned()
+            .chain(self.mint_currencies.keys())
+            .collect();
+        let mut ft_deposits = Vec::new();
+        for ft_contract in ft_contracts {
+            let amount = self.internal_take_ft_deposit(&old_account, &ft_contract);
+            if amount > 0 {
+                self.internal_credit_ft_deposit(&new_account, &ft_contract, amount);
+                ft_deposits.push((ft_contract, U128(amount)));
+            }
+        }
+
+        Event::Migrated {
+            old_account_id: &old_account,
+            new_account_id: &new_account,
+            token_count: U64(migration.tokens_moved),
+            rewards: U128(rewards),
+            ft_deposits,
+            storage_deposits: U128(storage_deposits),
+        }.emit();
+        0
+    }
+
+    /// The account `account_id` is partway through migrating to, if any.
+    pub fn migration_of(&self, account_id: AccountId) -> Option<AccountId> {
+        self.migrations.get(&account_id).map(|migration| migration.new_account)
+    }
+}
@@ -0,0 +1,96 @@
+use crate::*;
+
+//how long a pass verification lets the account mint, in nanoseconds
+pub const DEFAULT_PASS_VERIFICATION_TTL: u64 = 10 * 60 * 1_000_000_000;
+//gas for reading the pass balance and for recording the result
+const GAS_FOR_PASS_LOOKUP: Gas = Gas::from_tgas(10);
+const GAS_FOR_RESOLVE_PASS: Gas = Gas::from_tgas(15);
+
+#[near_bindgen]
+impl Contract {
+    /// Check that the caller holds a token of the mint pass contract. When it does, the caller may
+    /// mint until the verification expires. The attached deposit pays for the verification record
+    /// and the rest is refunded.
+    #[payable]
+    pub fn verify_pass(&mut self) -> Promise {
+        let pass_contract = self.mint_pass_contract.clone()
+            .unwrap_or_else(|| env::panic_str("No mint pass is required"));
+        let account_id = env::predecessor_account_id();
+        Promise::new(pass_contract)
+            .function_call(
+                "nft_supply_for_owner".to_string(),
+                json!({ "account_id": account_id }).to_string().into_bytes().to_vec(),
+                NearToken::from_yoctonear(0),
+                GAS_FOR_PASS_LOOKUP
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_PASS)
+                    .resolve_verify_pass(account_id, U128(env::attached_deposit().as_yoctonear()))
+            )
+    }
+
+    /// Record the verification of `account_id` if it holds a pass, paying for the record from the
+    /// deposit attached to `verify_pass` and refunding the rest.
+    #[private]
+    pub fn resolve_verify_pass(&mut self, account_id: AccountId, deposit: U128) -> bool {
+        let passes = match env::promise_result(0) {
+            PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<U128>(&value)
+                .map_or(0, |supply| supply.0),
+            _ => 0,
+        };
+
+        let initial_storage_usage = env::storage_usage();
+        let verified = passes > 0;
+        if verified {
+            let expires_at = env::block_timestamp() + self.pass_verification_ttl;
+            self.pass_verifications.insert(&account_id, &expires_at);
+        } else {
+            self.pass_verifications.remove(&account_id);
+        }
+        let cost = storage_cost_since(initial_storage_usage);
+        if cost > deposit.0 {
+            // Not paid for, so not recorded
+            self.pass_verifications.remove(&account_id);
+            Promise::new(account_id).transfer(NearToken::from_yoctonear(deposit.0));
+            return false;
+        }
+        if deposit.0 > cost {
+            Promise::new(account_id).transfer(NearToken::from_yoctonear(deposit.0 - cost));
+        }
+        verified
+    }
+
+    pub fn set_mint_pass_contract(&mut self, mint_pass_contract: Option<AccountId>) {
+        self.assert_owner();
+        self.mint_pass_contract = mint_pass_contract;
+    }
+
+    pub fn set_pass_verification_ttl(&mut self, ttl: U64) {
+        self.assert_owner();
+        self.pass_verification_ttl = ttl.0;
+    }
+
+    pub fn mint_pass_contract(&self) -> Option<AccountId> {
+        self.mint_pass_contract.clone()
+    }
+
+    /// Whether `account_id` has an unexpired pass verification.
+    pub fn is_pass_verified(&self, account_id: AccountId) -> bool {
+        self.pass_verifications
+            .get(&account_id)
+            .is_some_and(|expires_at| env::block_timestamp() < expires_at)
+    }
+}
+
+impl Contract {
+    /// Panic when a mint pass is required and `account_id` has no unexpired verification.
+    pub(crate) fn assert_pass_verified(&self, account_id: &AccountId) {
+        if self.mint_pass_contract.is_some() {
+            require!(
+                self.is_pass_verified(account_id.clone()),
+                "Call verify_pass while holding a mint pass before minting"
+            );
+        }
+    }
+}
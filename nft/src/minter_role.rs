@@ -0,0 +1,48 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Let `account_id` mint while public minting is off. The caller pays for the storage used.
+    #[payable]
+    pub fn grant_minter(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        let initial_storage_usage = env::storage_usage();
+        self.approved_minters.insert(&account_id);
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    pub fn revoke_minter(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.approved_minters.remove(&account_id);
+    }
+
+    /// Open `nft_mint` to everyone, or with `false` only to the owner and approved minters.
+    pub fn set_public_minting(&mut self, public_minting: bool) {
+        self.assert_owner();
+        self.public_minting = public_minting;
+    }
+
+    pub fn public_minting(&self) -> bool {
+        self.public_minting
+    }
+
+    pub fn is_minter(&self, account_id: AccountId) -> bool {
+        self.approved_minters.contains(&account_id)
+    }
+
+    pub fn get_minters(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<AccountId> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.approved_minters.iter().skip(start).take(page_limit(limit)).collect()
+    }
+}
+
+impl Contract {
+    /// Panic unless `account_id` may mint: anyone while public minting is on, otherwise only the
+    /// collection owner and approved minters.
+    pub(crate) fn assert_minter(&self, account_id: &AccountId) {
+        require!(
+            self.public_minting || *account_id == self.tokens.owner_id || self.approved_minters.contains(account_id),
+            "Only approved minters can mint"
+        );
+    }
+}
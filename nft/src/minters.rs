@@ -0,0 +1,33 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// How many tokens `account_id` has minted. Transfers and burns don't change the count.
+    pub fn minted_by(&self, account_id: AccountId) -> U64 {
+        U64(self.minted_counts.get(&account_id).unwrap_or(0))
+    }
+
+    pub fn minters(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<(AccountId, U64)> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.minted_counts
+            .iter()
+            .skip(start)
+            .take(page_limit(limit))
+            .map(|(account_id, count)| (account_id, U64(count)))
+            .collect()
+    }
+}
+
+impl Contract {
+    /// Count one more mint by `minter`, or one less when a mint is rolled back.
+    pub(crate) fn internal_count_minted(&mut self, minter: &AccountId, minted: bool) {
+        let count = self.minted_counts.get(minter).unwrap_or(0);
+        if minted {
+            self.minted_counts.insert(minter, &(count + 1));
+        } else if count > 1 {
+            self.minted_counts.insert(minter, &(count - 1));
+        } else {
+            self.minted_counts.remove(minter);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Burn `token_id` for violating policy, e.g. on a DMCA notice, with `memo` recorded in the
+    /// `nft_burn` event. The token's current owner still gets its whole vault, with no burn fee
+    /// taken and the vault deleted in their favor whatever the vault beneficiary, and the token id
+    /// can never be minted again.
+    #[payable]
+    pub fn admin_burn(&mut self, token_id: TokenId, memo: String) -> BurnReceipt {
+        assert_one_yocto();
+        self.assert_owner();
+        let owner = self.tokens.owner_by_id.get(&token_id)
+            .unwrap_or_else(|| env::panic_str("Token not found"));
+        self.moderated_tokens.insert(&token_id);
+        let (receipt, _) = self.internal_burn(&token_id, &owner, Some(&env::predecessor_account_id()), Some(&memo));
+        receipt
+    }
+
+    pub fn is_moderated(&self, token_id: TokenId) -> bool {
+        self.moderated_tokens.contains(&token_id)
+    }
+}
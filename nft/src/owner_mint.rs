@@ -0,0 +1,51 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Mint `token_id` to `receiver_id` from the collection owner's allocation, e.g. for a
+    /// giveaway. No mint price is charged: the attached deposit pays the vault deposit and the
+    /// token's storage, or only the storage with `create_vault: false`. A token without a vault
+    /// burns without any rewards.
+    #[payable]
+    pub fn owner_mint(
+        &mut self,
+        token_id: TokenId,
+        receiver_id: AccountId,
+        token_metadata: TokenMetadata,
+        create_vault: Option<bool>,
+    ) -> Token {
+        self.assert_owner();
+        self.assert_mint_not_paused();
+        require!(
+            self.owner_mints < self.owner_allocation,
+            format!("The owner allocation of {} tokens is used up", self.owner_allocation)
+        );
+        self.owner_mints += 1;
+
+        let create_vault = create_vault.unwrap_or(true);
+        let near_required = if create_vault { self.vault_deposit() } else { 0 };
+        self.internal_mint(
+            token_id,
+            receiver_id.clone(),
+            token_metadata,
+            receiver_id,
+            near_required,
+            env::attached_deposit().as_yoctonear(),
+            MintOptions {
+                price: Some(0),
+                without_vault: !create_vault,
+                currency: self.base_currency.clone(),
+                ..Default::default()
+            }
+        )
+    }
+
+    pub fn owner_allocation(&self) -> U64 {
+        U64(self.owner_allocation)
+    }
+
+    /// How many tokens the collection owner has minted from its allocation.
+    pub fn owner_mints(&self) -> U64 {
+        U64(self.owner_mints)
+    }
+}
@@ -0,0 +1,73 @@
+use crate::*;
+
+/// A falling mint price: `start_price` until `start_time`, then declining linearly to
+/// `floor_price` over `duration` nanoseconds.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct DutchAuctionConfig {
+    pub start_price: U128,
+    pub floor_price: U128,
+    pub start_time: U64,
+    pub duration: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Price the collection's own tokens by tier: each `(up_to_index, price)` applies while the
+    /// mint index is below `up_to_index`, and `mint_price` after the last tier. Tiers must be in
+    /// ascending order and can only be replaced before the first mint.
+    pub fn set_price_tiers(&mut self, price_tiers: Vec<(U128, U128)>) {
+        self.assert_owner();
+        require!(self.index == 0, "The price schedule can't change after the first mint");
+        require!(
+            price_tiers.windows(2).all(|pair| pair[0].0 .0 < pair[1].0 .0),
+            "Price tiers must be in ascending order"
+        );
+        self.price_tiers = price_tiers.into_iter().map(|(up_to_index, price)| (up_to_index.0, price.0)).collect();
+    }
+
+    /// Price the collection's own tokens with a Dutch auction instead of `mint_price` and the
+    /// price tiers, or stop the auction with `None`.
+    pub fn set_dutch_auction(&mut self, dutch_auction: Option<DutchAuctionConfig>) {
+        self.assert_owner();
+        if let Some(auction) = &dutch_auction {
+            require!(auction.floor_price.0 <= auction.start_price.0, "The floor price can't exceed the start price");
+            require!(auction.duration.0 > 0, "Auction duration must be positive");
+        }
+        self.dutch_auction = dutch_auction;
+    }
+
+    pub fn dutch_auction(&self) -> Option<DutchAuctionConfig> {
+        self.dutch_auction.clone()
+    }
+
+    pub fn price_tiers(&self) -> Vec<(U128, U128)> {
+        self.price_tiers.iter().map(|(up_to_index, price)| (U128(*up_to_index), U128(*price))).collect()
+    }
+
+    /// Price of the next token minted outside a series, live while a Dutch auction runs.
+    pub fn current_mint_price(&self) -> U128 {
+        U128(self.mint_price_at(self.index))
+    }
+}
+
+impl Contract {
+    /// Price of the token minted at `index` outside a series.
+    pub(crate) fn mint_price_at(&self, index: u128) -> u128 {
+        if let Some(auction) = &self.dutch_auction {
+            return auction_price(auction, env::block_timestamp());
+        }
+        self.price_tiers
+            .iter()
+            .find(|(up_to_index, _)| index < *up_to_index)
+            .map_or(self.mint_price, |(_, price)| *price)
+    }
+}
+
+/// Price of `auction` at `now`, in nanoseconds.
+fn auction_price(auction: &DutchAuctionConfig, now: u64) -> u128 {
+    let elapsed = now.saturating_sub(auction.start_time.0).min(auction.duration.0);
+    let decline = auction.start_price.0 - auction.floor_price.0;
+    auction.start_price.0 - decline * elapsed as u128 / auction.duration.0 as u128
+}
@@ -0,0 +1,166 @@
+use crate::*;
+
+//storage kept on top of a raffle token's estimated storage, as its metadata is only known at the draw
+const RAFFLE_STORAGE_MARGIN: u64 = 200;
+
+/// A raffle for the collection's own tokens: accounts enter by depositing `entry_price` until
+/// `ends_at`, then `winners` of them are drawn and minted a token at `mint_price`. Once it is
+/// `closed`, the entrants who weren't drawn can claim their deposit back.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct Raffle {
+    pub mint_price: U128,
+    pub entry_price: U128,
+    pub ends_at: U64,
+    pub winners: U64,
+    pub closed: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Open the collection's raffle for `winners` tokens, taking entries until `ends_at`, in
+    /// nanoseconds. The entry price covers the current NEAR mint price, the vault deposit and the
+    /// token's storage. A collection holds one raffle.
+    pub fn open_raffle(&mut self, ends_at: U64, winners: U64) {
+        self.assert_owner();
+        require!(self.raffle.is_none(), "The collection already held a raffle");
+        require!(ends_at.0 > env::block_timestamp(), "The raffle must end in the future");
+        require!(winners.0 > 0, "The raffle needs at least one winner");
+        let mint_price = self.mint_price_in(&None, self.mint_price_at(self.index));
+        let storage_estimate = estimate_token_storage(None)
+            + env::storage_byte_cost().as_yoctonear() * RAFFLE_STORAGE_MARGIN as u128;
+        let cost = self.internal_mint_cost(None, mint_price, storage_estimate);
+        self.raffle = Some(Raffle {
+            mint_price: U128(mint_price),
+            entry_price: cost.total_attached_near_required,
+            ends_at,
+            winners,
+            closed: false,
+        });
+    }
+
+    /// Enter the raffle, depositing at least the entry price plus the storage of the entry. The
+    /// deposit pays for the token if the caller is drawn, and is refunded otherwise.
+    #[payable]
+    pub fn enter_raffle(&mut self) {
+        self.assert_mint_not_paused();
+        let raffle = self.raffle.clone().unwrap_or_else(|| env::panic_str("No raffle is open"));
+        require!(env::block_timestamp() < raffle.ends_at.0, "The raffle has ended");
+        let account_id = env::predecessor_account_id();
+        self.assert_minter(&account_id);
+        require!(self.raffle_refunds.get(&account_id).is_none(), "Already entered the raffle");
+
+        let initial_storage_usage = env::storage_usage();
+        self.raffle_entries.push(&account_id);
+        self.raffle_refunds.insert(&account_id, &0);
+        let deposit = env::attached_deposit().as_yoctonear();
+        let required = raffle.entry_price.0 + storage_cost_since(initial_storage_usage);
+        require!(deposit >= required, format!("Must attach at least {} yoctoNEAR to enter", required));
+        self.raffle_refunds.insert(&account_id, &(deposit - (required - raffle.entry_price.0)));
+    }
+
+    /// Draw up to `count` winners at random among the entries and mint each a token with its
+    /// deposit, deploying its vault as `nft_mint` does. Draws go on over several calls until the
+    /// raffle has all its winners or runs out of entries or supply, which closes it. A winner's
+    /// change goes to its storage balance.
+    pub fn draw_raffle(&mut self, count: u64) -> Vec<TokenId> {
+        self.assert_owner();
+        self.assert_mint_not_paused();
+        let mut raffle = self.raffle.clone().unwrap_or_else(|| env::panic_str("No raffle is open"));
+        require!(!raffle.closed, "The raffle is closed");
+        require!(env::block_timestamp() >= raffle.ends_at.0, "The raffle is still taking entries");
+        let count = count
+            .min(raffle.winners.0 - self.raffle_winners.len())
+            .min(self.raffle_entries.len())
+            .min(self.max_mints_per_tx)
+            .min(self.unsold_remaining().0 as u64);
+        let gas_needed = self.gas_per_mint(0, false).as_gas() * count;
+        require!(
+            env::prepaid_gas().as_gas() >= gas_needed,
+            format!("Attach at least {} gas to draw {} winners", gas_needed, count)
+        );
+
+        let price = raffle.mint_price.0;
+        let vault = if self.mints_vault(price) { self.vault_deposit() } else { 0 };
+        let mut minted = Vec::with_capacity(count as usize);
+        for round in 0..count {
+            let index = raffle_draw(self.raffle_entries.len(), round);
+            let winner = self.raffle_entries.swap_remove(index);
+            let deposit = self.raffle_refunds.remove(&winner).unwrap_or(0);
+            let token_id = self.internal_assign_token_id(None);
+            let token_metadata = self.minted_metadata(TokenMetadata { title: Some(token_id.clone()), ..Default::default() });
+            let token = self.internal_mint(
+                token_id,
+                winner.clone(),
+                token_metadata,
+                winner.clone(),
+                price + vault,
+                deposit,
+                MintOptions {
+                    price: Some(price),
+                    without_vault: vault == 0,
+                    storage_payer: Some(winner.clone()),
+                    ..Default::default()
+                }
+            );
+            self.raffle_winners.insert(&winner, &token.token_id);
+            minted.push(token.token_id);
+        }
+
+        if self.raffle_winners.len() == raffle.winners.0
+            || self.raffle_entries.is_empty()
+            || self.unsold_remaining().0 == 0
+        {
+            raffle.closed = true;
+        }
+        self.raffle = Some(raffle);
+        minted
+    }
+
+    /// Refund the caller's raffle deposit once the raffle is closed without drawing it.
+    pub fn claim_raffle_refund(&mut self) -> U128 {
+        require!(self.raffle.as_ref().is_some_and(|raffle| raffle.closed), "The raffle is not closed");
+        let account_id = env::predecessor_account_id();
+        let refund = self.raffle_refunds.remove(&account_id)
+            .unwrap_or_else(|| env::panic_str("Nothing to refund"));
+        Promise::new(account_id).transfer(NearToken::from_yoctonear(refund));
+        U128(refund)
+    }
+
+    pub fn raffle(&self) -> Option<Raffle> {
+        self.raffle.clone()
+    }
+
+    /// Entrants not drawn yet.
+    pub fn raffle_entries(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<AccountId> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.raffle_entries.iter().skip(start).take(page_limit(limit)).collect()
+    }
+
+    /// Every winner with the token it was minted.
+    pub fn raffle_winners(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<(AccountId, TokenId)> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.raffle_winners.iter().skip(start).take(page_limit(limit)).collect()
+    }
+
+    /// Deposits of the entrants not drawn, refundable once the raffle is closed.
+    pub fn raffle_refunds(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<(AccountId, U128)> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.raffle_refunds
+            .iter()
+            .skip(start)
+            .take(page_limit(limit))
+            .map(|(account_id, refund)| (account_id, U128(refund)))
+            .collect()
+    }
+}
+
+/// Pseudo-random index below `len` for the `round`th winner of a draw, from the block's random
+/// seed. A validator could bias it, as with any on-chain randomness.
+fn raffle_draw(len: u64, round: u64) -> u64 {
+    let mut seed = env::random_seed();
+    seed.extend_from_slice(&round.to_le_bytes());
+    let hash = env::sha256(&seed);
+    u64::from_le_bytes(hash[..8].try_into().unwrap()) % len
+}
@@ -0,0 +1,104 @@
+use crate::*;
+
+//rarity scores are fixed point numbers with this many units per 1.0
+pub const RARITY_SCORE_SCALE: u128 = 1_000_000;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RarityScore {
+    pub token_id: TokenId,
+    //sum over the token's traits of `total / count`, scaled by RARITY_SCORE_SCALE
+    pub score: U128,
+}
+
+/// Key of a trait value in `trait_counts`.
+pub(crate) fn trait_key(attribute: &TraitAttribute) -> String {
+    format!("{}\u{0}{}", attribute.trait_type, attribute.value)
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Rarity of a live token against the current supply: the sum of `1 / (count / total)` over
+    /// its traits, where `count` is the number of live tokens sharing the trait value.
+    pub fn rarity_score(&self, token_id: TokenId) -> RarityScore {
+        require!(self.tokens.owner_by_id.get(&token_id).is_some(), "Token not found");
+        let score = self.internal_rarity_score(&token_id);
+        RarityScore { token_id, score: U128(score) }
+    }
+
+    /// Tokens from rarest to most common, as of the scores of the last `rebuild_rarity_index`.
+    pub fn rarity_ranking(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<RarityScore> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.rarity_index
+            .iter_rev()
+            .skip(start)
+            .take(page_limit(limit))
+            .map(|((score, token_id), _)| RarityScore { token_id, score: U128(score) })
+            .collect()
+    }
+
+    /// Re-score up to `limit` tokens for `rarity_ranking`, continuing where the previous call
+    /// stopped and starting over once every token was visited. Returns the cursor of the next call,
+    /// 0 when the pass is complete.
+    #[payable]
+    pub fn rebuild_rarity_index(&mut self, limit: Option<u64>) -> u64 {
+        self.assert_owner();
+        let start = self.rarity_rebuild_cursor as usize;
+        let token_ids: Vec<TokenId> = self
+            .tokens
+            .owner_by_id
+            .iter()
+            .skip(start)
+            .take(page_limit(limit))
+            .map(|(token_id, _)| token_id)
+            .collect();
+        for token_id in token_ids.iter() {
+            self.internal_remove_from_rarity_index(token_id);
+            let score = self.internal_rarity_score(token_id);
+            self.rarity_index.insert(&(score, token_id.clone()), &());
+            self.rarity_index_scores.insert(token_id, &score);
+        }
+
+        let cursor = start as u64 + token_ids.len() as u64;
+        self.rarity_rebuild_cursor = if cursor >= self.tokens.owner_by_id.len() { 0 } else { cursor };
+        self.rarity_rebuild_cursor
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_add_trait_counts(&mut self, attributes: &[TraitAttribute]) {
+        for attribute in attributes {
+            let key = trait_key(attribute);
+            let count = self.trait_counts.get(&key).unwrap_or(0);
+            self.trait_counts.insert(&key, &(count + 1));
+        }
+    }
+
+    pub(crate) fn internal_remove_trait_counts(&mut self, attributes: &[TraitAttribute]) {
+        for attribute in attributes {
+            let key = trait_key(attribute);
+            match self.trait_counts.get(&key).unwrap_or(0) {
+                0 | 1 => self.trait_counts.remove(&key),
+                count => self.trait_counts.insert(&key, &(count - 1)),
+            };
+        }
+    }
+
+    pub(crate) fn internal_remove_from_rarity_index(&mut self, token_id: &TokenId) {
+        if let Some(score) = self.rarity_index_scores.remove(token_id) {
+            self.rarity_index.remove(&(score, token_id.clone()));
+        }
+    }
+
+    fn internal_rarity_score(&self, token_id: &TokenId) -> u128 {
+        let total = self.tokens.owner_by_id.len() as u128;
+        self.token_extra(token_id)
+            .attributes
+            .iter()
+            .map(|attribute| {
+                let count = self.trait_counts.get(&trait_key(attribute)).unwrap_or(1).max(1) as u128;
+                total * RARITY_SCORE_SCALE / count
+            })
+            .sum()
+    }
+}
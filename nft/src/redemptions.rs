@@ -0,0 +1,115 @@
+use crate::*;
+
+//the longest encrypted shipping reference a redemption can carry, in bytes
+const MAX_SHIPPING_REF_LEN: usize = 1024;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub enum RedemptionStatus {
+    Pending,
+    Fulfilled,
+    Rejected,
+}
+
+/// A token burned for its physical item, tracked until the owner ships or rejects the claim.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct Redemption {
+    pub token_id: TokenId,
+    pub redeemer: AccountId,
+    //opaque to the contract, only the fulfiller can decrypt it
+    #[serde(rename = "ref")]
+    pub shipping_ref: String,
+    pub status: RedemptionStatus,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Burn `token_id` to claim its physical item, recording the encrypted shipping reference for
+    /// the owner to fulfill. The attached deposit pays for the redemption record.
+    #[payable]
+    pub fn redeem_physical(&mut self, token_id: TokenId, encrypted_shipping_ref: String) -> Redemption {
+        let redeemer = env::predecessor_account_id();
+        self.assert_token_owner(&token_id, &redeemer);
+        require!(
+            !encrypted_shipping_ref.is_empty() && encrypted_shipping_ref.len() <= MAX_SHIPPING_REF_LEN,
+            format!("Shipping reference must be 1 to {} bytes", MAX_SHIPPING_REF_LEN)
+        );
+        self.internal_burn(&token_id, &redeemer, None, None);
+
+        let initial_storage_usage = env::storage_usage();
+        let redemption = Redemption {
+            token_id: token_id.clone(),
+            redeemer,
+            shipping_ref: encrypted_shipping_ref,
+            status: RedemptionStatus::Pending,
+        };
+        self.redemptions.insert(&token_id, &redemption);
+        self.internal_set_redemption_status(&token_id, None, RedemptionStatus::Pending);
+        refund_storage_deposit(initial_storage_usage);
+        redemption
+    }
+
+    /// Move a pending redemption to `Fulfilled` or `Rejected`. A rejected redeemer is credited the
+    /// consolation from the treasury balance, withdrawable like holder rewards.
+    pub fn mark_redemption(&mut self, token_id: TokenId, status: RedemptionStatus) {
+        self.assert_owner();
+        require!(status != RedemptionStatus::Pending, "A redemption can't be moved back to pending");
+        let mut redemption = self.redemptions.get(&token_id)
+            .unwrap_or_else(|| env::panic_str("Redemption not found"));
+        require!(redemption.status == RedemptionStatus::Pending, "Redemption was already settled");
+
+        if status == RedemptionStatus::Rejected && self.redemption_consolation > 0 {
+            let consolation = self.redemption_consolation;
+            require!(consolation <= self.treasury_balance, "Treasury can't cover the consolation");
+            self.treasury_balance -= consolation;
+            let balance = self.balances_by_owner.get(&redemption.redeemer).unwrap_or(0);
+            self.balances_by_owner.insert(&redemption.redeemer, &(balance + consolation));
+            self.reward_pool += consolation;
+        }
+
+        self.internal_set_redemption_status(&token_id, Some(RedemptionStatus::Pending), status);
+        redemption.status = status;
+        self.redemptions.insert(&token_id, &redemption);
+    }
+
+    pub fn set_redemption_consolation(&mut self, consolation: U128) {
+        self.assert_owner();
+        self.redemption_consolation = consolation.0;
+    }
+
+    pub fn redemption(&self, token_id: TokenId) -> Option<Redemption> {
+        self.redemptions.get(&token_id)
+    }
+
+    pub fn redemptions_by_status(&self, status: RedemptionStatus, from_index: Option<U128>, limit: Option<u64>) -> Vec<Redemption> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        let tokens = match self.redemptions_by_status.get(&status) {
+            Some(tokens) => tokens,
+            None => return vec![],
+        };
+        tokens
+            .iter()
+            .skip(start)
+            .take(page_limit(limit))
+            .filter_map(|token_id| self.redemptions.get(&token_id))
+            .collect()
+    }
+}
+
+impl Contract {
+    /// Move `token_id` from the `from` status index to the `to` one.
+    fn internal_set_redemption_status(&mut self, token_id: &TokenId, from: Option<RedemptionStatus>, to: RedemptionStatus) {
+        if let Some(from) = from {
+            let mut tokens = self.redemptions_by_status.get(&from).unwrap();
+            tokens.remove(token_id);
+            self.redemptions_by_status.insert(&from, &tokens);
+        }
+        let mut tokens = self.redemptions_by_status.get(&to)
+            .unwrap_or_else(|| UnorderedSet::new(StorageKey::RedemptionsByStatus { status: to }));
+        tokens.insert(token_id);
+        self.redemptions_by_status.insert(&to, &tokens);
+    }
+}
@@ -0,0 +1,31 @@
+use crate::*;
+
+//the largest share of the owner's amount a referrer can earn
+const MAX_REFERRAL_BPS: u32 = 2_000;
+
+#[near_bindgen]
+impl Contract {
+    /// Set the share of the owner's amount paid to the `referrer` named on a mint.
+    pub fn set_referral_bps(&mut self, referral_bps: u32) {
+        self.assert_owner();
+        require!(
+            referral_bps <= MAX_REFERRAL_BPS,
+            format!("Referral share can't exceed {} bps", MAX_REFERRAL_BPS)
+        );
+        self.referral_bps = referral_bps;
+    }
+
+    pub fn referral_bps(&self) -> u32 {
+        self.referral_bps
+    }
+}
+
+impl Contract {
+    /// The share of `owner_amount` `referrer` earns for referring `minter`, which can't be the
+    /// minter itself or this contract.
+    pub(crate) fn referral_share(&self, referrer: &AccountId, minter: &AccountId, owner_amount: u128) -> u128 {
+        require!(referrer != minter, "Can't refer yourself");
+        require!(*referrer != env::current_account_id(), "The collection can't be a referrer");
+        owner_amount * self.referral_bps as u128 / 10_000
+    }
+}
@@ -0,0 +1,99 @@
+use crate::*;
+
+//NEAR held while a token id is reserved, on top of the reservation's storage
+pub const RESERVATION_DEPOSIT: u128 = 100_000_000_000_000_000_000_000;
+//how long a reservation holds, in nanoseconds
+pub const RESERVATION_PERIOD: u64 = 7 * 24 * 3_600 * 1_000_000_000;
+
+/// A token id held for `account_id` until `expires_at`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct Reservation {
+    pub account_id: AccountId,
+    pub deposit: U128,
+    pub expires_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Reserve `token_id` for the caller for `RESERVATION_PERIOD`, so only they can mint it. The
+    /// attached deposit must cover `RESERVATION_DEPOSIT` and the reservation's storage; it's
+    /// refunded when the caller mints the token or cancels, and forfeited to the treasury if the
+    /// reservation lapses.
+    #[payable]
+    pub fn reserve_token_id(&mut self, token_id: TokenId) -> Reservation {
+        assert_valid_token_id(&token_id);
+        assert_not_series_token_id(&token_id);
+        require!(!self.tokens.owner_by_id.contains_key(&token_id), "Token already exists");
+        if let Some(reservation) = self.reservations.get(&token_id) {
+            require!(reservation.expires_at.0 <= env::block_timestamp(), "Token id is already reserved");
+            self.internal_forfeit_reservation(&token_id, reservation);
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        let mut reservation = Reservation {
+            account_id: env::predecessor_account_id(),
+            deposit: U128(0),
+            expires_at: U64(env::block_timestamp() + RESERVATION_PERIOD),
+        };
+        // Measure the reservation's storage first, the deposit covering it has a fixed size
+        self.reservations.insert(&token_id, &reservation);
+        reservation.deposit = U128(RESERVATION_DEPOSIT + storage_cost_since(initial_storage_usage));
+        self.reservations.insert(&token_id, &reservation);
+
+        let attached = env::attached_deposit().as_yoctonear();
+        require!(
+            attached >= reservation.deposit.0,
+            format!("Must attach {} yoctoNEAR to reserve a token id", reservation.deposit.0)
+        );
+        if attached > reservation.deposit.0 {
+            Promise::new(reservation.account_id.clone())
+                .transfer(NearToken::from_yoctonear(attached - reservation.deposit.0));
+        }
+        reservation
+    }
+
+    /// Release the caller's reservation of `token_id` and refund its deposit.
+    pub fn cancel_reservation(&mut self, token_id: TokenId) {
+        let reservation = self.reservations.get(&token_id)
+            .unwrap_or_else(|| env::panic_str("Token id is not reserved"));
+        require!(reservation.account_id == env::predecessor_account_id(), "Only the reserver can cancel");
+        require!(env::block_timestamp() < reservation.expires_at.0, "The reservation has lapsed");
+        self.reservations.remove(&token_id);
+        Promise::new(reservation.account_id).transfer(NearToken::from_yoctonear(reservation.deposit.0));
+    }
+
+    /// The live reservation of `token_id`, if any. Lapsed reservations aren't returned.
+    pub fn reservation_of(&self, token_id: TokenId) -> Option<Reservation> {
+        self.reservations.get(&token_id)
+            .filter(|reservation| env::block_timestamp() < reservation.expires_at.0)
+    }
+}
+
+impl Contract {
+    /// Let `minter` mint `token_id`: a live reservation by someone else panics, the minter's own
+    /// reservation is refunded and a lapsed one is forfeited.
+    pub(crate) fn internal_claim_reservation(&mut self, token_id: &TokenId, minter: &AccountId) {
+        let Some(reservation) = self.reservations.get(token_id) else {
+            return;
+        };
+        if reservation.expires_at.0 <= env::block_timestamp() {
+            self.internal_forfeit_reservation(token_id, reservation);
+            return;
+        }
+        require!(reservation.account_id == *minter, format!("Token id {} is reserved", token_id));
+        self.reservations.remove(token_id);
+        Promise::new(reservation.account_id).transfer(NearToken::from_yoctonear(reservation.deposit.0));
+    }
+
+    /// Whether `token_id` is held by a reservation that hasn't lapsed.
+    pub(crate) fn is_reserved(&self, token_id: &TokenId) -> bool {
+        self.reservation_of(token_id.clone()).is_some()
+    }
+
+    fn internal_forfeit_reservation(&mut self, token_id: &TokenId, reservation: Reservation) {
+        self.reservations.remove(token_id);
+        Promise::new(self.treasury.clone()).transfer(NearToken::from_yoctonear(reservation.deposit.0));
+    }
+}
@@ -0,0 +1,66 @@
+use crate::*;
+
+//most tokens one reveal call announces, keeping the event log and the gas in bounds
+const REVEAL_BATCH: u64 = 100;
+
+#[near_bindgen]
+impl Contract {
+    /// Show `placeholder_metadata` for every token until `reveal`, e.g. until the drop sells out.
+    /// Minted tokens keep their real metadata underneath. Only before the first mint or while
+    /// still unrevealed, and the caller pays for the storage used.
+    #[payable]
+    pub fn set_placeholder_metadata(&mut self, placeholder_metadata: TokenMetadata) {
+        self.assert_owner();
+        require!(
+            !self.revealed || self.index == 0,
+            "The placeholder can only be set before the first mint or until the reveal"
+        );
+        self.metadata_limits.assert_valid(&placeholder_metadata);
+        let initial_storage_usage = env::storage_usage();
+        self.placeholder_metadata.set(&placeholder_metadata);
+        self.revealed = false;
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    /// Show every token's real metadata, announcing the first tokens' update. Call `reveal_range`
+    /// for the rest.
+    pub fn reveal(&mut self) {
+        self.assert_owner();
+        require!(!self.revealed, "The collection is already revealed");
+        self.revealed = true;
+        self.placeholder_metadata.remove();
+        self.reveal_range(U64(0), U64(REVEAL_BATCH));
+    }
+
+    /// Emit `nft_metadata_update` for the revealed tokens from `from` to `to` in enumeration
+    /// order, at most 100 per call.
+    pub fn reveal_range(&mut self, from: U64, to: U64) {
+        self.assert_owner();
+        require!(self.revealed, "The collection isn't revealed yet");
+        require!(from.0 < to.0, "The range is empty");
+        require!(to.0 - from.0 <= REVEAL_BATCH, format!("At most {} tokens can be revealed at once", REVEAL_BATCH));
+        let token_ids: Vec<TokenId> = self.tokens.owner_by_id
+            .iter()
+            .skip(from.0 as usize)
+            .take((to.0 - from.0) as usize)
+            .map(|(token_id, _)| token_id)
+            .collect();
+        if !token_ids.is_empty() {
+            Event::NftMetadataUpdate { token_ids: token_ids.iter().collect() }.emit();
+        }
+    }
+
+    pub fn is_revealed(&self) -> bool {
+        self.revealed
+    }
+}
+
+impl Contract {
+    /// `token` as views show it: with the placeholder metadata until the collection is revealed.
+    pub(crate) fn displayed_token(&self, mut token: Token) -> Token {
+        if !self.revealed {
+            token.metadata = self.placeholder_metadata.get();
+        }
+        token
+    }
+}
@@ -0,0 +1,233 @@
+use crate::*;
+use crate::staking::BASE_REWARD_WEIGHT;
+
+/// How a holder's share of the burn fees is weighted, before staking boosts: one share per
+/// holding account, or one per token held.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub enum RewardMode {
+    PerHolder,
+    PerToken,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Holder rewards `account_id` can withdraw: its credited balance plus what its weight accrued
+    /// since it was last settled. Nothing for an account excluded from rewards, whatever it was
+    /// credited before is still withdrawn with `withdraw`, see `balance_of`.
+    pub fn claimable_of(&self, account_id: AccountId) -> U128 {
+        if self.excluded_from_rewards.contains(&account_id) {
+            return U128(0);
+        }
+        U128(self.balance_of(account_id))
+    }
+
+    /// Sum of the reward weights of every holder, which burn fees are shared over.
+    pub fn total_reward_weight(&self) -> U128 {
+        U128(self.total_reward_weight)
+    }
+
+    /// Weigh burn fee shares per holder or per token held. Holders keep accruing with the weight
+    /// they were last settled at, so the new mode applies to each once its tokens next change
+    /// hands or `update_reward_weight` is called for it.
+    pub fn set_reward_mode(&mut self, reward_mode: RewardMode) {
+        self.assert_owner();
+        self.reward_mode = reward_mode;
+        Event::RewardModeChanged { reward_mode }.emit();
+    }
+
+    pub fn reward_mode(&self) -> RewardMode {
+        self.reward_mode
+    }
+
+    /// Stop `account_id` from sharing in burn fees, crediting it what it accrued so far. The
+    /// caller pays for the storage it uses.
+    #[payable]
+    pub fn exclude_from_rewards(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        let initial_storage_usage = env::storage_usage();
+        self.excluded_from_rewards.insert(&account_id);
+        self.internal_update_reward_weight(&account_id);
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    /// Let `account_id` share in burn fees again from now on.
+    pub fn include_in_rewards(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        require!(self.excluded_from_rewards.remove(&account_id), "Not excluded from rewards");
+        self.internal_update_reward_weight(&account_id);
+    }
+
+    pub fn excluded_from_rewards(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<AccountId> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.excluded_from_rewards.iter().skip(start).take(page_limit(limit)).collect()
+    }
+
+    /// Settle the rewards of `account_id` and bring its weight up to date with the reward mode.
+    pub fn update_reward_weight(&mut self, account_id: AccountId) -> U128 {
+        self.internal_update_reward_weight(&account_id);
+        U128(self.settled_reward_weight(&account_id))
+    }
+}
+
+impl Contract {
+    /// Weight `account_id` should accrue rewards with, by the reward mode and its staked tokens:
+    /// nothing unless it holds a token and isn't excluded from rewards.
+    pub(crate) fn current_reward_weight(&self, account_id: &AccountId) -> u128 {
+        if !self.holders.contains(account_id) || self.excluded_from_rewards.contains(account_id) {
+            return 0;
+        }
+        match self.reward_mode {
+            RewardMode::PerHolder => self.reward_weight(account_id),
+            RewardMode::PerToken => {
+                let staked = self.staked_counts.get(account_id).unwrap_or(0) as u128;
+                let unstaked = (self.owned_count(account_id) as u128).saturating_sub(staked);
+                unstaked * BASE_REWARD_WEIGHT + staked * self.stake_multiplier_bps as u128
+            }
+        }
+    }
+
+    /// Weight `account_id` accrues with until it is next settled.
+    pub(crate) fn settled_reward_weight(&self, account_id: &AccountId) -> u128 {
+        self.reward_weights.get(account_id).unwrap_or(0)
+    }
+
+    /// How many holders share the burn fee of a token `owner` burns: every holder but `owner`,
+    /// whether or not it stays a holder, and the accounts excluded from rewards.
+    pub(crate) fn rewarded_holder_count(&self, owner: &AccountId) -> u64 {
+        let excluded = self.excluded_from_rewards.iter().filter(|account_id| self.holders.contains(account_id)).count();
+        let burner = self.holders.contains(owner) && !self.excluded_from_rewards.contains(owner);
+        self.holders.len() - excluded as u64 - burner as u64
+    }
+
+    /// Rewards `account_id` accrued since it was last settled.
+    pub(crate) fn pending_rewards(&self, account_id: &AccountId) -> u128 {
+        accrued(self.settled_reward_weight(account_id), self.reward_per_weight)
+            .saturating_sub(self.reward_debts.get(account_id).unwrap_or(0))
+    }
+
+    /// Credit `account_id` the rewards it accrued so far to `balances_by_owner`, and have it
+    /// accrue with its current weight from now on. Runs after anything that changes the weight:
+    /// the tokens it holds or stakes, or the reward mode.
+    pub(crate) fn internal_update_reward_weight(&mut self, account_id: &AccountId) {
+        let pending = self.pending_rewards(account_id);
+        if pending > 0 {
+            let balance = self.balances_by_owner.get(account_id).unwrap_or(0);
+            self.balances_by_owner.insert(account_id, &balance.checked_add(pending).unwrap());
+        }
+        let old_weight = self.settled_reward_weight(account_id);
+        let weight = self.current_reward_weight(account_id);
+        self.total_reward_weight = self.total_reward_weight - old_weight + weight;
+        if weight == 0 {
+            self.reward_weights.remove(account_id);
+            self.reward_debts.remove(account_id);
+        } else {
+            if weight != old_weight {
+                self.reward_weights.insert(account_id, &weight);
+            }
+            self.reward_debts.insert(account_id, &accrued(weight, self.reward_per_weight));
+        }
+    }
+
+    /// Share `amount` between every holder but `owner` by weight, in constant time: the whole
+    /// yoctos per unit of weight are added to `reward_per_weight`, and each holder collects its
+    /// share when it is next settled. What doesn't divide evenly is credited to the treasury, so
+    /// the holders' shares and the dust add up to `amount` exactly. With no other holder to share
+    /// it, e.g. when `owner` is the only one, all of `amount` is dust. Returns the yoctos each
+    /// unit of weight was credited and the dust.
+    pub(crate) fn internal_distribute_rewards(&mut self, owner: &AccountId, amount: u128) -> (u128, u128) {
+        self.internal_update_reward_weight(owner);
+        if amount == 0 {
+            return (0, 0);
+        }
+        // Only the holders but `owner` are eligible, `owner` is still one if it kept other tokens
+        let owner_weight = self.settled_reward_weight(owner);
+        let eligible_weight = self.total_reward_weight - owner_weight;
+        let per_weight = amount.checked_div(eligible_weight).unwrap_or(0);
+        self.reward_per_weight = self.reward_per_weight.checked_add(per_weight).unwrap();
+        let dust = amount - per_weight * eligible_weight;
+        if dust > 0 {
+            let treasury = self.treasury.clone();
+            let balance = self.balances_by_owner.get(&treasury).unwrap_or(0);
+            self.balances_by_owner.insert(&treasury, &balance.checked_add(dust).unwrap());
+        }
+        self.reward_pool = self.reward_pool.checked_add(amount).unwrap();
+        // The owner's settlement point moves past this distribution, so it earns nothing from it
+        if owner_weight > 0 {
+            self.reward_debts.insert(owner, &accrued(owner_weight, self.reward_per_weight));
+        }
+        (per_weight, dust)
+    }
+}
+
+/// What `weight` has accrued at `reward_per_weight`.
+fn accrued(weight: u128, reward_per_weight: u128) -> u128 {
+    weight.checked_mul(reward_per_weight).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::MintOptions;
+    use crate::test_utils::{account, contract};
+
+    fn mint(contract: &mut Contract, token_id: &str, owner: &str) {
+        let metadata = TokenMetadata { title: Some(token_id.to_string()), ..Default::default() };
+        let options = MintOptions { price: Some(0), without_vault: true, ..Default::default() };
+        contract.internal_record_mint(token_id.to_string(), account(owner), metadata, account(owner), options);
+    }
+
+    /// Remove `token_id` as a burn does and share `amount` as its burn fee. Returns how many
+    /// holders the burn counted as sharing it.
+    fn burn(contract: &mut Contract, token_id: &str, owner: &str, amount: u128) -> u64 {
+        let rewarded = contract.rewarded_holder_count(&account(owner));
+        contract.internal_remove_token(&token_id.to_string(), &account(owner));
+        contract.internal_distribute_rewards(&account(owner), amount);
+        rewarded
+    }
+
+    fn balance(contract: &Contract, owner: &str) -> u128 {
+        contract.balance_of(account(owner))
+    }
+
+    #[test]
+    fn a_sole_holder_burning_credits_the_treasury() {
+        let mut contract = contract(None);
+        mint(&mut contract, "1", "alice.near");
+        assert_eq!(burn(&mut contract, "1", "alice.near", 1_000_000), 0);
+        assert_eq!(balance(&contract, "alice.near"), 0);
+        assert_eq!(balance(&contract, "treasury.near"), 1_000_000);
+        assert_eq!(contract.total_reward_weight, 0);
+    }
+
+    #[test]
+    fn a_burner_keeping_tokens_shares_nothing() {
+        let mut contract = contract(None);
+        mint(&mut contract, "1", "alice.near");
+        mint(&mut contract, "2", "alice.near");
+        mint(&mut contract, "3", "bob.near");
+        assert_eq!(burn(&mut contract, "1", "alice.near", 1_000_000), 1);
+        assert_eq!(balance(&contract, "alice.near"), 0);
+        assert_eq!(balance(&contract, "bob.near"), 1_000_000);
+        assert_eq!(balance(&contract, "treasury.near"), 0);
+
+        // Alice still holds a token, so she shares bob's burn
+        assert_eq!(burn(&mut contract, "3", "bob.near", 500_000), 1);
+        assert_eq!(balance(&contract, "alice.near"), 500_000);
+        assert_eq!(balance(&contract, "bob.near"), 1_000_000);
+    }
+
+    #[test]
+    fn the_other_of_two_holders_gets_the_whole_fee() {
+        let mut contract = contract(None);
+        mint(&mut contract, "1", "alice.near");
+        mint(&mut contract, "2", "bob.near");
+        // Bob's weight doesn't divide the odd yocto, which goes to the treasury
+        assert_eq!(burn(&mut contract, "1", "alice.near", 1_000_001), 1);
+        assert_eq!(balance(&contract, "alice.near"), 0);
+        assert_eq!(balance(&contract, "bob.near"), 1_000_000);
+        assert_eq!(balance(&contract, "treasury.near"), 1);
+        assert_eq!(contract.reward_pool, 1_000_001);
+    }
+}
@@ -0,0 +1,44 @@
+use crate::*;
+
+/// Most a token's royalties can take of a sale, in basis points.
+pub const MAX_ROYALTY_BPS: u32 = 5_000;
+/// Most accounts a token's royalties can pay, keeping payouts within a marketplace's gas.
+pub const MAX_ROYALTY_RECIPIENTS: usize = 6;
+
+#[near_bindgen]
+impl Contract {
+    /// Royalties set for `token_id` at mint, in basis points. Tokens without any pay the
+    /// contract-wide royalty to the collection owner.
+    pub fn nft_royalties(&self, token_id: TokenId) -> Option<HashMap<AccountId, u32>> {
+        self.token_royalties.get(&token_id)
+    }
+}
+
+/// Split `balance` by `royalties`, the rest going to `owner_id`.
+pub(crate) fn royalty_payout(
+    royalties: &HashMap<AccountId, u32>,
+    owner_id: &AccountId,
+    balance: u128,
+) -> HashMap<AccountId, U128> {
+    let mut payout: HashMap<AccountId, U128> = HashMap::new();
+    let mut total_bps = 0;
+    for (account_id, bps) in royalties.iter() {
+        payout.insert(account_id.clone(), royalty_to_payout(*bps as u128, balance));
+        total_bps += *bps as u128;
+    }
+    let owner_share = royalty_to_payout(10_000 - total_bps, balance).0;
+    payout.entry(owner_id.clone()).or_insert(U128(0)).0 += owner_share;
+    payout
+}
+
+pub(crate) fn assert_valid_royalties(royalties: &HashMap<AccountId, u32>) {
+    require!(
+        royalties.len() <= MAX_ROYALTY_RECIPIENTS,
+        format!("At most {} royalty recipients are allowed", MAX_ROYALTY_RECIPIENTS)
+    );
+    let total_bps: u32 = royalties.values().fold(0, |total, bps| total.saturating_add(*bps));
+    require!(
+        total_bps <= MAX_ROYALTY_BPS,
+        format!("Royalties can't exceed {} basis points", MAX_ROYALTY_BPS)
+    );
+}
@@ -0,0 +1,176 @@
+use crate::*;
+
+/// Who may mint: only allowlisted accounts, everyone, or only the collection owner.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub enum SalePhase {
+    Presale,
+    Public,
+    Closed,
+}
+
+/// What an account may mint during the presale: up to `quota` tokens at `price` each, in the
+/// base currency.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct Tier {
+    pub price: U128,
+    pub quota: U64,
+}
+
+/// When minting opens and closes, in nanoseconds. Either end can be left open.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SaleWindow {
+    pub sale_start: Option<U64>,
+    pub sale_end: Option<U64>,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn set_sale_phase(&mut self, sale_phase: SalePhase) {
+        self.assert_owner();
+        self.sale_phase = sale_phase;
+    }
+
+    /// Let `accounts` mint during the presale. The caller pays for the storage they use.
+    #[payable]
+    pub fn add_to_allowlist(&mut self, accounts: Vec<AccountId>) {
+        self.assert_owner();
+        let initial_storage_usage = env::storage_usage();
+        for account_id in accounts.iter() {
+            self.presale_allowlist.insert(account_id);
+        }
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    pub fn remove_from_allowlist(&mut self, accounts: Vec<AccountId>) {
+        self.assert_owner();
+        for account_id in accounts.iter() {
+            self.presale_allowlist.remove(account_id);
+        }
+    }
+
+    /// Put `accounts` in `tier`, letting them mint its quota at its price during the presale, or
+    /// take them out of their tier with `None`. Mints already made still count against a new
+    /// quota. The caller pays for the storage they use.
+    #[payable]
+    pub fn set_tier(&mut self, accounts: Vec<AccountId>, tier: Option<Tier>) {
+        self.assert_owner();
+        let initial_storage_usage = env::storage_usage();
+        for account_id in accounts.iter() {
+            match &tier {
+                Some(tier) => self.presale_tiers.insert(account_id, tier),
+                None => self.presale_tiers.remove(account_id),
+            };
+        }
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    pub fn tier_of(&self, account_id: AccountId) -> Option<Tier> {
+        self.presale_tiers.get(&account_id)
+    }
+
+    /// How many more tokens `account_id` can mint from its tier during the presale.
+    pub fn remaining_quota(&self, account_id: AccountId) -> U64 {
+        let quota = self.presale_tiers.get(&account_id).map_or(0, |tier| tier.quota.0);
+        U64(quota.saturating_sub(self.presale_tier_mints.get(&account_id).unwrap_or(0)))
+    }
+
+    pub fn set_sale_window(&mut self, sale_start: Option<U64>, sale_end: Option<U64>) {
+        self.assert_owner();
+        assert_valid_sale_window(sale_start, sale_end);
+        self.sale_start = sale_start.map(|start| start.0);
+        self.sale_end = sale_end.map(|end| end.0);
+    }
+
+    pub fn sale_window(&self) -> SaleWindow {
+        SaleWindow { sale_start: self.sale_start.map(U64), sale_end: self.sale_end.map(U64) }
+    }
+
+    /// Stop every mint, e.g. to fix a metadata mistake mid-drop. Transfers and burns still work.
+    pub fn pause_minting(&mut self) {
+        self.assert_owner();
+        self.mint_paused = true;
+    }
+
+    pub fn resume_minting(&mut self) {
+        self.assert_owner();
+        self.mint_paused = false;
+    }
+
+    pub fn is_mint_paused(&self) -> bool {
+        self.mint_paused
+    }
+
+    pub fn sale_phase(&self) -> SalePhase {
+        self.sale_phase
+    }
+
+    pub fn is_allowlisted(&self, account_id: AccountId) -> bool {
+        self.presale_allowlist.contains(&account_id)
+    }
+
+    pub fn get_allowlist(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<AccountId> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.presale_allowlist
+            .iter()
+            .skip(start)
+            .take(page_limit(limit))
+            .collect()
+    }
+}
+
+impl Contract {
+    pub(crate) fn assert_mint_not_paused(&self) {
+        require!(!self.mint_paused, "Minting is paused");
+    }
+
+    /// Panic unless the sale window and phase let `account_id` mint. The collection owner can
+    /// always mint.
+    pub(crate) fn assert_sale_open(&self, account_id: &AccountId) {
+        if *account_id == self.tokens.owner_id {
+            return;
+        }
+        let now = env::block_timestamp();
+        if let Some(sale_start) = self.sale_start {
+            require!(now >= sale_start, "The sale has not started");
+        }
+        if let Some(sale_end) = self.sale_end {
+            require!(now < sale_end, "The sale has ended");
+        }
+        match self.sale_phase {
+            SalePhase::Presale => require!(
+                self.presale_allowlist.contains(account_id) || self.presale_tiers.contains_key(account_id),
+                "Only allowlisted accounts can mint during the presale"
+            ),
+            SalePhase::Public => {}
+            SalePhase::Closed => env::panic_str("Minting is closed"),
+        }
+    }
+}
+
+impl Contract {
+    /// During the presale, count a mint by `account_id` against its tier's quota and return the
+    /// tier's price. `None` outside the presale, for the collection owner and for accounts without
+    /// a tier, which pay the usual price.
+    pub(crate) fn internal_use_tier(&mut self, account_id: &AccountId) -> Option<u128> {
+        if self.sale_phase != SalePhase::Presale || *account_id == self.tokens.owner_id {
+            return None;
+        }
+        let tier = self.presale_tiers.get(account_id)?;
+        let used = self.presale_tier_mints.get(account_id).unwrap_or(0);
+        require!(used < tier.quota.0, format!("Tier quota of {} mints used up", tier.quota.0));
+        self.presale_tier_mints.insert(account_id, &(used + 1));
+        Some(tier.price.0)
+    }
+}
+
+/// Panic unless the window ends after it starts.
+pub(crate) fn assert_valid_sale_window(sale_start: Option<U64>, sale_end: Option<U64>) {
+    if let (Some(start), Some(end)) = (sale_start, sale_end) {
+        require!(start.0 < end.0, "The sale must end after it starts");
+    }
+}
@@ -0,0 +1,119 @@
+use crate::*;
+
+/// Pricing and supply of a series minted alongside the collection's own tokens. Series share the
+/// collection's mint currency, holders, burn rewards and treasury.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesConfig {
+    pub name: String,
+    pub mint_price: U128,
+    pub payment_split_percent: U128,
+    //0 for an unlimited series
+    pub total_supply: U128,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct Series {
+    pub config: SeriesConfig,
+    pub minted: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesStats {
+    pub series_id: u32,
+    pub name: String,
+    pub mint_price: U128,
+    pub payment_split_percent: U128,
+    pub total_supply: U128,
+    pub minted: U128,
+}
+
+/// The price terms a token of a series is minted and burned under.
+pub(crate) struct SeriesTerms {
+    pub mint_price: u128,
+    pub payment_split_percent: u128,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Add a series minted with `nft_mint(series_id: ...)` under ids `"{series_id}-{n}"`. Series 0
+    /// is the collection's own configuration. The attached deposit pays for the series record.
+    #[payable]
+    pub fn create_collection(&mut self, config: SeriesConfig) -> u32 {
+        self.assert_owner();
+        require!(config.payment_split_percent.0 <= 100, "Payment split can't exceed 100 percent");
+        let initial_storage_usage = env::storage_usage();
+        let series_id = self.series.len() as u32 + 1;
+        self.series.insert(&series_id, &Series { config, minted: 0 });
+        refund_storage_deposit(initial_storage_usage);
+        series_id
+    }
+
+    pub fn series_stats(&self, series_id: u32) -> SeriesStats {
+        if series_id == 0 {
+            return SeriesStats {
+                series_id,
+                name: self.metadata.get().unwrap().name,
+                mint_price: U128(self.mint_price),
+                payment_split_percent: U128(self.payment_split_percent),
+                total_supply: U128(self.total_supply),
+                minted: U128(self.series_zero_minted()),
+            };
+        }
+        let series = self.series.get(&series_id).unwrap_or_else(|| env::panic_str("Series not found"));
+        SeriesStats {
+            series_id,
+            name: series.config.name,
+            mint_price: series.config.mint_price,
+            payment_split_percent: series.config.payment_split_percent,
+            total_supply: series.config.total_supply,
+            minted: U128(series.minted as u128),
+        }
+    }
+}
+
+impl Contract {
+    pub(crate) fn series_terms(&self, series_id: u32) -> SeriesTerms {
+        if series_id == 0 {
+            return SeriesTerms { mint_price: self.mint_price, payment_split_percent: self.payment_split_percent };
+        }
+        let series = self.series.get(&series_id).unwrap_or_else(|| env::panic_str("Series not found"));
+        SeriesTerms {
+            mint_price: series.config.mint_price.0,
+            payment_split_percent: series.config.payment_split_percent.0,
+        }
+    }
+
+    /// Count a mint of `series_id` against its supply and return the id of the new token.
+    pub(crate) fn internal_next_series_token(&mut self, series_id: u32) -> TokenId {
+        let mut series = self.series.get(&series_id).unwrap_or_else(|| env::panic_str("Series not found"));
+        series.minted += 1;
+        require!(
+            series.config.total_supply.0 == 0 || series.minted as u128 <= series.config.total_supply.0,
+            "Exceeded series supply"
+        );
+        self.series.insert(&series_id, &series);
+        self.series_minted += 1;
+        format!("{}-{}", series_id, series.minted)
+    }
+
+    /// Tokens minted under the collection's own configuration, leaving out every other series.
+    pub(crate) fn series_zero_minted(&self) -> u128 {
+        self.index - self.series_minted as u128
+    }
+}
+
+/// Panic if `token_id` has the `"{series_id}-{n}"` form series tokens are numbered with. Only the
+/// contract assigns such ids, so no mint or reservation can take a series' next token id first.
+pub(crate) fn assert_not_series_token_id(token_id: &TokenId) {
+    let series_form = token_id.split_once('-').is_some_and(|(series_id, n)| {
+        series_id.parse::<u32>().is_ok() && n.parse::<u64>().is_ok()
+    });
+    require!(
+        !series_form,
+        format!("Token id {} is reserved for series tokens, which the contract numbers", token_id)
+    );
+}
@@ -0,0 +1,161 @@
+use crate::*;
+
+/// A holder list recorded at a point in time, built over one or more pages of `holders`.
+#[derive(BorshDeserialize, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct Snapshot {
+    pub label: String,
+    pub entries: Vector<SnapshotEntry>,
+    //position in `holders` the next page starts from
+    pub cursor: u64,
+    pub finalized: bool,
+    pub started_at: u64,
+    pub finalized_at: Option<u64>,
+    //running sha256 over every recorded entry, so copies of the list can be checked against it
+    pub digest: [u8; 32],
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct SnapshotEntry {
+    pub account_id: AccountId,
+    pub token_count: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SnapshotView {
+    pub snapshot_id: u64,
+    pub label: String,
+    pub finalized: bool,
+    pub holder_count: u64,
+    pub started_at: U64,
+    pub finalized_at: Option<U64>,
+    pub digest: String,
+    pub entries: Vec<SnapshotEntry>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Start a snapshot of `holders` and record its first page. Snapshots with more holders than
+    /// fit in one call are completed with `continue_snapshot`, which should follow right away since
+    /// holders changing between pages are only recorded as of the page that reads them.
+    /// The attached deposit pays for the storage of the recorded entries.
+    #[payable]
+    pub fn take_snapshot(&mut self, label: String) -> u64 {
+        self.assert_owner();
+        let initial_storage_usage = env::storage_usage();
+        let snapshot_id = self.next_snapshot_id;
+        self.next_snapshot_id += 1;
+
+        let mut snapshot = Snapshot {
+            label,
+            entries: Vector::new(StorageKey::SnapshotEntries { snapshot_id }),
+            cursor: 0,
+            finalized: false,
+            started_at: env::block_timestamp(),
+            finalized_at: None,
+            digest: [0; 32],
+        };
+        self.internal_record_snapshot_page(&mut snapshot);
+        self.snapshots.insert(&snapshot_id, &snapshot);
+        refund_storage_deposit(initial_storage_usage);
+        snapshot_id
+    }
+
+    /// Record the next page of an unfinished snapshot. Returns whether it is now finalized.
+    #[payable]
+    pub fn continue_snapshot(&mut self, snapshot_id: u64) -> bool {
+        self.assert_owner();
+        let initial_storage_usage = env::storage_usage();
+        let mut snapshot = self.snapshots.get(&snapshot_id).expect("Snapshot not found");
+        require!(!snapshot.finalized, "Snapshot is finalized");
+
+        self.internal_record_snapshot_page(&mut snapshot);
+        self.snapshots.insert(&snapshot_id, &snapshot);
+        refund_storage_deposit(initial_storage_usage);
+        snapshot.finalized
+    }
+
+    /// Delete up to `limit` entries of a snapshot, removing the snapshot itself once it is empty.
+    /// Returns whether the snapshot is gone.
+    #[payable]
+    pub fn prune_snapshot(&mut self, snapshot_id: u64, limit: Option<u64>) -> bool {
+        self.assert_owner();
+        let mut snapshot = self.snapshots.get(&snapshot_id).expect("Snapshot not found");
+        for _ in 0..page_limit(limit) {
+            if snapshot.entries.pop().is_none() {
+                break;
+            }
+        }
+        if snapshot.entries.is_empty() {
+            self.snapshots.remove(&snapshot_id);
+            true
+        } else {
+            self.snapshots.insert(&snapshot_id, &snapshot);
+            false
+        }
+    }
+
+    pub fn get_snapshot(&self, snapshot_id: u64, from_index: Option<U128>, limit: Option<u64>) -> Option<SnapshotView> {
+        self.snapshots.get(&snapshot_id).map(|snapshot| {
+            let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+            let entries = snapshot.entries.iter().skip(start).take(page_limit(limit)).collect();
+            snapshot_view(snapshot_id, snapshot, entries)
+        })
+    }
+
+    /// Summaries of the stored snapshots, without their entries.
+    pub fn snapshots(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<SnapshotView> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.snapshots
+            .iter()
+            .skip(start)
+            .take(page_limit(limit))
+            .map(|(snapshot_id, snapshot)| snapshot_view(snapshot_id, snapshot, vec![]))
+            .collect()
+    }
+}
+
+impl Contract {
+    fn internal_record_snapshot_page(&self, snapshot: &mut Snapshot) {
+        let holders = self.holders.as_vector();
+        let end = (snapshot.cursor + SNAPSHOT_PAGE_SIZE).min(holders.len());
+        for index in snapshot.cursor..end {
+            let account_id = holders.get(index).unwrap();
+            let entry = SnapshotEntry {
+                token_count: self.owned_count(&account_id),
+                account_id,
+            };
+            let mut preimage = snapshot.digest.to_vec();
+            preimage.extend_from_slice(entry.account_id.as_bytes());
+            preimage.extend_from_slice(&entry.token_count.to_le_bytes());
+            snapshot.digest = env::sha256_array(&preimage);
+            snapshot.entries.push(&entry);
+        }
+        snapshot.cursor = end;
+
+        if snapshot.cursor >= holders.len() {
+            snapshot.finalized = true;
+            snapshot.finalized_at = Some(env::block_timestamp());
+        }
+    }
+}
+
+fn snapshot_view(snapshot_id: u64, snapshot: Snapshot, entries: Vec<SnapshotEntry>) -> SnapshotView {
+    SnapshotView {
+        snapshot_id,
+        label: snapshot.label,
+        finalized: snapshot.finalized,
+        holder_count: snapshot.entries.len(),
+        started_at: U64(snapshot.started_at),
+        finalized_at: snapshot.finalized_at.map(U64),
+        digest: hex(&snapshot.digest),
+        entries,
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
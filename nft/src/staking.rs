@@ -0,0 +1,120 @@
+use crate::*;
+
+//a holder's weight in burn distributions before any staking boost
+pub const BASE_REWARD_WEIGHT: u128 = 10_000;
+//each staked token adds this much over the base weight by default, so it counts double
+pub const DEFAULT_STAKE_MULTIPLIER_BPS: u32 = 20_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeInfo {
+    pub since: U64,
+    //set once unstaking started with a cooldown, the token is released from this time on
+    pub unlocks_at: Option<U64>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Stake `token_id`. It stays owned by the caller but can't be transferred, and boosts the
+    /// caller's share of burn distributions until it is unstaked. The attached deposit pays for
+    /// the stake record.
+    #[payable]
+    pub fn stake(&mut self, token_id: TokenId) {
+        let owner = env::predecessor_account_id();
+        self.assert_token_owner(&token_id, &owner);
+        self.assert_listable(&token_id);
+        self.assert_no_approvals(&token_id);
+
+        let initial_storage_usage = env::storage_usage();
+        self.staked.insert(&token_id, &StakeInfo { since: U64(env::block_timestamp()), unlocks_at: None });
+        self.staked_counts.insert(&owner, &(self.staked_counts.get(&owner).unwrap_or(0) + 1));
+        self.internal_update_reward_weight(&owner);
+        self.total_staked += 1;
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    /// Unstake `token_id`. Without a cooldown the token is released right away. Otherwise the
+    /// first call stops the boost and starts the cooldown, and a call after it ends releases the
+    /// token. Returns whether the token was released.
+    pub fn unstake(&mut self, token_id: TokenId) -> bool {
+        let owner = env::predecessor_account_id();
+        self.assert_token_owner(&token_id, &owner);
+        let mut info = self.staked.get(&token_id).unwrap_or_else(|| env::panic_str("Token is not staked"));
+        let now = env::block_timestamp();
+
+        match info.unlocks_at {
+            None => {
+                self.internal_remove_boost(&owner);
+                if self.unstake_cooldown > 0 {
+                    info.unlocks_at = Some(U64(now + self.unstake_cooldown));
+                    self.staked.insert(&token_id, &info);
+                    return false;
+                }
+            }
+            Some(unlocks_at) => require!(now >= unlocks_at.0, "Token is still cooling down"),
+        }
+        self.staked.remove(&token_id);
+        self.total_staked -= 1;
+        true
+    }
+
+    /// Weight of a staked token in burn distributions, in basis points of an unstaked one. Holders
+    /// accrue with the weight they had when last settled, so it can only change while nothing is
+    /// staked.
+    pub fn set_stake_multiplier_bps(&mut self, stake_multiplier_bps: u32) {
+        self.assert_owner();
+        require!(self.total_staked == 0, "The stake multiplier can't change while tokens are staked");
+        require!(
+            stake_multiplier_bps as u128 >= BASE_REWARD_WEIGHT,
+            "A staked token can't weigh less than an unstaked one"
+        );
+        self.stake_multiplier_bps = stake_multiplier_bps;
+    }
+
+    pub fn set_unstake_cooldown(&mut self, unstake_cooldown: U64) {
+        self.assert_owner();
+        self.unstake_cooldown = unstake_cooldown.0;
+    }
+
+    pub fn stake_info(&self, token_id: TokenId) -> Option<StakeInfo> {
+        self.staked.get(&token_id)
+    }
+
+    /// Tokens of `account_id`, still staked or cooling down, paged over the tokens it owns.
+    pub fn staked_tokens_of(&self, account_id: AccountId, from_index: Option<U128>, limit: Option<u64>) -> Vec<TokenId> {
+        let owned = match self.tokens.tokens_per_owner.as_ref().and_then(|by_owner| by_owner.get(&account_id)) {
+            Some(owned) => owned,
+            None => return vec![],
+        };
+        owned
+            .iter()
+            .skip(from_index.map_or(0, |index| index.0 as usize))
+            .take(page_limit(limit))
+            .filter(|token_id| self.staked.get(token_id).is_some())
+            .collect()
+    }
+
+    pub fn total_staked(&self) -> u64 {
+        self.total_staked
+    }
+}
+
+impl Contract {
+    /// Weight of `account_id` in a burn distribution: the base weight plus the boost of every
+    /// token it has staked.
+    pub(crate) fn reward_weight(&self, account_id: &AccountId) -> u128 {
+        let staked = self.staked_counts.get(account_id).unwrap_or(0) as u128;
+        BASE_REWARD_WEIGHT + staked * (self.stake_multiplier_bps as u128 - BASE_REWARD_WEIGHT)
+    }
+
+    fn internal_remove_boost(&mut self, owner: &AccountId) {
+        let count = self.staked_counts.get(owner).unwrap_or(0);
+        if count > 1 {
+            self.staked_counts.insert(owner, &(count - 1));
+        } else {
+            self.staked_counts.remove(owner);
+        }
+        self.internal_update_reward_weight(owner);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::*;
+use near_contract_standards::non_fungible_token::metadata::NFT_METADATA_SPEC;
+use near_sdk::test_utils::VMContextBuilder;
+use near_sdk::testing_env;
+
+pub(crate) fn account(name: &str) -> AccountId {
+    name.parse().unwrap()
+}
+
+/// A collection at `nft.near` owned by `owner.near`, minting for free in `mint_currency` with a
+/// 50% split and a 10% burn fee, called by its owner.
+pub(crate) fn contract(mint_currency: Option<&str>) -> Contract {
+    testing_env!(VMContextBuilder::new()
+        .current_account_id(account("nft.near"))
+        .predecessor_account_id(account("owner.near"))
+        .build());
+    let metadata = NFTContractMetadata {
+        spec: NFT_METADATA_SPEC.to_string(),
+        name: "Test".to_string(),
+        symbol: "TEST".to_string(),
+        icon: None,
+        base_uri: None,
+        reference: None,
+        reference_hash: None,
+    };
+    Contract::new(
+        account("owner.near"), metadata, U128(0), mint_currency.map(account), U128(50), U128(0), U128(10),
+        account("treasury.near"), U128(0), None, None, None, None, None, None, None, None,
+    )
+}
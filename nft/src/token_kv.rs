@@ -0,0 +1,74 @@
+use crate::*;
+
+//the most keys a token can carry, and the longest key or value in bytes
+pub const MAX_KV_KEYS: usize = 8;
+pub const MAX_KV_BYTES: usize = 128;
+
+#[near_bindgen]
+impl Contract {
+    /// Set `key` of `token_id` to `value`, or remove it with `None`. Only the collection owner and
+    /// the accounts it added with `add_kv_writer` can write. The attached deposit pays for added
+    /// storage, and storage released by a change is refunded to the caller.
+    #[payable]
+    pub fn set_token_kv(&mut self, token_id: TokenId, key: String, value: Option<String>) {
+        let writer = env::predecessor_account_id();
+        require!(
+            writer == self.tokens.owner_id || self.kv_writers.contains(&writer),
+            "Not allowed to write token values"
+        );
+        require!(self.tokens.owner_by_id.get(&token_id).is_some(), "Token not found");
+        require!(
+            !key.is_empty() && key.len() <= MAX_KV_BYTES,
+            format!("Key must be 1 to {} bytes", MAX_KV_BYTES)
+        );
+
+        let initial_storage_usage = env::storage_usage();
+        let mut extra = self.token_extra(&token_id);
+        match &value {
+            Some(value) => {
+                require!(value.len() <= MAX_KV_BYTES, format!("Value can't exceed {} bytes", MAX_KV_BYTES));
+                require!(
+                    extra.kv.contains_key(&key) || extra.kv.len() < MAX_KV_KEYS,
+                    format!("A token can carry at most {} keys", MAX_KV_KEYS)
+                );
+                extra.kv.insert(key.clone(), value.clone());
+            }
+            None => require!(extra.kv.remove(&key).is_some(), "Key is not set"),
+        }
+        self.token_extras.insert(&token_id, &extra);
+
+        if env::storage_usage() > initial_storage_usage {
+            refund_storage_deposit(initial_storage_usage);
+        } else {
+            let refund = storage_freed_since(initial_storage_usage) + env::attached_deposit().as_yoctonear();
+            if refund > 0 {
+                Promise::new(writer).transfer(NearToken::from_yoctonear(refund));
+            }
+        }
+
+        Event::TokenKvChanged { token_id: &token_id, key: &key, value: value.as_ref() }.emit();
+    }
+
+    /// Allow `account_id` to write token values. The attached deposit pays for the entry.
+    #[payable]
+    pub fn add_kv_writer(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        let initial_storage_usage = env::storage_usage();
+        self.kv_writers.insert(&account_id);
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    pub fn remove_kv_writer(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        require!(self.kv_writers.remove(&account_id), "Not a writer");
+    }
+
+    pub fn kv_writers(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<AccountId> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.kv_writers.iter().skip(start).take(page_limit(limit)).collect()
+    }
+
+    pub fn token_kv(&self, token_id: TokenId) -> HashMap<String, String> {
+        self.token_extra(&token_id).kv
+    }
+}
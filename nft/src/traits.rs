@@ -0,0 +1,82 @@
+use crate::*;
+
+//the most attributes a token can carry
+const MAX_ATTRIBUTES: usize = 32;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct TraitAttribute {
+    pub trait_type: String,
+    pub value: String,
+}
+
+/// A trait type tokens may carry, with the values it may take when restricted.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct TraitDefinition {
+    pub trait_type: String,
+    pub allowed_values: Option<Vec<String>>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Replace the schema minted attributes are validated against. The attached deposit pays for
+    /// its storage.
+    #[payable]
+    pub fn set_trait_schema(&mut self, schema: Vec<TraitDefinition>) {
+        self.assert_owner();
+        for (i, definition) in schema.iter().enumerate() {
+            require!(
+                schema[..i].iter().all(|other| other.trait_type != definition.trait_type),
+                format!("Trait type {} is defined twice", definition.trait_type)
+            );
+        }
+        let initial_storage_usage = env::storage_usage();
+        self.trait_schema.set(&schema);
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    pub fn get_trait_schema(&self) -> Vec<TraitDefinition> {
+        self.trait_schema.get().unwrap_or_default()
+    }
+
+    pub fn nft_attributes(&self, token_id: TokenId) -> Vec<TraitAttribute> {
+        self.token_extra(&token_id).attributes
+    }
+}
+
+impl Contract {
+    /// Panic unless `attributes` has at most one value per trait type and, once a schema is
+    /// registered, only uses its trait types and allowed values.
+    pub(crate) fn assert_valid_attributes(&self, attributes: &[TraitAttribute]) {
+        require!(
+            attributes.len() <= MAX_ATTRIBUTES,
+            format!("A token can have at most {} attributes", MAX_ATTRIBUTES)
+        );
+        for (i, attribute) in attributes.iter().enumerate() {
+            require!(
+                attributes[..i].iter().all(|other| other.trait_type != attribute.trait_type),
+                format!("Trait type {} is set twice", attribute.trait_type)
+            );
+        }
+
+        let schema = match self.trait_schema.get() {
+            Some(schema) => schema,
+            None => return,
+        };
+        for attribute in attributes {
+            let definition = schema
+                .iter()
+                .find(|definition| definition.trait_type == attribute.trait_type)
+                .unwrap_or_else(|| env::panic_str(&format!("Unknown trait type {}", attribute.trait_type)));
+            if let Some(allowed_values) = &definition.allowed_values {
+                require!(
+                    allowed_values.contains(&attribute.value),
+                    format!("{} is not an allowed value of {}", attribute.value, attribute.trait_type)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::*;
+
+//gas one vault-less mint of `claim_unsold` uses at most
+const GAS_PER_UNSOLD_MINT: Gas = Gas::from_tgas(10);
+//gas kept back for the events and the refund once the minting stops
+const GAS_FOR_UNSOLD_WRAP_UP: Gas = Gas::from_tgas(10);
+
+#[near_bindgen]
+impl Contract {
+    /// Once the sale has ended, mint up to `count` of the unsold tokens to `receiver_id`, with the
+    /// next free ids and no vault. Stops early when the attached gas runs low, so a large remainder
+    /// is swept over several calls, and returns how many tokens were minted. The attached deposit
+    /// pays for their storage and the excess is refunded.
+    #[payable]
+    pub fn claim_unsold(&mut self, receiver_id: AccountId, count: u64) -> u64 {
+        self.assert_owner();
+        self.assert_mint_not_paused();
+        match self.sale_end {
+            Some(sale_end) => require!(env::block_timestamp() >= sale_end, "The sale has not ended"),
+            None => env::panic_str("The sale has no end to claim unsold tokens after"),
+        }
+        require!(count > 0, "Nothing to mint");
+        let remaining = self.unsold_remaining().0;
+        require!(remaining > 0, "No unsold supply left");
+        let count = (count as u128).min(remaining) as usize;
+
+        let mut minted: Vec<TokenId> = Vec::with_capacity(count);
+        let mut storage_cost = 0;
+        while minted.len() < count
+            && env::used_gas().as_gas() + GAS_PER_UNSOLD_MINT.as_gas() + GAS_FOR_UNSOLD_WRAP_UP.as_gas()
+                <= env::prepaid_gas().as_gas()
+        {
+            let token_id = self.internal_assign_token_id(None);
+            let token_metadata = self.minted_metadata(TokenMetadata { title: Some(token_id.clone()), ..Default::default() });
+            let options = MintOptions {
+                price: Some(0),
+                without_vault: true,
+                currency: self.base_currency.clone(),
+                ..Default::default()
+            };
+            let (token, _, token_storage) =
+                self.internal_record_mint(token_id, receiver_id.clone(), token_metadata, receiver_id.clone(), options);
+            storage_cost += token_storage;
+            minted.push(token.token_id);
+        }
+        require!(!minted.is_empty(), "Attach more gas to mint an unsold token");
+        let deposit = env::attached_deposit().as_yoctonear();
+        require!(
+            deposit >= storage_cost,
+            format!("Must attach {} yoctoNEAR more to cover token storage", storage_cost - deposit)
+        );
+
+        let token_ids: Vec<&str> = minted.iter().map(|token_id| token_id.as_str()).collect();
+        let events: Vec<NftMint> = token_ids
+            .chunks(AIRDROP_EVENT_BATCH)
+            .map(|token_ids| NftMint { owner_id: &receiver_id, token_ids, memo: None })
+            .collect();
+        NftMint::emit_many(&events);
+
+        if deposit > storage_cost {
+            Promise::new(env::predecessor_account_id()).transfer(NearToken::from_yoctonear(deposit - storage_cost));
+        }
+        minted.len() as u64
+    }
+
+    /// How many tokens of the total supply are left to mint, whether the sale is still running
+    /// or they're waiting for `claim_unsold`. Always 0 without a supply cap.
+    pub fn unsold_remaining(&self) -> U128 {
+        U128(self.total_supply.saturating_sub(self.series_zero_minted() + self.vouchers_outstanding as u128))
+    }
+}
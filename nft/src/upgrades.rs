@@ -0,0 +1,113 @@
+use crate::*;
+use near_sdk::serde_json::{Map, Value};
+
+/// How many tokens of a tier `upgrade` burns into one token of `output_tier`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct UpgradeRule {
+    pub inputs_required: u32,
+    pub output_tier: u32,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Burn `token_ids`, all the caller's and of one tier, into a new token of the tier its
+    /// upgrade rule gives, minted to the caller with a fresh id and the tier set in the `tier`
+    /// field of its metadata `extra`. Each input goes through the full burn flow, with its vault
+    /// withdrawn to the caller. The attached deposit pays the new vault deposit and the new
+    /// token's storage; the mint price is not charged.
+    #[payable]
+    pub fn upgrade(&mut self, token_ids: Vec<TokenId>, new_metadata: TokenMetadata) -> Token {
+        self.assert_mint_not_paused();
+        let owner = env::predecessor_account_id();
+        let input_tier = token_ids.first()
+            .map(|token_id| self.tier_of_token(token_id))
+            .unwrap_or_else(|| env::panic_str("Nothing to upgrade"));
+        let rule = self.upgrade_rules.get(&input_tier)
+            .unwrap_or_else(|| env::panic_str(&format!("Tier {} tokens can't be upgraded", input_tier)));
+        require!(
+            token_ids.len() == rule.inputs_required as usize,
+            format!("Upgrading tier {} takes {} tokens", input_tier, rule.inputs_required)
+        );
+        for (i, token_id) in token_ids.iter().enumerate() {
+            require!(!token_ids[..i].contains(token_id), format!("Token {} is listed twice", token_id));
+            self.assert_token_owner(token_id, &owner);
+            self.assert_no_approvals(token_id);
+            require!(
+                self.tier_of_token(token_id) == input_tier,
+                format!("Token {} is not of tier {}", token_id, input_tier)
+            );
+        }
+
+        let token_id = self.next_free_token_id("upgraded");
+        for burned_token_id in token_ids.iter() {
+            self.internal_burn(burned_token_id, &owner, None, None);
+        }
+
+        let token = self.internal_mint(
+            token_id,
+            owner.clone(),
+            with_tier(new_metadata, rule.output_tier),
+            owner.clone(),
+            self.vault_deposit(),
+            env::attached_deposit().as_yoctonear(),
+            MintOptions { price: Some(0), currency: self.base_currency.clone(), ..Default::default() }
+        );
+        Event::Upgrade {
+            owner_id: &owner,
+            burned_token_ids: token_ids.iter().collect(),
+            token_id: &token.token_id,
+            tier: rule.output_tier,
+        }.emit();
+        token
+    }
+
+    /// Let `upgrade` burn `inputs_required` tokens of `input_tier` into one of `output_tier`,
+    /// replacing the tier's previous rule. Tokens without a tier are of tier 0.
+    pub fn set_upgrade_rule(&mut self, inputs_required: u32, input_tier: u32, output_tier: u32) {
+        self.assert_owner();
+        require!(inputs_required >= 2, "An upgrade takes at least 2 tokens");
+        require!(output_tier != input_tier, "An upgrade must change the tier");
+        self.upgrade_rules.insert(&input_tier, &UpgradeRule { inputs_required, output_tier });
+    }
+
+    pub fn remove_upgrade_rule(&mut self, input_tier: u32) {
+        self.assert_owner();
+        require!(self.upgrade_rules.remove(&input_tier).is_some(), "No upgrade rule for this tier");
+    }
+
+    pub fn upgrade_rules(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<(u32, UpgradeRule)> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.upgrade_rules.iter().skip(start).take(page_limit(limit)).collect()
+    }
+
+    pub fn token_tier(&self, token_id: TokenId) -> u32 {
+        require!(self.tokens.owner_by_id.contains_key(&token_id), "Token not found");
+        self.tier_of_token(&token_id)
+    }
+}
+
+impl Contract {
+    /// The `tier` in the metadata `extra` of `token_id`, 0 if it has none.
+    fn tier_of_token(&self, token_id: &TokenId) -> u32 {
+        self.tokens.token_metadata_by_id.as_ref()
+            .and_then(|by_id| by_id.get(token_id))
+            .and_then(|metadata| metadata.extra)
+            .and_then(|extra| near_sdk::serde_json::from_str::<Value>(&extra).ok())
+            .and_then(|extra| extra["tier"].as_u64())
+            .map_or(0, |tier| tier as u32)
+    }
+}
+
+/// `metadata` with `tier` set in its `extra`, which must be a JSON object if there is one.
+fn with_tier(mut metadata: TokenMetadata, tier: u32) -> TokenMetadata {
+    let mut extra: Map<String, Value> = match &metadata.extra {
+        Some(extra) => near_sdk::serde_json::from_str(extra)
+            .unwrap_or_else(|_| env::panic_str("The metadata extra must be a JSON object")),
+        None => Map::new(),
+    };
+    extra.insert("tier".to_string(), json!(tier));
+    metadata.extra = Some(Value::Object(extra).to_string());
+    metadata
+}
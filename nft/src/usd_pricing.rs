@@ -0,0 +1,148 @@
+use crate::*;
+
+//`usd_price` is in millionths of a dollar
+const USD_PRICE_DECIMALS: u8 = 6;
+//highest slippage a USD-priced mint may accept, in basis points
+const MAX_USD_SLIPPAGE_BPS: u32 = 2_000;
+//gas for reading the oracle's price, and for `resolve_quote` besides it
+const GAS_FOR_PRICE_LOOKUP: Gas = Gas::from_tgas(10);
+const GAS_FOR_RESOLVE_QUOTE: Gas = Gas::from_tgas(10);
+//gas `resolve_usd_mint` spends besides the mint itself
+const GAS_FOR_RESOLVE_USD_MINT: Gas = Gas::from_tgas(20);
+
+/// Price the collection's own NEAR mints in USD: `usd_price`, in millionths of a dollar, is
+/// converted at the NEAR price `price_oracle` reports for `asset_id` (e.g. `wrap.near` on
+/// priceoracle.near). A mint may pay up to `slippage_bps` less than the converted price, to cover
+/// the price moving between the quote and the mint.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct UsdPricing {
+    pub price_oracle: AccountId,
+    pub asset_id: String,
+    pub usd_price: U128,
+    pub slippage_bps: u32,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Peg the mint price to USD, or go back to the configured NEAR prices with `None`.
+    pub fn set_usd_pricing(&mut self, usd_pricing: Option<UsdPricing>) {
+        self.assert_owner();
+        if let Some(pricing) = &usd_pricing {
+            require!(pricing.usd_price.0 > 0, "The USD price must be positive");
+            require!(
+                pricing.slippage_bps <= MAX_USD_SLIPPAGE_BPS,
+                format!("The slippage can't exceed {} basis points", MAX_USD_SLIPPAGE_BPS)
+            );
+        }
+        self.usd_pricing = usd_pricing;
+    }
+
+    pub fn usd_pricing(&self) -> Option<UsdPricing> {
+        self.usd_pricing.clone()
+    }
+
+    /// The NEAR price of the next mint at the oracle's current rate, without the vault deposit
+    /// and storage.
+    pub fn quoted_mint_price(&self) -> Promise {
+        let pricing = self.usd_pricing.clone().unwrap_or_else(|| env::panic_str("The mint price is not pegged to USD"));
+        price_lookup(&pricing).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_RESOLVE_QUOTE)
+                .resolve_quote()
+        )
+    }
+
+    #[private]
+    pub fn resolve_quote(&self) -> U128 {
+        let pricing = self.usd_pricing.clone().unwrap_or_else(|| env::panic_str("The mint price is not pegged to USD"));
+        U128(oracle_near_price(&pricing).unwrap_or_else(|| env::panic_str("The price oracle returned no usable price")))
+    }
+
+    /// Mint for `minter` at the NEAR price the oracle just returned, if `deposit` covers it within
+    /// the slippage. Otherwise the deposit is refunded and nothing is minted. The deposit waits in
+    /// the minter's storage balance meanwhile, so it can still be claimed if the mint fails here.
+    #[private]
+    pub fn resolve_usd_mint(&mut self, minter: AccountId, args: MintArgs, deposit: U128) -> Option<Token> {
+        let balance = self.storage_deposits.remove(&minter).unwrap_or(0);
+        let earlier_balance = balance.saturating_sub(deposit.0);
+        let price = self.usd_pricing.as_ref().and_then(oracle_near_price).and_then(|near_price| {
+            let pricing = self.usd_pricing.as_ref().unwrap();
+            let vault = if self.mints_vault(near_price) { self.vault_deposit() } else { 0 };
+            let storage_estimate = estimate_token_storage(Some(&self.minted_metadata(args.token_metadata.clone())));
+            let affordable = near_price.min(deposit.0.saturating_sub(vault + storage_estimate));
+            let lowest = near_price * (10_000 - pricing.slippage_bps as u128) / 10_000;
+            Some(affordable).filter(|price| *price >= lowest)
+        });
+        let Some(price) = price else {
+            if earlier_balance > 0 {
+                self.storage_deposits.insert(&minter, &earlier_balance);
+            }
+            Promise::new(minter).transfer(NearToken::from_yoctonear(deposit.0));
+            return None;
+        };
+
+        let (token, _) = self.internal_nft_mint(minter.clone(), args, None, deposit.0, Some(minter.clone()), Some(price));
+        let refund = self.storage_deposits.get(&minter).unwrap_or(0);
+        if earlier_balance > 0 {
+            self.storage_deposits.insert(&minter, &earlier_balance);
+        } else {
+            self.storage_deposits.remove(&minter);
+        }
+        if refund > 0 {
+            Promise::new(minter).transfer(NearToken::from_yoctonear(refund));
+        }
+        Some(token)
+    }
+}
+
+impl Contract {
+    /// Start a USD-priced mint of `args` for the caller: look the NEAR price up and mint in
+    /// `resolve_usd_mint`, which makes every change to the collection.
+    pub(crate) fn internal_usd_mint(&mut self, args: MintArgs, deposit: u128) -> Promise {
+        let pricing = self.usd_pricing.clone().unwrap();
+        self.assert_mint_not_paused();
+        let minter = env::predecessor_account_id();
+        let balance = self.storage_deposits.get(&minter).unwrap_or(0);
+        self.storage_deposits.insert(&minter, &(balance + deposit));
+        price_lookup(&pricing).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(self.gas_per_mint(2, false).saturating_add(GAS_FOR_RESOLVE_USD_MINT))
+                .resolve_usd_mint(minter, args, U128(deposit))
+        )
+    }
+}
+
+/// Ask the oracle of `pricing` for the price of its asset.
+fn price_lookup(pricing: &UsdPricing) -> Promise {
+    Promise::new(pricing.price_oracle.clone()).function_call(
+        "get_price_data".to_string(),
+        json!({ "asset_ids": [pricing.asset_id] }).to_string().into_bytes(),
+        NearToken::from_yoctonear(0),
+        GAS_FOR_PRICE_LOOKUP,
+    )
+}
+
+/// The NEAR price of `pricing.usd_price`, in yoctoNEAR, from the oracle's answer to
+/// `price_lookup`. `None` when the lookup failed, the price is missing or it's out of date.
+fn oracle_near_price(pricing: &UsdPricing) -> Option<u128> {
+    let PromiseResult::Successful(value) = env::promise_result(0) else {
+        return None;
+    };
+    let data: near_sdk::serde_json::Value = near_sdk::serde_json::from_slice(&value).ok()?;
+    let timestamp: u64 = data["timestamp"].as_str()?.parse().ok()?;
+    let recency = data["recency_duration_sec"].as_u64()? * 1_000_000_000;
+    if env::block_timestamp().saturating_sub(timestamp) > recency {
+        return None;
+    }
+    let price = data["prices"].as_array()?
+        .iter()
+        .find(|price| price["asset_id"] == pricing.asset_id.as_str())?
+        .get("price")?;
+    let multiplier: u128 = price["multiplier"].as_str()?.parse().ok()?;
+    let decimals = u8::try_from(price["decimals"].as_u64()?).ok()?;
+    // The oracle prices one yoctoNEAR at `multiplier / 10^decimals` dollars
+    let scale = 10u128.checked_pow(decimals.checked_sub(USD_PRICE_DECIMALS)? as u32)?;
+    pricing.usd_price.0.checked_mul(scale)?.checked_div(multiplier)
+}
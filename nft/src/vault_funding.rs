@@ -0,0 +1,68 @@
+use crate::*;
+
+const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+//bounds of the per-byte storage price, a tenth to ten times the price at launch
+const MIN_NEAR_PER_STORAGE: u128 = DEFAULT_NEAR_PER_STORAGE / 10;
+const MAX_NEAR_PER_STORAGE: u128 = DEFAULT_NEAR_PER_STORAGE * 10;
+//bounds of the buffer for a vault's state
+const MIN_VAULT_STORAGE: u128 = ONE_NEAR / 100;
+const MAX_VAULT_STORAGE: u128 = ONE_NEAR;
+
+/// What each new vault is funded with, and the minimum `storage_deposit`, all in yoctoNEAR.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VaultFunding {
+    pub near_per_storage: U128,
+    pub vault_storage: U128,
+    pub storage_per_sale: U128,
+    pub vault_deposit: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Price per byte of the storage a vault's code takes, for when the network's price changes.
+    pub fn set_near_per_storage(&mut self, near_per_storage: U128) {
+        self.assert_owner();
+        require!(
+            (MIN_NEAR_PER_STORAGE..=MAX_NEAR_PER_STORAGE).contains(&near_per_storage.0),
+            format!("The storage price must be between {} and {}", MIN_NEAR_PER_STORAGE, MAX_NEAR_PER_STORAGE)
+        );
+        self.near_per_storage = near_per_storage.0;
+    }
+
+    /// Buffer sent to each vault for its state on top of its code's storage, e.g. after the vault
+    /// wasm changes.
+    pub fn set_vault_storage(&mut self, vault_storage: U128) {
+        self.assert_owner();
+        require!(
+            (MIN_VAULT_STORAGE..=MAX_VAULT_STORAGE).contains(&vault_storage.0),
+            "The vault storage must be between 0.01 and 1 NEAR"
+        );
+        self.vault_storage = vault_storage.0;
+    }
+
+    pub fn set_storage_per_sale(&mut self, storage_per_sale: U128) {
+        self.assert_owner();
+        require!(
+            storage_per_sale.0 > 0 && storage_per_sale.0 <= ONE_NEAR,
+            "The storage per sale must be positive and at most 1 NEAR"
+        );
+        self.storage_per_sale = storage_per_sale.0;
+    }
+
+    pub fn vault_funding(&self) -> VaultFunding {
+        VaultFunding {
+            near_per_storage: U128(self.near_per_storage),
+            vault_storage: U128(self.vault_storage),
+            storage_per_sale: U128(self.storage_per_sale),
+            vault_deposit: U128(self.vault_deposit()),
+        }
+    }
+}
+
+impl Contract {
+    /// NEAR sent to each new vault: the storage for its code plus a buffer for its state.
+    pub(crate) fn vault_deposit(&self) -> u128 {
+        self.near_per_storage * VAULT_CODE.len() as u128 + self.vault_storage
+    }
+}
@@ -0,0 +1,52 @@
+use crate::*;
+
+/// Who receives the NEAR left in a burned token's vault, its storage stake included, when the
+/// vault deletes itself.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub enum VaultBeneficiary {
+    Owner,
+    Treasury,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Deploy a vault for every paid mint, or with `false` mint every token without one, like a
+    /// free mint. Tokens keep whichever they were minted with.
+    pub fn set_vaults_enabled(&mut self, vaults_enabled: bool) {
+        self.assert_owner();
+        self.vaults_enabled = vaults_enabled;
+    }
+
+    pub fn vaults_enabled(&self) -> bool {
+        self.vaults_enabled
+    }
+
+    /// Send what is left in a burned token's vault to its last owner or to the treasury.
+    pub fn set_vault_beneficiary(&mut self, vault_beneficiary: VaultBeneficiary) {
+        self.assert_owner();
+        self.vault_beneficiary = vault_beneficiary;
+    }
+
+    pub fn vault_beneficiary(&self) -> VaultBeneficiary {
+        self.vault_beneficiary
+    }
+}
+
+impl Contract {
+    /// Whether a mint for `price` gets a vault. Free mints have nothing to put in one, so they
+    /// skip the vault deposit and pay only for the token's storage.
+    pub(crate) fn mints_vault(&self, price: u128) -> bool {
+        self.vaults_enabled && price > 0
+    }
+
+    /// The account a burned token's vault is deleted in favor of, `owner` being the token's
+    /// last owner.
+    pub(crate) fn vault_beneficiary_of(&self, owner: &AccountId) -> AccountId {
+        match self.vault_beneficiary {
+            VaultBeneficiary::Owner => owner.clone(),
+            VaultBeneficiary::Treasury => self.treasury.clone(),
+        }
+    }
+}
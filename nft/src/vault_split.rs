@@ -0,0 +1,47 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Let minters lock between `min_percent` and `max_percent` of a token's price in its vault
+    /// through `split_percent_override`, or stop overrides with `None`. Tokens already minted
+    /// keep their split.
+    pub fn set_vault_split_range(&mut self, range: Option<(U128, U128)>) {
+        self.assert_owner();
+        if let Some((min_percent, max_percent)) = range {
+            require!(min_percent.0 <= max_percent.0, "The minimum split can't exceed the maximum");
+            require!(max_percent.0 <= 100, "Payment split can't exceed 100 percent");
+        }
+        self.vault_split_range = range.map(|(min_percent, max_percent)| (min_percent.0, max_percent.0));
+    }
+
+    pub fn vault_split_range(&self) -> Option<(U128, U128)> {
+        self.vault_split_range.map(|(min_percent, max_percent)| (U128(min_percent), U128(max_percent)))
+    }
+
+    /// Percent of `token_id`'s price locked in its vault: the override it was minted with, or its
+    /// series' split. `None` for a token that doesn't exist.
+    pub fn vault_split_of(&self, token_id: TokenId) -> Option<U128> {
+        self.tokens.owner_by_id.get(&token_id)?;
+        let extra = self.token_extra(&token_id);
+        Some(U128(self.token_split_percent(&token_id, extra.series_id)))
+    }
+}
+
+impl Contract {
+    /// Panic unless `split_percent` is inside the range the owner allows overrides in.
+    pub(crate) fn assert_split_in_range(&self, split_percent: u128) {
+        let (min_percent, max_percent) = self.vault_split_range
+            .unwrap_or_else(|| env::panic_str("Vault split overrides are disabled"));
+        require!(
+            (min_percent..=max_percent).contains(&split_percent),
+            format!("The vault split must be between {} and {} percent", min_percent, max_percent)
+        );
+    }
+
+    /// Percent of `token_id`'s price its vault holds, with `series_id` the token's series.
+    pub(crate) fn token_split_percent(&self, token_id: &TokenId, series_id: u32) -> u128 {
+        self.token_vault_splits
+            .get(token_id)
+            .unwrap_or_else(|| self.series_terms(series_id).payment_split_percent)
+    }
+}
@@ -0,0 +1,57 @@
+use crate::*;
+
+/// Linear release schedule of the collection owner's mint proceeds, fixed at init.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingSchedule {
+    //timestamp in nanoseconds the release starts at
+    pub start: U64,
+    //nanoseconds until everything credited is released
+    pub duration: U64,
+}
+
+/// Mint proceeds credited to the collection owner while vesting, and how much was claimed.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct VestingLedger {
+    pub total: u128,
+    pub claimed: u128,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Transfer the currently released part of the owner's vested proceeds to the owner.
+    #[payable]
+    pub fn claim_vested(&mut self) -> U128 {
+        self.assert_owner();
+        let claimable = self.vested_claimable().0;
+        require!(claimable > 0, "Nothing to claim");
+        self.owner_vesting.claimed += claimable;
+        let collection_owner = self.tokens.owner_id.clone();
+        self.internal_send_funds(&self.base_currency, &collection_owner, claimable);
+        U128(claimable)
+    }
+
+    /// Total proceeds credited to the vesting ledger so far.
+    pub fn vested_total(&self) -> U128 {
+        U128(self.owner_vesting.total)
+    }
+
+    pub fn vested_claimable(&self) -> U128 {
+        let schedule = match &self.vesting {
+            Some(schedule) => schedule,
+            None => return U128(0),
+        };
+        let now = env::block_timestamp();
+        let elapsed = now.saturating_sub(schedule.start.0).min(schedule.duration.0);
+        // Split the product so a large total times a long duration can't overflow
+        let (total, elapsed, duration) = (self.owner_vesting.total, elapsed as u128, schedule.duration.0 as u128);
+        let released = total / duration * elapsed + total % duration * elapsed / duration;
+        U128(released.saturating_sub(self.owner_vesting.claimed))
+    }
+
+    pub fn vesting_schedule(&self) -> Option<VestingSchedule> {
+        self.vesting.clone()
+    }
+}
@@ -0,0 +1,281 @@
+use crate::*;
+
+//the most options a proposal can offer
+const MAX_PROPOSAL_OPTIONS: usize = 16;
+//gas for the callback that settles a treasury spend
+const GAS_FOR_RESOLVE_SPEND: Gas = Gas::from_tgas(10);
+
+/// What a proposal does once it passes. Option 0 of a `TreasurySpend` proposal approves it.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalKind {
+    Text,
+    TreasurySpend { receiver_id: AccountId, amount: U128 },
+}
+
+/// Bounds the owner sets on the quorum and pass thresholds proposals choose, and the delay
+/// between a proposal passing and its execution.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct GovernanceConfig {
+    pub min_quorum_bps: u32,
+    pub min_pass_bps: u32,
+    pub timelock: U64,
+}
+
+impl Default for GovernanceConfig {
+    fn default() -> Self {
+        Self { min_quorum_bps: 0, min_pass_bps: 5_001, timelock: U64(0) }
+    }
+}
+
+/// A holder vote. Tallies are kept up to date as votes are cast and changed, and are final once
+/// the proposal is finalized after `ends_at`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct Proposal {
+    pub creator: AccountId,
+    pub title: String,
+    pub description: String,
+    pub options: Vec<String>,
+    pub ends_at: U64,
+    //total voting weight behind each option
+    pub tallies: Vec<U128>,
+    pub voters: u64,
+    pub finalized: bool,
+    pub kind: ProposalKind,
+    //share of `eligible_weight` that must vote, and share of the votes option 0 needs, to pass
+    pub quorum_bps: u32,
+    pub pass_bps: u32,
+    //live tokens when the proposal was created
+    pub eligible_weight: u64,
+    //set when the proposal is finalized
+    pub passed: Option<bool>,
+    pub executed: bool,
+}
+
+/// What a new proposal does and the thresholds it must reach, each defaulting when left out.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalTerms {
+    pub kind: Option<ProposalKind>,
+    pub quorum_bps: Option<u32>,
+    pub pass_bps: Option<u32>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct Vote {
+    pub option_index: u32,
+    //tokens the voter held when the vote was cast
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalView {
+    pub proposal_id: u64,
+    #[serde(flatten)]
+    pub proposal: Proposal,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Open a proposal for holders to vote on until `ends_at` (nanoseconds). It is a text proposal
+    /// unless `terms` give its kind, and its thresholds default to the governance minimums. The
+    /// attached deposit pays for its storage.
+    #[payable]
+    pub fn create_proposal(
+        &mut self,
+        title: String,
+        description: String,
+        options: Vec<String>,
+        ends_at: U64,
+        terms: Option<ProposalTerms>
+    ) -> u64 {
+        self.assert_owner();
+        require!(
+            options.len() >= 2 && options.len() <= MAX_PROPOSAL_OPTIONS,
+            format!("A proposal needs between 2 and {} options", MAX_PROPOSAL_OPTIONS)
+        );
+        require!(ends_at.0 > env::block_timestamp(), "Proposal must end in the future");
+        let ProposalTerms { kind, quorum_bps, pass_bps } = terms.unwrap_or_default();
+        let quorum_bps = quorum_bps.unwrap_or(self.governance.min_quorum_bps);
+        let pass_bps = pass_bps.unwrap_or(self.governance.min_pass_bps);
+        require!(
+            quorum_bps >= self.governance.min_quorum_bps && quorum_bps <= 10_000,
+            format!("Quorum must be between {} and 10000 bps", self.governance.min_quorum_bps)
+        );
+        require!(
+            pass_bps >= self.governance.min_pass_bps && pass_bps <= 10_000,
+            format!("Pass threshold must be between {} and 10000 bps", self.governance.min_pass_bps)
+        );
+        let kind = kind.unwrap_or(ProposalKind::Text);
+        if let ProposalKind::TreasurySpend { amount, .. } = &kind {
+            require!(amount.0 > 0, "Treasury spend must be positive");
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        let proposal_id = self.next_proposal_id;
+        self.next_proposal_id += 1;
+        let proposal = Proposal {
+            creator: env::predecessor_account_id(),
+            title,
+            description,
+            tallies: vec![U128(0); options.len()],
+            options,
+            ends_at,
+            voters: 0,
+            finalized: false,
+            kind,
+            quorum_bps,
+            pass_bps,
+            eligible_weight: self.tokens.owner_by_id.len(),
+            passed: None,
+            executed: false,
+        };
+        self.proposals.insert(&proposal_id, &proposal);
+        refund_storage_deposit(initial_storage_usage);
+        proposal_id
+    }
+
+    /// Vote for `option_index` with a weight of the caller's current token count. Voting again
+    /// before the proposal ends replaces the earlier vote. The caller's tokens are locked until
+    /// the proposal ends, so they can't vote a second time from another account. The attached
+    /// deposit pays for the storage of a first vote.
+    #[payable]
+    pub fn vote(&mut self, proposal_id: u64, option_index: u32) {
+        let voter = env::predecessor_account_id();
+        let mut proposal = self.proposals.get(&proposal_id)
+            .unwrap_or_else(|| env::panic_str("Proposal not found"));
+        require!(env::block_timestamp() < proposal.ends_at.0, "Voting has closed");
+        require!((option_index as usize) < proposal.options.len(), "Unknown option");
+        let weight = self.owned_count(&voter);
+        require!(weight > 0, "Only holders can vote");
+
+        let initial_storage_usage = env::storage_usage();
+        if self.vote_locks.get(&voter).unwrap_or(0) < proposal.ends_at.0 {
+            self.vote_locks.insert(&voter, &proposal.ends_at.0);
+        }
+        let key = (proposal_id, voter);
+        match self.votes.get(&key) {
+            Some(previous) => proposal.tallies[previous.option_index as usize].0 -= previous.weight as u128,
+            None => proposal.voters += 1,
+        }
+        proposal.tallies[option_index as usize].0 += weight as u128;
+        self.votes.insert(&key, &Vote { option_index, weight });
+        self.proposals.insert(&proposal_id, &proposal);
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    /// Close a proposal whose voting period has passed, fixing its tally and whether it passed.
+    /// Anyone can call it.
+    pub fn finalize_proposal(&mut self, proposal_id: u64) -> ProposalView {
+        let mut proposal = self.proposals.get(&proposal_id)
+            .unwrap_or_else(|| env::panic_str("Proposal not found"));
+        require!(!proposal.finalized, "Proposal is already finalized");
+        require!(env::block_timestamp() >= proposal.ends_at.0, "Voting is still open");
+        let votes: u128 = proposal.tallies.iter().map(|tally| tally.0).sum();
+        let quorum = proposal.eligible_weight as u128 * proposal.quorum_bps as u128;
+        proposal.passed = Some(
+            votes > 0
+                && votes * 10_000 >= quorum
+                && proposal.tallies[0].0 * 10_000 >= votes * proposal.pass_bps as u128
+        );
+        proposal.finalized = true;
+        self.proposals.insert(&proposal_id, &proposal);
+        ProposalView { proposal_id, proposal }
+    }
+
+    /// Carry out a passed `TreasurySpend` proposal once its timelock has elapsed, paying from the
+    /// treasury balance. Anyone can call it, and a proposal executes at most once.
+    pub fn execute_proposal(&mut self, proposal_id: u64) -> Promise {
+        let mut proposal = self.proposals.get(&proposal_id)
+            .unwrap_or_else(|| env::panic_str("Proposal not found"));
+        require!(proposal.passed == Some(true), "Proposal has not passed");
+        require!(!proposal.executed, "Proposal was already executed");
+        require!(
+            env::block_timestamp() >= proposal.ends_at.0 + self.governance.timelock.0,
+            "Proposal is still timelocked"
+        );
+        let (receiver_id, amount) = match &proposal.kind {
+            ProposalKind::TreasurySpend { receiver_id, amount } => (receiver_id.clone(), amount.0),
+            ProposalKind::Text => env::panic_str("Proposal has nothing to execute"),
+        };
+        require!(
+            amount <= self.treasury_balance,
+            format!("Treasury holds {}, the proposal spends {}", self.treasury_balance, amount)
+        );
+
+        self.treasury_balance -= amount;
+        proposal.executed = true;
+        self.proposals.insert(&proposal_id, &proposal);
+        Promise::new(receiver_id)
+            .transfer(NearToken::from_yoctonear(amount))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_SPEND)
+                    .resolve_treasury_spend(proposal_id, U128(amount))
+            )
+    }
+
+    /// Undo a treasury spend whose transfer failed, so the proposal can be executed again.
+    #[private]
+    pub fn resolve_treasury_spend(&mut self, proposal_id: u64, amount: U128) -> bool {
+        if env::promise_results_count() == 1 && matches!(env::promise_result(0), PromiseResult::Successful(_)) {
+            return true;
+        }
+        self.treasury_balance += amount.0;
+        if let Some(mut proposal) = self.proposals.get(&proposal_id) {
+            proposal.executed = false;
+            self.proposals.insert(&proposal_id, &proposal);
+        }
+        false
+    }
+
+    /// Add the attached NEAR to the balance treasury spend proposals pay from.
+    #[payable]
+    pub fn fund_treasury(&mut self) -> U128 {
+        self.treasury_balance += env::attached_deposit().as_yoctonear();
+        U128(self.treasury_balance)
+    }
+
+    pub fn set_governance_config(&mut self, config: GovernanceConfig) {
+        self.assert_owner();
+        require!(
+            config.min_quorum_bps <= 10_000 && config.min_pass_bps <= 10_000,
+            "Thresholds are at most 10000 bps"
+        );
+        self.governance = config;
+    }
+
+    pub fn governance_config(&self) -> GovernanceConfig {
+        self.governance.clone()
+    }
+
+    pub fn treasury_balance(&self) -> U128 {
+        U128(self.treasury_balance)
+    }
+
+    pub fn get_proposal(&self, proposal_id: u64) -> Option<ProposalView> {
+        self.proposals.get(&proposal_id).map(|proposal| ProposalView { proposal_id, proposal })
+    }
+
+    pub fn proposals(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<ProposalView> {
+        let start = u128::from(from_index.unwrap_or(U128(0))) as usize;
+        self.proposals
+            .iter()
+            .skip(start)
+            .take(page_limit(limit))
+            .map(|(proposal_id, proposal)| ProposalView { proposal_id, proposal })
+            .collect()
+    }
+
+    pub fn vote_of(&self, proposal_id: u64, account_id: AccountId) -> Option<Vote> {
+        self.votes.get(&(proposal_id, account_id))
+    }
+}
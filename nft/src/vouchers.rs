@@ -0,0 +1,136 @@
+use crate::*;
+
+/// Funds held for an account's unredeemed vouchers.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct VoucherEscrow {
+    //NEAR covering the vault deposit, plus the price when minting for NEAR
+    pub near: u128,
+    //mint price taken from ft_deposits when the base currency is a fungible token
+    pub ft: u128,
+    //part of `near` the vaults are created with, the vault deposit when each voucher was bought
+    pub vault: u128,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Pre-sell a mint: take the full mint cost now and credit the caller one voucher, redeemable
+    /// for a token once the owner opens redemption and refundable until then.
+    #[payable]
+    pub fn buy_mint_voucher(&mut self) -> u64 {
+        require!(!self.redemption_open, "Voucher sales are closed");
+        let buyer = env::predecessor_account_id();
+        self.assert_supply_left(1);
+
+        let minimum_needed = self.vault_deposit();
+        let mut escrow = self.voucher_escrow.get(&buyer).unwrap_or_default();
+        let near_price = if let Some(ft_contract) = self.base_currency.clone() {
+            let amount = self.ft_deposit(&buyer, &ft_contract);
+            require!(amount >= self.mint_price, "Insufficient price to mint");
+            self.internal_set_ft_deposit(&buyer, &ft_contract, amount - self.mint_price);
+            escrow.ft = escrow.ft.checked_add(self.mint_price).unwrap();
+            minimum_needed
+        } else {
+            self.mint_price + minimum_needed
+        };
+        escrow.near = escrow.near.checked_add(near_price).unwrap();
+        escrow.vault = escrow.vault.checked_add(minimum_needed).unwrap();
+
+        let initial_storage_usage = env::storage_usage();
+        let count = self.vouchers.get(&buyer).unwrap_or(0) + 1;
+        self.vouchers.insert(&buyer, &count);
+        self.voucher_escrow.insert(&buyer, &escrow);
+        self.vouchers_outstanding += 1;
+        self.total_vouchers_sold += 1;
+
+        let required = near_price + storage_cost_since(initial_storage_usage);
+        let deposit = env::attached_deposit().as_yoctonear();
+        require!(deposit >= required, "Insufficient price to mint");
+        if deposit - required > 1 {
+            Promise::new(buyer).transfer(NearToken::from_yoctonear(deposit - required));
+        }
+        count
+    }
+
+    /// Consume one of the caller's vouchers to mint `token_id` to them with the escrowed funds,
+    /// at the price and vault deposit it was bought at. The attached deposit covers the storage of
+    /// the token.
+    #[payable]
+    pub fn redeem_voucher(&mut self, token_id: TokenId, token_metadata: TokenMetadata) -> Token {
+        self.assert_mint_not_paused();
+        require!(self.redemption_open, "Voucher redemption is not open");
+        let owner = env::predecessor_account_id();
+        // The escrowed FTs already left ft_deposits and stay on the contract for resolve_create
+        let (near, ft, vault) = self.internal_take_voucher(&owner);
+        let price = if self.base_currency.is_some() { ft } else { near - vault };
+
+        let near_available = near + env::attached_deposit().as_yoctonear();
+        self.internal_mint(
+            token_id,
+            owner.clone(),
+            token_metadata,
+            owner,
+            near,
+            near_available,
+            MintOptions {
+                price: Some(price),
+                currency: self.base_currency.clone(),
+                vault_deposit: Some(vault),
+                ..Default::default()
+            }
+        )
+    }
+
+    /// Refund one of the caller's vouchers. Only possible until redemption opens.
+    pub fn refund_voucher(&mut self) {
+        require!(!self.redemption_open, "Vouchers can't be refunded once redemption is open");
+        let owner = env::predecessor_account_id();
+        let (near, ft, _) = self.internal_take_voucher(&owner);
+        if let Some(ft_contract) = self.base_currency.clone().filter(|_| ft > 0) {
+            self.internal_credit_ft_deposit(&owner, &ft_contract, ft);
+        }
+        Promise::new(owner).transfer(NearToken::from_yoctonear(near));
+    }
+
+    #[payable]
+    pub fn open_redemption(&mut self) {
+        self.assert_owner();
+        self.redemption_open = true;
+    }
+
+    pub fn vouchers_of(&self, account_id: AccountId) -> u64 {
+        self.vouchers.get(&account_id).unwrap_or(0)
+    }
+
+    pub fn total_vouchers_sold(&self) -> u64 {
+        self.total_vouchers_sold
+    }
+}
+
+impl Contract {
+    /// Remove one voucher of `account_id` and return its share of the escrow as
+    /// `(near, ft, vault)`.
+    fn internal_take_voucher(&mut self, account_id: &AccountId) -> (u128, u128, u128) {
+        let count = self.vouchers.get(account_id).unwrap_or(0);
+        require!(count > 0, "No vouchers to use");
+        let mut escrow = self.voucher_escrow.get(account_id).unwrap_or_default();
+        // The last voucher takes whatever rounding left behind
+        let near = escrow.near / count as u128;
+        let ft = escrow.ft / count as u128;
+        let vault = escrow.vault / count as u128;
+        let (near, ft, vault) = if count == 1 { (escrow.near, escrow.ft, escrow.vault) } else { (near, ft, vault) };
+
+        if count == 1 {
+            self.vouchers.remove(account_id);
+            self.voucher_escrow.remove(account_id);
+        } else {
+            escrow.near -= near;
+            escrow.ft -= ft;
+            escrow.vault -= vault;
+            self.vouchers.insert(account_id, &(count - 1));
+            self.voucher_escrow.insert(account_id, &escrow);
+        }
+        self.vouchers_outstanding -= 1;
+        (near, ft, vault)
+    }
+}
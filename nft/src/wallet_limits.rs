@@ -0,0 +1,68 @@
+use crate::*;
+
+//blocks an account waits between mints unless the owner changes it: one mint per block
+pub const DEFAULT_MINT_COOLDOWN_BLOCKS: u64 = 1;
+
+#[near_bindgen]
+impl Contract {
+    /// Cap how many tokens can be minted to one account, whoever pays for them, or lift the cap
+    /// with `None`. Mints made before the change still count.
+    pub fn set_max_mints_per_wallet(&mut self, max_mints_per_wallet: Option<U64>) {
+        self.assert_owner();
+        self.max_mints_per_wallet = max_mints_per_wallet.map(|max| max.0);
+    }
+
+    pub fn max_mints_per_wallet(&self) -> Option<U64> {
+        self.max_mints_per_wallet.map(U64)
+    }
+
+    /// How many tokens have been minted to `account_id`, against `max_mints_per_wallet`.
+    pub fn mints_of(&self, account_id: AccountId) -> U64 {
+        U64(self.mints_by_wallet.get(&account_id).unwrap_or(0))
+    }
+
+    /// Make an account wait `blocks` blocks after a mint before its next one, e.g. 1 for one mint
+    /// per block, or 0 to turn the cooldown off.
+    pub fn set_mint_cooldown_blocks(&mut self, blocks: U64) {
+        self.assert_owner();
+        self.mint_cooldown_blocks = blocks.0;
+    }
+
+    pub fn mint_cooldown_blocks(&self) -> U64 {
+        U64(self.mint_cooldown_blocks)
+    }
+
+    /// Height of the block `account_id` last minted at, if it ever did.
+    pub fn last_mint_block(&self, account_id: AccountId) -> Option<U64> {
+        self.last_mint_blocks.get(&account_id).map(U64)
+    }
+}
+
+impl Contract {
+    /// Count `count` mints to `account_id`, panicking if that takes it past the per-wallet limit.
+    /// The collection owner isn't limited.
+    pub(crate) fn internal_count_wallet_mints(&mut self, account_id: &AccountId, count: u64) {
+        if *account_id == self.tokens.owner_id {
+            return;
+        }
+        let minted = self.mints_by_wallet.get(account_id).unwrap_or(0) + count;
+        if let Some(max) = self.max_mints_per_wallet {
+            require!(minted <= max, format!("Exceeded the limit of {} mints per wallet", max));
+        }
+        self.mints_by_wallet.insert(account_id, &minted);
+    }
+
+    /// Panic if `account_id` minted less than `mint_cooldown_blocks` blocks ago, then record this
+    /// block as its last mint. The collection owner has no cooldown.
+    pub(crate) fn internal_use_mint_cooldown(&mut self, account_id: &AccountId) {
+        if *account_id == self.tokens.owner_id || self.mint_cooldown_blocks == 0 {
+            return;
+        }
+        let height = env::block_height();
+        if let Some(last) = self.last_mint_blocks.get(account_id) {
+            let next = last + self.mint_cooldown_blocks;
+            require!(height >= next, format!("Minting too fast, wait until block {}", next));
+        }
+        self.last_mint_blocks.insert(account_id, &height);
+    }
+}
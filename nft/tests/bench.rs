@@ -0,0 +1,145 @@
+//! Gas benchmarks of the canonical flows. Run with `cargo test --features bench --test bench`;
+//! the per-receipt gas is written to `target/bench_report.json`.
+#![cfg(feature = "bench")]
+
+mod common;
+
+use common::*;
+use near_workspaces::result::ExecutionFinalResult;
+use near_workspaces::types::NearToken;
+use near_workspaces::Account;
+use serde_json::{json, Value};
+
+const TGAS: u64 = 1_000_000_000_000;
+
+// Gas budgets of each flow, summed over the receipts executed by the NFT contract
+const MINT_BUDGET: u64 = 60 * TGAS;
+const FT_MINT_BUDGET: u64 = 60 * TGAS;
+const TRANSFER_BUDGET: u64 = 20 * TGAS;
+const BURN_BUDGET: u64 = 250 * TGAS;
+const WITHDRAW_BUDGET: u64 = 20 * TGAS;
+
+struct Report(serde_json::Map<String, Value>);
+
+impl Report {
+    /// Record every receipt of `outcome` under `flow` and return the gas burnt by the NFT contract.
+    fn record(&mut self, flow: &str, setup: &Setup, outcome: &ExecutionFinalResult) -> u64 {
+        let receipts: Vec<Value> = outcome
+            .receipt_outcomes()
+            .iter()
+            .map(|receipt| json!({ "executor_id": receipt.executor_id, "gas_burnt": receipt.gas_burnt.as_gas() }))
+            .collect();
+        let contract_gas = outcome
+            .receipt_outcomes()
+            .iter()
+            .filter(|receipt| &receipt.executor_id == setup.contract.id())
+            .map(|receipt| receipt.gas_burnt.as_gas())
+            .sum();
+        self.0.insert(
+            flow.to_string(),
+            json!({
+                "total_gas_burnt": outcome.total_gas_burnt.as_gas(),
+                "contract_gas_burnt": contract_gas,
+                "receipts": receipts,
+            }),
+        );
+        contract_gas
+    }
+
+    fn write(&self) -> TestResult {
+        std::fs::write("target/bench_report.json", serde_json::to_string_pretty(&self.0)?)?;
+        Ok(())
+    }
+}
+
+async fn burn(setup: &Setup, account: &Account, token_id: &str) -> TestResult<ExecutionFinalResult> {
+    Ok(account
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": token_id }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?)
+}
+
+#[tokio::test]
+async fn bench_canonical_flows() -> TestResult {
+    let wasm = compile_bench_wasm()?;
+    let mut report = Report(serde_json::Map::new());
+
+    // NEAR mint, transfer, burn with a growing holder count, withdraw
+    let setup = init_with_wasm(&wasm, 0, json!({})).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+
+    let outcome = mint(&setup, &alice, "t1").await?;
+    assert!(outcome.is_success());
+    assert!(report.record("near_mint", &setup, &outcome) < MINT_BUDGET);
+
+    let outcome = alice
+        .call(setup.contract.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": bob.id(), "token_id": "t1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    assert!(report.record("transfer", &setup, &outcome) < TRANSFER_BUDGET);
+
+    for (holders, token_id) in [(1, "b1"), (100, "b100")] {
+        assert!(mint(&setup, &alice, token_id).await?.is_success());
+        seed_holders(&setup, holders).await?;
+        let outcome = burn(&setup, &alice, token_id).await?;
+        assert!(outcome.is_success(), "{:?}", outcome);
+        assert!(report.record(&format!("burn_{}_holders", holders), &setup, &outcome) < BURN_BUDGET);
+    }
+
+    let outcome = bob.call(setup.contract.id(), "withdraw").args_json(json!({})).max_gas().transact().await?;
+    assert!(outcome.is_success());
+    assert!(report.record("withdraw", &setup, &outcome) < WITHDRAW_BUDGET);
+
+    // The distribution doesn't visit the holders, so 1000 of them cost what one does
+    assert!(mint(&setup, &alice, "b1000").await?.is_success());
+    seed_holders(&setup, 1000).await?;
+    let outcome = burn(&setup, &alice, "b1000").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(report.record("burn_1000_holders", &setup, &outcome) < BURN_BUDGET);
+
+    report.write()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn bench_check_invariants_window_is_capped() -> TestResult {
+    let wasm = compile_bench_wasm()?;
+    let setup = init_with_wasm(&wasm, 0, json!({})).await?;
+    // Each seeded holder owns no tokens, so it shows up as one violation in the window
+    seed_holders(&setup, 501).await?;
+
+    let window = |args: Value| async {
+        let violations: Vec<String> = view(&setup, "check_invariants", args).await?;
+        TestResult::Ok(violations.iter().filter(|v| v.contains("owns no tokens")).count())
+    };
+    assert_eq!(window(json!({})).await?, 100);
+    assert_eq!(window(json!({ "limit": 500 })).await?, 500);
+    // A larger limit is truncated to the max rather than rejected
+    assert_eq!(window(json!({ "limit": 501 })).await?, 500);
+    assert_eq!(window(json!({ "from_index": "500", "limit": 500 })).await?, 1);
+    assert_eq!(window(json!({ "from_index": "501" })).await?, 0);
+    assert_eq!(window(json!({ "from_index": "10000" })).await?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bench_ft_mint() -> TestResult {
+    let (setup, ft, ft_owner) = init_ft_collection(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let mut report = Report(serde_json::Map::new());
+
+    let outcome = ft_mint(&setup, &ft, &ft_owner, &alice, "t1").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(report.record("ft_mint", &setup, &outcome) < FT_MINT_BUDGET);
+
+    std::fs::write("target/bench_report_ft.json", serde_json::to_string_pretty(&report.0)?)?;
+    Ok(())
+}
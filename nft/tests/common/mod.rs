@@ -0,0 +1,286 @@
+#![allow(dead_code)]
+
+use near_workspaces::network::Sandbox;
+use near_workspaces::result::ExecutionFinalResult;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract, Worker};
+use serde_json::{json, Value};
+
+pub type TestResult<T = ()> = Result<T, Box<dyn std::error::Error>>;
+
+pub const MINT_PRICE: NearToken = NearToken::from_near(1);
+// Vault code storage and state buffer, with headroom for the token's own storage
+pub const VAULT_DEPOSIT: NearToken = NearToken::from_near(2);
+
+pub struct Setup {
+    pub worker: Worker<Sandbox>,
+    pub contract: Contract,
+    pub owner: Account,
+    pub treasury: Account,
+}
+
+pub async fn init(total_supply: u128) -> TestResult<Setup> {
+    init_with(total_supply, json!({})).await
+}
+
+/// Deploy the contract and call `new`, overriding the default init args with `extra_args`.
+pub async fn init_with(total_supply: u128, extra_args: Value) -> TestResult<Setup> {
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    init_with_wasm(&contract_wasm, total_supply, extra_args).await
+}
+
+pub async fn init_with_wasm(contract_wasm: &[u8], total_supply: u128, extra_args: Value) -> TestResult<Setup> {
+    let worker = near_workspaces::sandbox().await?;
+    deploy(worker, contract_wasm, total_supply, extra_args).await
+}
+
+/// Deploy an FT, then a collection priced in it. The FT supply is owned by the returned account.
+pub async fn init_ft_collection(total_supply: u128) -> TestResult<(Setup, Contract, Account)> {
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let ft_wasm = near_workspaces::compile_project("../ft").await?;
+    let worker = near_workspaces::sandbox().await?;
+    let ft_owner = worker.dev_create_account().await?;
+    let ft = worker.dev_deploy(&ft_wasm).await?;
+    let outcome = ft
+        .call("new_default_meta")
+        .args_json(json!({ "owner_id": ft_owner.id(), "total_supply": "1000000000000000000000000000000" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let setup = deploy(worker, &contract_wasm, total_supply, json!({ "mint_currency": ft.id() })).await?;
+    for account_id in [setup.contract.id(), setup.owner.id(), setup.treasury.id()] {
+        ft_storage_deposit(&ft, &ft_owner, account_id.as_str()).await?;
+    }
+    Ok((setup, ft, ft_owner))
+}
+
+/// Deploy a second collection on the same sandbox, to act as a partner collection.
+pub async fn init_partner(setup: &Setup) -> TestResult<Setup> {
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    deploy(setup.worker.clone(), &contract_wasm, 0, json!({})).await
+}
+
+async fn deploy(worker: Worker<Sandbox>, contract_wasm: &[u8], total_supply: u128, extra_args: Value) -> TestResult<Setup> {
+    let contract = worker.dev_deploy(contract_wasm).await?;
+    let owner = worker.dev_create_account().await?;
+    let treasury = worker.dev_create_account().await?;
+
+    let mut args = json!({
+        "owner_id": owner.id(),
+        "metadata": {
+            "spec": "nft-1.0.0",
+            "name": "Test Collection",
+            "symbol": "TEST",
+        },
+        "mint_price": MINT_PRICE.as_yoctonear().to_string(),
+        "payment_split_percent": "50",
+        "total_supply": total_supply.to_string(),
+        "burn_fee": "10",
+        "treasury": treasury.id(),
+        "royalty": "500",
+    });
+    merge(&mut args, &extra_args);
+    let outcome = contract.call("new").args_json(args).transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    Ok(Setup { worker, contract, owner, treasury })
+}
+
+pub fn token_metadata(title: &str) -> Value {
+    json!({ "title": title })
+}
+
+pub async fn mint(setup: &Setup, minter: &Account, token_id: &str) -> TestResult<ExecutionFinalResult> {
+    mint_with_deposit(
+        setup,
+        minter,
+        token_id,
+        NearToken::from_yoctonear(MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear()),
+    )
+    .await
+}
+
+pub async fn mint_with_deposit(
+    setup: &Setup,
+    minter: &Account,
+    token_id: &str,
+    deposit: NearToken,
+) -> TestResult<ExecutionFinalResult> {
+    Ok(minter
+        .call(setup.contract.id(), "nft_mint")
+        .args_json(json!({
+            "token_id": token_id,
+            "token_owner_id": minter.id(),
+            "token_metadata": token_metadata(token_id),
+        }))
+        .deposit(deposit)
+        .max_gas()
+        .transact()
+        .await?)
+}
+
+/// Mint `token_id` to `minter`, overriding the default `nft_mint` args with `extra_args`.
+pub async fn mint_with_args(
+    setup: &Setup,
+    minter: &Account,
+    token_id: &str,
+    extra_args: Value,
+) -> TestResult<ExecutionFinalResult> {
+    let mut args = json!({
+        "token_id": token_id,
+        "token_owner_id": minter.id(),
+        "token_metadata": token_metadata(token_id),
+    });
+    merge(&mut args, &extra_args);
+    Ok(minter
+        .call(setup.contract.id(), "nft_mint")
+        .args_json(args)
+        .deposit(NearToken::from_yoctonear(MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear()))
+        .max_gas()
+        .transact()
+        .await?)
+}
+
+/// Burn `token_id` as `account` and assert it succeeded.
+pub async fn burn(setup: &Setup, account: &Account, token_id: &str) -> TestResult {
+    let outcome = account
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": token_id }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    Ok(())
+}
+
+fn merge(args: &mut Value, extra_args: &Value) {
+    if let (Some(args), Some(extra)) = (args.as_object_mut(), extra_args.as_object()) {
+        for (key, value) in extra {
+            args.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+pub async fn view<T: near_sdk::serde::de::DeserializeOwned>(setup: &Setup, method: &str, args: Value) -> TestResult<T> {
+    Ok(setup.contract.view(method).args_json(args).await?.json::<T>()?)
+}
+
+pub fn vault_id(setup: &Setup, token_id: &str) -> String {
+    format!("{}.{}", token_id, setup.contract.id())
+}
+
+/// Whether any receipt of `outcome` was executed by `account_id`.
+pub fn touched(outcome: &ExecutionFinalResult, account_id: &str) -> bool {
+    outcome
+        .receipt_outcomes()
+        .iter()
+        .any(|receipt| receipt.executor_id.as_str() == account_id)
+}
+
+pub async fn ft_storage_deposit(ft: &Contract, payer: &Account, account_id: &str) -> TestResult {
+    let outcome = payer
+        .call(ft.id(), "storage_deposit")
+        .args_json(json!({ "account_id": account_id }))
+        .deposit(NearToken::from_millinear(100))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    Ok(())
+}
+
+/// Send `amount` of `ft` from `sender` to `receiver_id` with `ft_transfer_call`.
+pub async fn ft_transfer_call(
+    ft: &Contract,
+    sender: &Account,
+    receiver_id: &str,
+    amount: u128,
+    msg: &str,
+) -> TestResult<ExecutionFinalResult> {
+    Ok(sender
+        .call(ft.id(), "ft_transfer_call")
+        .args_json(json!({ "receiver_id": receiver_id, "amount": amount.to_string(), "msg": msg }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?)
+}
+
+pub async fn ft_balance_of(ft: &Contract, account_id: &str) -> TestResult<u128> {
+    let balance: String = ft
+        .view("ft_balance_of")
+        .args_json(json!({ "account_id": account_id }))
+        .await?
+        .json()?;
+    Ok(balance.parse()?)
+}
+
+/// Fund `minter` with the mint price in `ft`, deposit it into the collection and mint `token_id`.
+pub async fn ft_mint(
+    setup: &Setup,
+    ft: &Contract,
+    ft_owner: &Account,
+    minter: &Account,
+    token_id: &str,
+) -> TestResult<ExecutionFinalResult> {
+    ft_storage_deposit(ft, ft_owner, minter.id().as_str()).await?;
+    let outcome = ft_owner
+        .call(ft.id(), "ft_transfer")
+        .args_json(json!({ "receiver_id": minter.id(), "amount": MINT_PRICE.as_yoctonear().to_string() }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = ft_transfer_call(ft, minter, setup.contract.id().as_str(), MINT_PRICE.as_yoctonear(), "").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    ft_mint_with_deposit(setup, ft, minter, token_id, VAULT_DEPOSIT).await
+}
+
+/// Mint `token_id` to `minter`, paying the price from its deposits of `ft`.
+pub async fn ft_mint_with_deposit(
+    setup: &Setup,
+    ft: &Contract,
+    minter: &Account,
+    token_id: &str,
+    deposit: NearToken,
+) -> TestResult<ExecutionFinalResult> {
+    Ok(minter
+        .call(setup.contract.id(), "nft_mint")
+        .args_json(json!({
+            "token_id": token_id,
+            "token_owner_id": minter.id(),
+            "token_metadata": token_metadata(token_id),
+            "pay_with": ft.id(),
+        }))
+        .deposit(deposit)
+        .max_gas()
+        .transact()
+        .await?)
+}
+
+/// Build the contract with the `bench` feature, whose dev helpers seed large states.
+pub fn compile_bench_wasm() -> TestResult<Vec<u8>> {
+    let status = std::process::Command::new("cargo")
+        .args(["build", "--release", "--target", "wasm32-unknown-unknown", "--features", "bench"])
+        .status()?;
+    assert!(status.success(), "failed to build the bench wasm");
+    Ok(std::fs::read("target/wasm32-unknown-unknown/release/nft.wasm")?)
+}
+
+/// Insert `count` token-less accounts into the holders of a `bench` build, in batches that fit in
+/// a call's gas.
+pub async fn seed_holders(setup: &Setup, count: usize) -> TestResult {
+    let accounts: Vec<String> = (0..count).map(|i| format!("holder-{}.test.near", i)).collect();
+    for batch in accounts.chunks(200) {
+        let outcome = setup
+            .owner
+            .call(setup.contract.id(), "dev_seed_holders")
+            .args_json(json!({ "accounts": batch }))
+            .max_gas()
+            .transact()
+            .await?;
+        assert!(outcome.is_success(), "{:?}", outcome);
+    }
+    Ok(())
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_affiliate_mint_pays_and_counts() -> TestResult {
+    let setup = init(0).await?;
+    let affiliate = setup.worker.dev_create_account().await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    for code in ["Upper", "ab", "has space"] {
+        let outcome = affiliate
+            .call(setup.contract.id(), "register_affiliate")
+            .args_json(json!({ "code": code }))
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+            .await?;
+        assert!(outcome.is_failure(), "{} should be rejected", code);
+    }
+    let outcome = affiliate
+        .call(setup.contract.id(), "register_affiliate")
+        .args_json(json!({ "code": "friends-1" }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = alice
+        .call(setup.contract.id(), "register_affiliate")
+        .args_json(json!({ "code": "friends-1" }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "codes are unique");
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_affiliate_bps")
+        .args_json(json!({ "affiliate_bps": 1000 }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+
+    let affiliate_before = affiliate.view_account().await?.balance;
+    let outcome = alice
+        .call(setup.contract.id(), "nft_mint")
+        .args_json(json!({
+            "token_id": "a1",
+            "token_owner_id": alice.id(),
+            "token_metadata": token_metadata("a1"),
+            "affiliate_code": "friends-1",
+        }))
+        .deposit(NearToken::from_yoctonear(MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear()))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    // 10% of the owner's half of the price
+    let share = MINT_PRICE.as_yoctonear() / 2 / 10;
+    let affiliate_after = affiliate.view_account().await?.balance;
+    assert_eq!(affiliate_after.as_yoctonear() - affiliate_before.as_yoctonear(), share);
+
+    let info: Value = view(&setup, "affiliate_info", json!({ "code": "friends-1" })).await?;
+    assert_eq!(info["mints_referred"], 1);
+    assert_eq!(info["total_earned"], share.to_string());
+    let leaderboard: Vec<Value> = view(&setup, "affiliate_leaderboard", json!({})).await?;
+    assert_eq!(leaderboard.len(), 1);
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "remove_affiliate")
+        .args_json(json!({ "code": "friends-1" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    assert!(view::<Option<Value>>(&setup, "affiliate_info", json!({ "code": "friends-1" })).await?.is_none());
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_airdrop_mints_vaultless_tokens_to_every_receiver() -> TestResult {
+    let setup = init(4).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let airdrop = |caller: &near_workspaces::Account, receivers: Vec<&str>| {
+        caller
+            .call(setup.contract.id(), "airdrop")
+            .args_json(json!({ "receivers": receivers, "base_metadata": token_metadata("Winner") }))
+            .deposit(NearToken::from_millinear(500))
+            .max_gas()
+            .transact()
+    };
+    assert!(airdrop(&alice, vec![alice.id().as_str()]).await?.is_failure(), "owner only");
+
+    let outcome = airdrop(&setup.owner, vec![alice.id().as_str(), bob.id().as_str(), alice.id().as_str()]).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(["0", "1", "2"].iter().all(|token_id| !touched(&outcome, &vault_id(&setup, token_id))));
+    let mint_logs = outcome.logs().iter().filter(|log| log.contains("\"event\":\"nft_mint\"")).count();
+    assert_eq!(mint_logs, 1, "one log with an entry per receiver");
+    let token_ids: Vec<String> = outcome.json()?;
+    assert_eq!(token_ids, vec!["0", "1", "2"]);
+
+    let token: Value = view(&setup, "nft_token", json!({ "token_id": "1" })).await?;
+    assert_eq!(token["owner_id"], bob.id().to_string());
+    assert_eq!(token["metadata"]["title"], "Winner #1");
+    assert_eq!(view::<u128>(&setup, "index", json!({})).await?, 3);
+    assert_eq!(view::<u64>(&setup, "total_holders", json!({})).await?, 2);
+
+    // The last token of the supply is left, burning an airdropped token needs no vault
+    assert!(airdrop(&setup.owner, vec![alice.id().as_str(), bob.id().as_str()]).await?.is_failure());
+    burn(&setup, &bob, "1").await?;
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::*;
+use near_crypto::{KeyType, SecretKey, Signature};
+use near_sdk::json_types::Base64VecU8;
+use near_workspaces::Account;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+/// The signer's proof that `account` may mint in `phase_id`.
+fn proof(signer: &SecretKey, account: &Account, phase_id: u32) -> Base64VecU8 {
+    let mut message = account.id().as_bytes().to_vec();
+    message.extend_from_slice(&phase_id.to_le_bytes());
+    match signer.sign(&Sha256::digest(&message)) {
+        Signature::ED25519(signature) => Base64VecU8::from(signature.to_bytes().to_vec()),
+        _ => unreachable!(),
+    }
+}
+
+#[tokio::test]
+async fn test_signed_allowlist_proofs_are_bound_to_the_phase() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let signer = SecretKey::from_random(KeyType::ED25519);
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_allowlist_signer")
+        .args_json(json!({ "signer_pk": signer.public_key().to_string() }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(mint(&setup, &alice, "a1").await?.is_failure(), "a proof is required");
+
+    let with_proof = |proof: &Base64VecU8| json!({ "allowlist_proof": proof });
+    let outcome = mint_with_args(&setup, &bob, "b1", with_proof(&proof(&signer, &alice, 0))).await?;
+    assert!(outcome.is_failure(), "proofs are bound to the account");
+    let forged = SecretKey::from_random(KeyType::ED25519);
+    let outcome = mint_with_args(&setup, &alice, "a1", with_proof(&proof(&forged, &alice, 0))).await?;
+    assert!(outcome.is_failure(), "proofs must come from the signer");
+
+    let presale = proof(&signer, &alice, 0);
+    let outcome = mint_with_args(&setup, &alice, "a1", with_proof(&presale)).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(view::<u32>(&setup, "allowlist_mints_of", json!({ "account_id": alice.id() })).await?, 1);
+    let outcome = mint_with_args(&setup, &alice, "a2", with_proof(&presale)).await?;
+    assert!(outcome.is_failure(), "the wallet limit is one mint per phase");
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_allowlist_phase")
+        .args_json(json!({ "phase_id": 1, "wallet_limit": 2 }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = mint_with_args(&setup, &alice, "a2", with_proof(&presale)).await?;
+    assert!(outcome.is_failure(), "presale proofs can't be reused in a later phase");
+    let public = proof(&signer, &alice, 1);
+    for token_id in ["a2", "a3"] {
+        let outcome = mint_with_args(&setup, &alice, token_id, with_proof(&public)).await?;
+        assert!(outcome.is_success(), "{:?}", outcome);
+    }
+    assert!(mint_with_args(&setup, &alice, "a4", with_proof(&public)).await?.is_failure());
+
+    Ok(())
+}
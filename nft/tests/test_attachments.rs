@@ -0,0 +1,87 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use near_workspaces::Account;
+use serde_json::{json, Value};
+
+async fn attach(partner: &Setup, setup: &Setup, account: &Account, token_id: &str, to_token_id: &str) -> TestResult {
+    let outcome = account
+        .call(partner.contract.id(), "nft_transfer_call")
+        .args_json(json!({
+            "receiver_id": setup.contract.id(),
+            "token_id": token_id,
+            "msg": json!({ "action": "attach", "to_token_id": to_token_id }).to_string(),
+        }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    Ok(())
+}
+
+async fn partner_owner(partner: &Setup, token_id: &str) -> TestResult<String> {
+    let token: Value = view(partner, "nft_token", json!({ "token_id": token_id })).await?;
+    Ok(token["owner_id"].as_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn test_attachments_follow_the_host_token() -> TestResult {
+    let setup = init(0).await?;
+    let partner = init_partner(&setup).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    for token_id in ["p1", "p2"] {
+        assert!(mint(&partner, &alice, token_id).await?.is_success());
+    }
+    for account in [&alice, &bob] {
+        let outcome = account
+            .call(setup.contract.id(), "storage_deposit")
+            .args_json(json!({}))
+            .deposit(NearToken::from_millinear(100))
+            .transact()
+            .await?;
+        assert!(outcome.is_success());
+    }
+
+    attach(&partner, &setup, &alice, "p1", "a1").await?;
+    assert_eq!(partner_owner(&partner, "p1").await?, setup.contract.id().as_str());
+    let attached: Vec<Value> = view(&setup, "attachments_of", json!({ "token_id": "a1" })).await?;
+    assert_eq!(attached.len(), 1);
+    assert_eq!(attached[0]["token_id"], "p1");
+
+    let outcome = alice
+        .call(setup.contract.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": bob.id(), "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let detach = |account: &Account| {
+        account
+            .call(setup.contract.id(), "detach")
+            .args_json(json!({ "to_token_id": "a1", "index": 0 }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+    };
+    assert!(detach(&alice).await?.is_failure(), "attachments moved with the host token");
+    assert!(detach(&bob).await?.is_success());
+    assert_eq!(partner_owner(&partner, "p1").await?, bob.id().as_str());
+
+    let outcome = alice
+        .call(partner.contract.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": bob.id(), "token_id": "p2" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    attach(&partner, &setup, &bob, "p2", "a1").await?;
+    burn(&setup, &bob, "a1").await?;
+    assert_eq!(partner_owner(&partner, "p2").await?, bob.id().as_str());
+
+    Ok(())
+}
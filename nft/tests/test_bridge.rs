@@ -0,0 +1,76 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_bridge_lock_blocks_the_token_until_unlocked() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bridge = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    let lock = || {
+        alice
+            .call(setup.contract.id(), "lock_for_bridge")
+            .args_json(json!({ "token_id": "a1", "dest_chain_address": "0xabc" }))
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+    };
+    assert!(lock().await?.is_failure(), "no bridge account is configured");
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_bridge_account")
+        .args_json(json!({ "bridge_account": bridge.id() }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+
+    let outcome = lock().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(outcome.logs().iter().any(|log| log.contains("\"event\":\"bridge_lock\"")));
+    let nonce: String = outcome.json()?;
+
+    let outcome = alice
+        .call(setup.contract.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": bridge.id(), "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "locked tokens can't move");
+    let outcome = alice
+        .call(setup.contract.id(), "nft_approve")
+        .args_json(json!({ "token_id": "a1", "account_id": bridge.id() }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "locked tokens can't be listed");
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "locked tokens can't burn");
+
+    let locked: Vec<Value> = view(&setup, "locked_tokens", json!({})).await?;
+    assert_eq!(locked.len(), 1);
+    assert_eq!(locked[0]["lock"]["dest_chain_address"], "0xabc");
+
+    let unlock = |account: &near_workspaces::Account, nonce: String| {
+        account
+            .call(setup.contract.id(), "unlock_from_bridge")
+            .args_json(json!({ "token_id": "a1", "proof_nonce": nonce }))
+            .transact()
+    };
+    assert!(unlock(&alice, nonce.clone()).await?.is_failure(), "only the bridge unlocks");
+    assert!(unlock(&bridge, "99".to_string()).await?.is_failure(), "the nonce must match");
+    assert!(unlock(&bridge, nonce).await?.is_success());
+
+    let status: Value = view(&setup, "bridge_status", json!({ "token_id": "a1" })).await?;
+    assert_eq!(status["locked"], false);
+
+    Ok(())
+}
@@ -0,0 +1,742 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::{KeyType, NearToken, SecretKey};
+use near_workspaces::result::ExecutionFinalResult;
+use near_workspaces::{AccessKey, Account};
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_burn_emits_nft_burn_with_the_vault_withdrawal() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let burn_event: Value = outcome.logs().iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|event| serde_json::from_str::<Value>(event).unwrap())
+        .find(|event| event["event"] == "nft_burn")
+        .unwrap();
+    assert_eq!(burn_event["standard"], "nep171");
+    assert_eq!(burn_event["data"][0]["owner_id"], alice.id().as_str());
+    assert_eq!(burn_event["data"][0]["token_ids"], json!(["a1"]));
+    assert!(burn_event["data"][0].get("authorized_id").is_none());
+
+    // Half the price is in the vault, and the 10% burn fee stays behind for the holders
+    let vault_amount = MINT_PRICE.as_yoctonear() / 2;
+    let memo: Value = serde_json::from_str(burn_event["data"][0]["memo"].as_str().unwrap())?;
+    assert_eq!(memo, json!({ "vault_withdrawal": (vault_amount - vault_amount / 10).to_string() }));
+
+    Ok(())
+}
+
+/// Holder balances, holder count and the burner's supply after `alice` burns `count` of her three
+/// tokens, one by one or with `burn_many`, while bob has a staked token and carol a plain one.
+async fn burn_outcome(count: usize, batched: bool) -> TestResult<Vec<String>> {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let carol = setup.worker.dev_create_account().await?;
+    for token_id in ["a1", "a2", "a3"] {
+        assert!(mint(&setup, &alice, token_id).await?.is_success());
+    }
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+    assert!(mint(&setup, &carol, "c1").await?.is_success());
+    let outcome = bob
+        .call(setup.contract.id(), "stake")
+        .args_json(json!({ "token_id": "b1" }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let token_ids = &["a1", "a2", "a3"][..count];
+    if batched {
+        let outcome = alice
+            .call(setup.contract.id(), "burn_many")
+            .args_json(json!({ "token_ids": token_ids }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await?;
+        assert!(outcome.is_success(), "{:?}", outcome);
+        let burn_events = outcome.logs().iter().filter(|log| log.contains("\"event\":\"nft_burn\"")).count();
+        assert_eq!(burn_events, count);
+    } else {
+        for token_id in token_ids {
+            burn(&setup, &alice, token_id).await?;
+        }
+    }
+
+    let mut state = Vec::new();
+    for account in [&alice, &bob, &carol] {
+        state.push(view::<u128>(&setup, "balance_of", json!({ "owner": account.id() })).await?.to_string());
+    }
+    state.push(view::<u64>(&setup, "total_holders", json!({})).await?.to_string());
+    state.push(view::<String>(&setup, "nft_supply_for_owner", json!({ "account_id": alice.id() })).await?);
+    state.push(view::<String>(&setup, "burned_supply", json!({})).await?);
+    Ok(state)
+}
+
+#[tokio::test]
+async fn test_burn_many_matches_burning_one_by_one() -> TestResult {
+    for count in 1..=3 {
+        let sequential = burn_outcome(count, false).await?;
+        let batched = burn_outcome(count, true).await?;
+        assert_eq!(batched, sequential, "burning {} tokens", count);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_burn_many_checks_every_token_first() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+    let burn_many = |token_ids: Vec<&str>| {
+        alice
+            .call(setup.contract.id(), "burn_many")
+            .args_json(json!({ "token_ids": token_ids }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+    };
+
+    let outcome = burn_many(vec!["a1", "b1"]).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("You don't own this NFT"));
+    let outcome = burn_many(vec!["a1", "a1"]).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Token a1 is listed twice"));
+    let too_many: Vec<String> = (0..10).map(|i| format!("a{}", i)).collect();
+    let outcome = burn_many(too_many.iter().map(String::as_str).collect()).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("At most 9 tokens can be burned at once"));
+    assert!(view::<Option<Value>>(&setup, "nft_token", json!({ "token_id": "a1" })).await?.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_approved_accounts_burn_for_the_owner() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let market = setup.worker.dev_create_account().await?;
+    let mallory = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    let outcome = alice
+        .call(setup.contract.id(), "nft_approve")
+        .args_json(json!({ "token_id": "a1", "account_id": market.id() }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let burn_as = |account: &Account, approval_id: u64| {
+        account
+            .call(setup.contract.id(), "burn")
+            .args_json(json!({ "token_id": "a1", "approval_id": approval_id }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+    };
+
+    let outcome = burn_as(&mallory, 0).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("You don't own this NFT"));
+    let outcome = burn_as(&market, 7).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("The approval ID doesn't match"));
+
+    let alice_before = alice.view_account().await?.balance;
+    let outcome = burn_as(&market, 0).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let burn_event: Value = outcome.logs().iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|event| serde_json::from_str::<Value>(event).unwrap())
+        .find(|event| event["event"] == "nft_burn")
+        .unwrap();
+    assert_eq!(burn_event["data"][0]["owner_id"], alice.id().as_str());
+    assert_eq!(burn_event["data"][0]["authorized_id"], market.id().as_str());
+
+    // The vault pays the owner, the approved caller only gets the freed storage back
+    assert!(alice.view_account().await?.balance > alice_before);
+    assert!(view::<bool>(&setup, "is_burned", json!({ "token_id": "a1" })).await?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_vault_withdrawal_credits_no_rewards() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+
+    // Take over the vault with a patched key and delete it, so the withdrawal has nowhere to go
+    let vault_key = SecretKey::from_seed(KeyType::ED25519, "vault");
+    let vault_account_id: near_workspaces::AccountId = vault_id(&setup, "a1").parse()?;
+    setup
+        .worker
+        .patch(&vault_account_id)
+        .access_key(vault_key.public_key(), AccessKey::full_access())
+        .transact()
+        .await?;
+    let vault = Account::from_secret_key(vault_account_id, vault_key, &setup.worker);
+    assert!(vault.delete_account(setup.treasury.id()).await?.is_success());
+
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.logs().iter().any(|log| log.contains("Withdrawal from the vault of token a1 failed")));
+    assert!(view::<bool>(&setup, "is_burned", json!({ "token_id": "a1" })).await?);
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "owner": bob.id() })).await?, 0);
+
+    // A burn whose withdrawal goes through still credits the other holders
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+    burn(&setup, &alice, "a2").await?;
+    assert!(view::<u128>(&setup, "balance_of", json!({ "owner": bob.id() })).await? > 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_burned_vaults_are_deleted_in_favor_of_the_beneficiary() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+    assert_eq!(view::<String>(&setup, "vault_beneficiary", json!({})).await?, "Owner");
+
+    // By default the last owner gets the vault's storage stake back
+    let vault_a1: near_workspaces::AccountId = vault_id(&setup, "a1").parse()?;
+    assert!(setup.worker.view_account(&vault_a1).await.is_ok());
+    burn(&setup, &alice, "a1").await?;
+    assert!(setup.worker.view_account(&vault_a1).await.is_err(), "the vault is deleted");
+
+    let outcome = alice
+        .call(setup.contract.id(), "set_vault_beneficiary")
+        .args_json(json!({ "vault_beneficiary": "Treasury" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_vault_beneficiary")
+        .args_json(json!({ "vault_beneficiary": "Treasury" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let vault_a2: near_workspaces::AccountId = vault_id(&setup, "a2").parse()?;
+    let vault_balance = setup.worker.view_account(&vault_a2).await?.balance;
+    let treasury_before = setup.treasury.view_account().await?.balance;
+    burn(&setup, &alice, "a2").await?;
+    assert!(setup.worker.view_account(&vault_a2).await.is_err(), "the vault is deleted");
+    let treasury_gain = setup.treasury.view_account().await?.balance.as_yoctonear() - treasury_before.as_yoctonear();
+    // The treasury gets the burn fee's half and the vault's stake, everything alice and the
+    // holders aren't paid, along with the yoctoNEAR attached to the withdrawal
+    let vault_amount = MINT_PRICE.as_yoctonear() / 2;
+    let burn_fee = vault_amount / 10;
+    let stake = vault_balance.as_yoctonear() - vault_amount;
+    let expected = burn_fee - burn_fee / 2 + stake + 1;
+    // On top of it only the vault's share of the gas its withdrawal burned
+    assert!(treasury_gain >= expected, "{} vs {}", treasury_gain, expected);
+    assert!(treasury_gain - expected < NearToken::from_millinear(1).as_yoctonear(), "{} vs {}", treasury_gain, expected);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_burns_require_one_yocto() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    // A function-call access key can't attach a deposit, so burning needs a full-access confirmation
+    for (method, args) in [("burn", json!({ "token_id": "a1" })), ("burn_many", json!({ "token_ids": ["a1"] }))] {
+        let outcome = alice.call(setup.contract.id(), method).args_json(args).max_gas().transact().await?;
+        let error = format!("{:?}", outcome.into_result().unwrap_err());
+        assert!(error.contains("Requires attached deposit of exactly 1 yoctoNEAR"), "{}", error);
+    }
+    assert!(view::<Option<Value>>(&setup, "nft_token", json!({ "token_id": "a1" })).await?.is_some());
+    burn(&setup, &alice, "a1").await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_burn_fee_decays_from_the_mint() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let year = 365 * 24 * 3_600 * 1_000_000_000u64;
+    let set_schedule = |account: &Account, schedule: Value| {
+        account
+            .call(setup.contract.id(), "set_burn_fee_schedule")
+            .args_json(json!({ "schedule": schedule }))
+            .transact()
+    };
+    let schedule = json!({ "initial_bps": 5_000, "final_bps": 500, "decay_duration": year.to_string() });
+    assert!(set_schedule(&alice, schedule.clone()).await?.is_failure());
+    let outcome = set_schedule(&setup.owner, json!({ "initial_bps": 500, "final_bps": 5_000, "decay_duration": "1" })).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("The burn fee can only decay"));
+    let outcome = set_schedule(&setup.owner, schedule).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    // A fresh token is charged about the initial fee, which the burn passes on to the vault
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    let fee: u32 = view(&setup, "current_burn_fee", json!({ "token_id": "a1" })).await?;
+    assert!((4_999..=5_000).contains(&fee), "{}", fee);
+    let treasury_before = setup.treasury.view_account().await?.balance;
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let memo = outcome.logs().iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|event| serde_json::from_str::<Value>(event).unwrap())
+        .find(|event| event["event"] == "nft_burn")
+        .unwrap()["data"][0]["memo"]
+        .clone();
+    let withdrawal: u128 = serde_json::from_str::<Value>(memo.as_str().unwrap())?["vault_withdrawal"].as_str().unwrap().parse()?;
+    let vault_amount = MINT_PRICE.as_yoctonear() / 2;
+    assert!(withdrawal <= vault_amount - vault_amount * 4_999 / 10_000);
+    assert!(withdrawal >= vault_amount / 2);
+    // The vault took the fee in basis points, half of it for the treasury
+    let treasury_gain = setup.treasury.view_account().await?.balance.as_yoctonear() - treasury_before.as_yoctonear();
+    assert_eq!(treasury_gain, (vault_amount - withdrawal) / 2);
+
+    // Once the decay is over every token is charged the final fee
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+    let schedule = json!({ "initial_bps": 5_000, "final_bps": 500, "decay_duration": "1" });
+    assert!(set_schedule(&setup.owner, schedule).await?.is_success());
+    assert_eq!(view::<u32>(&setup, "current_burn_fee", json!({ "token_id": "a2" })).await?, 500);
+    assert!(set_schedule(&setup.owner, Value::Null).await?.is_success());
+    assert_eq!(view::<u32>(&setup, "current_burn_fee", json!({ "token_id": "a2" })).await?, 1_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tokens_keep_the_burn_lock_they_were_minted_under() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let set_lock = |duration: u64| {
+        setup
+            .owner
+            .call(setup.contract.id(), "set_burn_lock_duration")
+            .args_json(json!({ "burn_lock_duration": duration.to_string() }))
+            .transact()
+    };
+    let day = 24 * 3_600 * 1_000_000_000u64;
+    assert!(set_lock(day).await?.is_success());
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    let unlocks_at: String = view(&setup, "burn_unlocks_at", json!({ "token_id": "a1" })).await?;
+
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    let error = format!("{:?}", outcome.into_result().unwrap_err());
+    assert!(error.contains(&format!("Token a1 can't be burned until {}", unlocks_at)), "{}", error);
+
+    // Lifting the lock only frees tokens minted afterwards
+    assert!(set_lock(0).await?.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+    assert_eq!(view::<Option<String>>(&setup, "burn_unlocks_at", json!({ "token_id": "a2" })).await?, None);
+    let outcome = alice
+        .call(setup.contract.id(), "burn_many")
+        .args_json(json!({ "token_ids": ["a2", "a1"] }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+    burn(&setup, &alice, "a2").await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tokens_sent_back_with_the_burn_msg_are_burned() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+    let send_back = |msg: &str| {
+        alice
+            .call(setup.contract.id(), "nft_transfer_call")
+            .args_json(json!({ "receiver_id": setup.contract.id(), "token_id": "a1", "msg": msg }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+    };
+
+    // Any other msg returns the token
+    let outcome = send_back("keep").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let token: Value = view(&setup, "nft_token", json!({ "token_id": "a1" })).await?;
+    assert_eq!(token["owner_id"], alice.id().as_str());
+    assert_eq!(view::<u64>(&setup, "total_holders", json!({})).await?, 2);
+
+    let alice_before = alice.view_account().await?.balance;
+    let outcome = send_back("burn").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let burn_event: Value = outcome.logs().iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|event| serde_json::from_str::<Value>(event).unwrap())
+        .find(|event| event["event"] == "nft_burn")
+        .unwrap();
+    assert_eq!(burn_event["data"][0]["owner_id"], alice.id().as_str());
+    assert!(view::<bool>(&setup, "is_burned", json!({ "token_id": "a1" })).await?);
+    assert!(alice.view_account().await?.balance > alice_before, "the vault pays the sender");
+    assert_eq!(view::<u64>(&setup, "total_holders", json!({})).await?, 1);
+    assert!(view::<u128>(&setup, "balance_of", json!({ "owner": bob.id() })).await? > 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_burn_returns_a_receipt() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let logs = outcome.logs().into_iter().map(String::from).collect::<Vec<_>>();
+    let receipt: Value = outcome.json()?;
+    let vault_amount = MINT_PRICE.as_yoctonear() / 2;
+    assert_eq!(receipt["token"]["token_id"], "a1");
+    assert_eq!(receipt["token"]["owner_id"], alice.id().as_str());
+    assert_eq!(receipt["vault_account"], vault_id(&setup, "a1"));
+    assert_eq!(receipt["estimated_payout"], (vault_amount - vault_amount / 10).to_string());
+    assert_eq!(receipt["burn_fee_applied"], (vault_amount / 10).to_string());
+    assert_eq!(receipt["holders_rewarded"], 1);
+
+    // The same receipt is logged for indexers
+    let event: Value = logs.iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|event| serde_json::from_str::<Value>(event).unwrap())
+        .find(|event| event["event"] == "burn_receipt")
+        .unwrap();
+    assert_eq!(event["data"][0]["estimated_payout"], receipt["estimated_payout"]);
+    assert_eq!(event["data"][0]["holders_rewarded"], "1");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_burn_quote_matches_the_burn() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let carol = setup.worker.dev_create_account().await?;
+    for (account, token_id) in [(&alice, "a1"), (&bob, "b1"), (&carol, "c1")] {
+        assert!(mint(&setup, account, token_id).await?.is_success());
+    }
+
+    let quote: Value = view(&setup, "burn_quote", json!({ "token_id": "a1" })).await?;
+    let vault_amount = MINT_PRICE.as_yoctonear() / 2;
+    assert_eq!(quote["vault_amount"], vault_amount.to_string());
+    assert_eq!(quote["burn_fee_bps"], 1_000);
+    assert_eq!(quote["owner_payout"], (vault_amount - vault_amount / 10).to_string());
+    assert_eq!(quote["to_holders"], (vault_amount / 10 / 2).to_string());
+    assert_eq!(quote["per_holder"], (vault_amount / 10 / 2 / 2).to_string());
+
+    // The vault reports what its mint deposited
+    let outcome = alice
+        .call(setup.contract.id(), "burn_quote_exact")
+        .args_json(json!({ "token_id": "a1" }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(outcome.json::<Value>()?, quote);
+    // And only the exact quote follows a vault that holds something else
+    reset_vault_amount(&setup, &carol, "c1", MINT_PRICE).await?;
+    let outcome = carol
+        .call(setup.contract.id(), "burn_quote_exact")
+        .args_json(json!({ "token_id": "c1" }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let exact: Value = outcome.json()?;
+    assert_eq!(exact["vault_amount"], MINT_PRICE.as_yoctonear().to_string());
+    assert_eq!(exact["owner_payout"], (MINT_PRICE.as_yoctonear() / 10 * 9).to_string());
+    let quote_c1: Value = view(&setup, "burn_quote", json!({ "token_id": "c1" })).await?;
+    assert_eq!(quote_c1["vault_amount"], vault_amount.to_string());
+
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(outcome.json::<Value>()?["estimated_payout"], quote["owner_payout"]);
+    let claimable: String = view(&setup, "claimable_of", json!({ "account_id": bob.id() })).await?;
+    assert_eq!(claimable, quote["per_holder"].as_str().unwrap());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_burned_tokens_leave_the_enumeration() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    for token_id in ["a1", "a2", "a3"] {
+        assert!(mint(&setup, &alice, token_id).await?.is_success());
+    }
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+
+    let token_ids = |tokens: Vec<Value>| -> Vec<String> {
+        tokens.iter().map(|token| token["token_id"].as_str().unwrap().to_string()).collect()
+    };
+    for (burned, token_id) in ["a2", "a1", "b1"].into_iter().enumerate() {
+        burn(&setup, if token_id == "b1" { &bob } else { &alice }, token_id).await?;
+        let supply: String = view(&setup, "nft_total_supply", json!({})).await?;
+        assert_eq!(supply, (3 - burned).to_string());
+        let tokens = token_ids(view(&setup, "nft_tokens", json!({})).await?);
+        assert_eq!(tokens.len(), 3 - burned);
+        assert!(!tokens.contains(&token_id.to_string()));
+    }
+    assert_eq!(token_ids(view(&setup, "nft_tokens", json!({})).await?), vec!["a3"]);
+    let alice_tokens = token_ids(view(&setup, "nft_tokens_for_owner", json!({ "account_id": alice.id() })).await?);
+    assert_eq!(alice_tokens, vec!["a3"]);
+
+    // A page that started before the burns ends quietly instead of going out of bounds
+    assert!(view::<Vec<Value>>(&setup, "nft_tokens", json!({ "from_index": "3" })).await?.is_empty());
+    let args = json!({ "account_id": alice.id(), "from_index": "2" });
+    assert!(view::<Vec<Value>>(&setup, "nft_tokens_for_owner", args).await?.is_empty());
+
+    Ok(())
+}
+
+/// Have `account` reset the amount the vault of `token_id` reports to `amount`, attached to the
+/// vault's own `deposit_near`, so it no longer matches what the contract recorded at mint.
+async fn reset_vault_amount(setup: &Setup, account: &Account, token_id: &str, amount: NearToken) -> TestResult {
+    let vault: near_workspaces::AccountId = vault_id(setup, token_id).parse()?;
+    let outcome = account.call(&vault, "deposit_near").args_json(json!({})).deposit(amount).transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_burn_settled_reports_the_vault_accounting() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+    // The vault reports more than the contract estimates from the mint
+    let vault_amount = NearToken::from_millinear(700).as_yoctonear();
+    reset_vault_amount(&setup, &bob, "a1", NearToken::from_yoctonear(vault_amount)).await?;
+
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let events: Vec<Value> = outcome.logs().iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|event| serde_json::from_str::<Value>(event).unwrap())
+        .filter(|event| event["event"] == "burn_settled")
+        .collect();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["standard"], "marketplace_nft");
+    let settled = &events[0]["data"][0];
+    let holder_pool = vault_amount / 10 / 2;
+    let per_holder = holder_pool / 10_000 * 10_000;
+    assert_eq!(settled["token_id"], "a1");
+    assert_eq!(settled["owner_id"], alice.id().as_str());
+    assert_eq!(settled["vault_amount"], vault_amount.to_string());
+    assert_eq!(settled["fee_retained"], (vault_amount / 10 - holder_pool).to_string());
+    assert_eq!(settled["holder_pool"], holder_pool.to_string());
+    assert_eq!(settled["per_holder"], per_holder.to_string());
+    assert_eq!(settled["remainder"], (holder_pool - per_holder).to_string());
+    assert!(outcome.logs().iter().all(|log| !log.starts_with("Amount to holders")));
+    let estimate = MINT_PRICE.as_yoctonear() / 2;
+    assert_eq!(outcome.json::<Value>()?["estimated_payout"], (estimate - estimate / 10).to_string());
+    let claimable: String = view(&setup, "claimable_of", json!({ "account_id": bob.id() })).await?;
+    assert_eq!(claimable, per_holder.to_string());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ft_burns_register_the_owner_before_paying_out() -> TestResult {
+    let (setup, ft, ft_owner) = init_ft_collection(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let carol = setup.worker.dev_create_account().await?;
+    assert!(ft_mint(&setup, &ft, &ft_owner, &alice, "a1").await?.is_success());
+    assert!(ft_mint(&setup, &ft, &ft_owner, &bob, "b1").await?.is_success());
+    // Carol never registered with the FT
+    let outcome = alice
+        .call(setup.contract.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": carol.id(), "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let storage: Value = ft.view("storage_balance_of").args_json(json!({ "account_id": carol.id() })).await?.json()?;
+    assert_eq!(storage, Value::Null);
+
+    burn(&setup, &carol, "a1").await?;
+    let vault_amount = MINT_PRICE.as_yoctonear() / 2;
+    assert_eq!(ft_balance_of(&ft, carol.id().as_str()).await?, vault_amount - vault_amount / 10);
+    // Bob alone shares the half of the burn fee the vault sent back
+    let holder_pool = vault_amount / 10 / 2;
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "owner": bob.id() })).await?, holder_pool / 10_000 * 10_000);
+    let record: Value = view(&setup, "burn_record", json!({ "token_id": "a1" })).await?;
+    assert_eq!(record["payout"], (vault_amount - vault_amount / 10).to_string());
+
+    Ok(())
+}
+
+/// NEAR `account` gained from `outcome`, its gas fees added back.
+async fn gained_by(account: &Account, balance_before: u128, outcome: &ExecutionFinalResult) -> TestResult<u128> {
+    let gas_fees: u128 = outcome.outcomes().iter().map(|outcome| outcome.tokens_burnt.as_yoctonear()).sum();
+    Ok(account.view_account().await?.balance.as_yoctonear() + gas_fees - balance_before)
+}
+
+#[tokio::test]
+async fn test_burns_refund_the_freed_storage_to_the_owner() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    // Without a vault the refund is all the owner gets back
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_vaults_enabled")
+        .args_json(json!({ "vaults_enabled": false }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    for token_id in ["a1", "a2", "a3"] {
+        assert!(mint(&setup, &alice, token_id).await?.is_success());
+    }
+
+    // Without a storage deposit the record comes out of the storage the token freed
+    let storage_before = setup.contract.view_account().await?.storage_usage;
+    let balance_before = alice.view_account().await?.balance.as_yoctonear();
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let refund = (storage_before - setup.contract.view_account().await?.storage_usage) as u128 * 10u128.pow(19);
+    // The token's metadata alone takes more than 100 bytes
+    assert!(refund > 100 * 10u128.pow(19), "{}", refund);
+    assert_eq!(gained_by(&alice, balance_before, &outcome).await?, refund);
+
+    // With one, each token's whole removal is refunded and the deposit pays the records
+    let outcome = alice
+        .call(setup.contract.id(), "storage_deposit")
+        .args_json(json!({}))
+        .deposit(NearToken::from_near(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let deposit_before: u128 = view(&setup, "storage_balance_of", json!({ "account_id": alice.id() })).await?;
+    let storage_before = setup.contract.view_account().await?.storage_usage;
+    let balance_before = alice.view_account().await?.balance.as_yoctonear();
+    let outcome = alice
+        .call(setup.contract.id(), "burn_many")
+        .args_json(json!({ "token_ids": ["a2", "a3"] }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let records = deposit_before - view::<u128>(&setup, "storage_balance_of", json!({ "account_id": alice.id() })).await?;
+    assert!(records > 0);
+    let freed = (storage_before - setup.contract.view_account().await?.storage_usage) as u128 * 10u128.pow(19);
+    assert_eq!(gained_by(&alice, balance_before, &outcome).await?, freed + records);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_approved_burners_get_the_freed_storage_back() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let market = setup.worker.dev_create_account().await?;
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_vaults_enabled")
+        .args_json(json!({ "vaults_enabled": false }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    let outcome = alice
+        .call(setup.contract.id(), "nft_approve")
+        .args_json(json!({ "token_id": "a1", "account_id": market.id() }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = market
+        .call(setup.contract.id(), "storage_deposit")
+        .args_json(json!({}))
+        .deposit(NearToken::from_near(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    // The market's deposit pays the record, so the token's freed storage goes back to it
+    let deposit_before: u128 = view(&setup, "storage_balance_of", json!({ "account_id": market.id() })).await?;
+    let storage_before = setup.contract.view_account().await?.storage_usage;
+    let alice_before = alice.view_account().await?.balance;
+    let market_before = market.view_account().await?.balance.as_yoctonear();
+    let outcome = market
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let record = deposit_before - view::<u128>(&setup, "storage_balance_of", json!({ "account_id": market.id() })).await?;
+    assert!(record > 0);
+    let freed = (storage_before - setup.contract.view_account().await?.storage_usage) as u128 * 10u128.pow(19);
+    assert_eq!(gained_by(&market, market_before, &outcome).await?, freed + record);
+    assert_eq!(alice.view_account().await?.balance, alice_before);
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::*;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_burned_tokens_keep_a_record() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+    assert_eq!(view::<Value>(&setup, "burn_record", json!({ "token_id": "a1" })).await?, Value::Null);
+
+    burn(&setup, &alice, "a1").await?;
+    burn(&setup, &alice, "a2").await?;
+    let record: Value = view(&setup, "burn_record", json!({ "token_id": "a1" })).await?;
+    let vault_amount = MINT_PRICE.as_yoctonear() / 2;
+    assert_eq!(record["owner_id"], alice.id().as_str());
+    assert_eq!(record["payout"], (vault_amount - vault_amount / 10).to_string());
+    assert!(record["timestamp"].as_str().unwrap().parse::<u64>()? > 0);
+    assert_eq!(view::<Value>(&setup, "nft_token", json!({ "token_id": "a1" })).await?, Value::Null);
+
+    let records: Vec<Value> = view(&setup, "burned_tokens", json!({})).await?;
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0], record);
+    let records: Vec<Value> = view(&setup, "burned_tokens", json!({ "from_index": "1", "limit": 1 })).await?;
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["token_id"], "a2");
+
+    Ok(())
+}
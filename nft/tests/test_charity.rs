@@ -0,0 +1,45 @@
+mod common;
+
+use common::*;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_charity_receives_its_share_of_every_mint() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let charity = setup.worker.dev_create_account().await?;
+
+    let set_charity = |bps: u16| {
+        setup
+            .owner
+            .call(setup.contract.id(), "set_charity")
+            .args_json(json!({ "charity": [charity.id(), bps] }))
+            .transact()
+    };
+    assert!(set_charity(10_001).await?.is_failure(), "the share is capped at the owner's amount");
+    let outcome = set_charity(1_000).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let owner_before = setup.owner.view_account().await?.balance;
+    let charity_before = charity.view_account().await?.balance;
+    let outcome = mint(&setup, &alice, "a1").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    // Half the price goes to the vault, and the charity gets 10% of the other half
+    let owner_amount = MINT_PRICE.as_yoctonear() / 2;
+    let donation = owner_amount / 10;
+    assert!(outcome.logs().iter().any(|log| log.contains(&format!("\\\"charity_amount\\\":\\\"{}\\\"", donation))));
+    let charity_after = charity.view_account().await?.balance;
+    assert_eq!(charity_after.as_yoctonear() - charity_before.as_yoctonear(), donation);
+    let owner_after = setup.owner.view_account().await?.balance;
+    assert_eq!(owner_after.as_yoctonear() - owner_before.as_yoctonear(), owner_amount - donation);
+    let donated: String = view(&setup, "charity_total_donated", json!({})).await?;
+    assert_eq!(donated.parse::<u128>()?, donation);
+
+    let outcome = setup.owner.call(setup.contract.id(), "freeze_charity").transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(set_charity(0).await?.is_failure(), "a frozen charity split can't change");
+    assert!(view::<bool>(&setup, "charity_frozen", json!({})).await?);
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use near_workspaces::Account;
+use serde_json::{json, Value};
+
+const STORAGE_DEPOSIT: NearToken = NearToken::from_millinear(10);
+const EXTRA_FT: u128 = 1_000;
+
+async fn claim_all(setup: &Setup, account: &Account) -> TestResult<near_workspaces::result::ExecutionFinalResult> {
+    Ok(account
+        .call(setup.contract.id(), "claim_all")
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?)
+}
+
+async fn deposit_storage(setup: &Setup, account: &Account) -> TestResult {
+    let outcome = account
+        .call(setup.contract.id(), "storage_deposit")
+        .deposit(STORAGE_DEPOSIT)
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_all_empties_every_ledger() -> TestResult {
+    let (setup, ft, ft_owner) = init_ft_collection(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(ft_mint(&setup, &ft, &ft_owner, &alice, "a1").await?.is_success());
+    assert!(ft_mint(&setup, &ft, &ft_owner, &bob, "b1").await?.is_success());
+    assert!(claim_all(&setup, &alice).await?.is_failure(), "nothing to claim yet");
+
+    // All three ledgers populated: rewards from bob's burn, spare FTs and a storage deposit
+    burn(&setup, &bob, "b1").await?;
+    let rewards: u128 = view(&setup, "balance_of", json!({ "owner": alice.id() })).await?;
+    assert!(rewards > 0);
+    let outcome = ft_owner
+        .call(ft.id(), "ft_transfer")
+        .args_json(json!({ "receiver_id": alice.id(), "amount": EXTRA_FT.to_string() }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(ft_transfer_call(&ft, &alice, setup.contract.id().as_str(), EXTRA_FT, "").await?.is_success());
+    deposit_storage(&setup, &alice).await?;
+
+    let ft_before = ft_balance_of(&ft, alice.id().as_str()).await?;
+    let outcome = claim_all(&setup, &alice).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let summary: Value = outcome.json()?;
+    assert_eq!(summary["rewards"], rewards.to_string());
+    assert_eq!(summary["ft_deposit"], EXTRA_FT.to_string());
+    assert_eq!(summary["storage_deposit"], STORAGE_DEPOSIT.as_yoctonear().to_string());
+    assert_eq!(summary["near_amount"], STORAGE_DEPOSIT.as_yoctonear().to_string());
+    assert_eq!(summary["ft_amount"], (rewards + EXTRA_FT).to_string());
+    assert_eq!(ft_balance_of(&ft, alice.id().as_str()).await?, ft_before + rewards + EXTRA_FT);
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "owner": alice.id() })).await?, 0);
+    assert_eq!(view::<u128>(&setup, "ft_deposits_of", json!({ "account_id": alice.id() })).await?, 0);
+    assert_eq!(view::<String>(&setup, "storage_balance_of", json!({ "account_id": alice.id() })).await?, "0");
+    assert!(claim_all(&setup, &alice).await?.is_failure(), "everything was claimed");
+
+    // Only the storage deposit: a single NEAR transfer and no FT transfer
+    deposit_storage(&setup, &alice).await?;
+    let outcome = claim_all(&setup, &alice).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(!touched(&outcome, ft.id().as_str()));
+    let summary: Value = outcome.json()?;
+    assert_eq!(summary["near_amount"], STORAGE_DEPOSIT.as_yoctonear().to_string());
+    assert_eq!(summary["ft_amount"], "0");
+
+    // Only FT deposits: no NEAR leg
+    assert!(ft_transfer_call(&ft, &alice, setup.contract.id().as_str(), EXTRA_FT, "").await?.is_success());
+    let summary: Value = claim_all(&setup, &alice).await?.json()?;
+    assert_eq!(summary["ft_amount"], EXTRA_FT.to_string());
+    assert_eq!(summary["near_amount"], "0");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_all_sends_near_rewards_with_the_storage_deposit() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+
+    // Only rewards
+    burn(&setup, &bob, "b1").await?;
+    let rewards: u128 = view(&setup, "balance_of", json!({ "owner": alice.id() })).await?;
+    let summary: Value = claim_all(&setup, &alice).await?.json()?;
+    assert_eq!(summary["near_amount"], rewards.to_string());
+    assert_eq!(summary["ft_contract"], Value::Null);
+
+    Ok(())
+}
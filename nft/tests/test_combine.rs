@@ -0,0 +1,118 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+const COMBINE_FEE: NearToken = NearToken::from_millinear(500);
+
+#[tokio::test]
+async fn test_combine_burns_inputs_and_mints_a_new_token() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    for token_id in ["a1", "a2"] {
+        assert!(mint(&setup, &alice, token_id).await?.is_success());
+    }
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_combine_fee")
+        .args_json(json!({ "combine_fee": COMBINE_FEE }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+
+    let deposit = NearToken::from_yoctonear(COMBINE_FEE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear());
+    let combine = |token_id_b: &'static str| {
+        alice
+            .call(setup.contract.id(), "combine")
+            .args_json(json!({
+                "token_id_a": "a1",
+                "token_id_b": token_id_b,
+                "new_metadata": token_metadata("fused"),
+            }))
+            .deposit(deposit)
+            .max_gas()
+            .transact()
+    };
+
+    assert!(combine("b1").await?.is_failure(), "both inputs must be owned by the caller");
+    assert!(combine("a1").await?.is_failure(), "inputs must differ");
+
+    let outcome = alice
+        .call(setup.contract.id(), "nft_approve")
+        .args_json(json!({ "token_id": "a1", "account_id": bob.id() }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(combine("a2").await?.is_failure(), "approved tokens can't be combined");
+
+    let outcome = alice
+        .call(setup.contract.id(), "nft_revoke_all")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let treasury_before = setup.treasury.view_account().await?.balance;
+    let alice_before = alice.view_account().await?.balance.as_yoctonear();
+    let outcome = combine("a2").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    // The burned vaults are paid out to alice less their burn fees, not moved into the new vault
+    let gas_fees: u128 = outcome.outcomes().iter().map(|outcome| outcome.tokens_burnt.as_yoctonear()).sum();
+    let returned = alice.view_account().await?.balance.as_yoctonear() + gas_fees + deposit.as_yoctonear() - alice_before;
+    let vault_amount = MINT_PRICE.as_yoctonear() / 2;
+    assert!(returned >= 2 * (vault_amount - vault_amount / 10), "{}", returned);
+    assert!(touched(&outcome, &vault_id(&setup, "a1")));
+    assert!(touched(&outcome, &vault_id(&setup, "a2")));
+    assert!(outcome.logs().iter().any(|log| log.contains("\"event\":\"combine\"")));
+    let treasury_after = setup.treasury.view_account().await?.balance;
+    assert!(treasury_after.as_yoctonear() >= treasury_before.as_yoctonear() + COMBINE_FEE.as_yoctonear());
+
+    let tokens: Vec<Value> = view(&setup, "nft_tokens_for_owner", json!({ "account_id": alice.id() })).await?;
+    let token_ids: Vec<&str> = tokens.iter().map(|token| token["token_id"].as_str().unwrap()).collect();
+    assert_eq!(token_ids, vec!["combined-4"]);
+    assert_eq!(tokens[0]["metadata"]["title"], "fused");
+    let supply: String = view(&setup, "nft_total_supply", json!({})).await?;
+    assert_eq!(supply, "2");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_combine_is_held_to_the_mint_checks() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    for token_id in ["a1", "a2"] {
+        assert!(mint(&setup, &alice, token_id).await?.is_success());
+    }
+
+    let owner_call = |method: &'static str, args: Value| {
+        setup.owner.call(setup.contract.id(), method).args_json(args).transact()
+    };
+    let combine = || {
+        alice
+            .call(setup.contract.id(), "combine")
+            .args_json(json!({ "token_id_a": "a1", "token_id_b": "a2", "new_metadata": token_metadata("fused") }))
+            .deposit(VAULT_DEPOSIT)
+            .max_gas()
+            .transact()
+    };
+
+    assert!(owner_call("set_sale_phase", json!({ "sale_phase": "Closed" })).await?.is_success());
+    assert!(combine().await?.is_failure(), "combining mints, so it needs an open sale");
+    assert!(owner_call("set_sale_phase", json!({ "sale_phase": "Public" })).await?.is_success());
+
+    assert!(owner_call("set_public_minting", json!({ "public_minting": false })).await?.is_success());
+    assert!(combine().await?.is_failure(), "only approved minters can combine");
+    assert!(owner_call("set_public_minting", json!({ "public_minting": true })).await?.is_success());
+
+    let outcome = combine().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_breakdown_total_is_exactly_what_the_mint_requires() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let metadata = json!({ "title": "a1", "description": "d".repeat(500) });
+
+    let breakdown: Value = view(&setup, "mint_cost_breakdown", json!({ "token_metadata": metadata })).await?;
+    let item = |name: &str| breakdown[name].as_str().unwrap().parse::<u128>().unwrap();
+    let near_total = item("near_total");
+    assert_eq!(
+        near_total,
+        item("mint_price") + item("vault_code_storage") + item("vault_state_buffer") + item("token_storage")
+    );
+    assert_eq!(item("mint_price"), MINT_PRICE.as_yoctonear());
+    assert_eq!(item("ft_total"), 0);
+    let without_metadata: Value = view(&setup, "mint_cost_breakdown", json!({})).await?;
+    assert!(item("token_storage") > without_metadata["token_storage"].as_str().unwrap().parse::<u128>()?);
+
+    // The mint gates its deposit on the same estimate, so one yocto less fails and the total succeeds
+    let mint_with = |deposit: u128| {
+        alice
+            .call(setup.contract.id(), "nft_mint")
+            .args_json(json!({ "token_id": "a1", "token_owner_id": alice.id(), "token_metadata": metadata }))
+            .deposit(NearToken::from_yoctonear(deposit))
+            .max_gas()
+            .transact()
+    };
+    let outcome = mint_with(near_total - 1).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Insufficient price to mint"));
+    let outcome = mint_with(near_total).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mint_cost_matches_the_deposit_nft_mint_requires() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let metadata = json!({ "title": "a1" });
+
+    let cost: Value = view(&setup, "mint_cost", json!({ "token_metadata": metadata })).await?;
+    let item = |name: &str| cost[name].as_str().unwrap().parse::<u128>().unwrap();
+    let required = item("total_attached_near_required");
+    assert_eq!(item("mint_price"), MINT_PRICE.as_yoctonear());
+    assert_eq!(cost["mint_currency"], Value::Null);
+    assert!(required > item("mint_price") + item("vault_deposit"), "token storage is included");
+
+    let mint_with = |token_id: &str, deposit: u128| {
+        alice
+            .call(setup.contract.id(), "nft_mint")
+            .args_json(json!({ "token_id": token_id, "token_owner_id": alice.id(), "token_metadata": { "title": token_id } }))
+            .deposit(NearToken::from_yoctonear(deposit))
+            .max_gas()
+            .transact()
+    };
+    assert!(mint_with("a1", required - 1).await?.is_failure());
+    let outcome = mint_with("a1", required).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    // Without vaults only the price and storage are left
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_vaults_enabled")
+        .args_json(json!({ "vaults_enabled": false }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let cost: Value = view(&setup, "mint_cost", json!({ "token_metadata": { "title": "a2" } })).await?;
+    assert_eq!(cost["vault_deposit"], "0");
+    let required = cost["total_attached_near_required"].as_str().unwrap().parse::<u128>()?;
+    assert!(mint_with("a2", required - 1).await?.is_failure());
+    assert!(mint_with("a2", required).await?.is_success());
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_free_mints_skip_the_vault() -> TestResult {
+    let setup = init_with(0, json!({ "mint_price": "0" })).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    let alice_before = alice.view_account().await?.balance;
+    let outcome = mint_with_deposit(&setup, &alice, "a1", NearToken::from_millinear(100)).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(!touched(&outcome, &vault_id(&setup, "a1")));
+    let alice_spent = alice_before.as_yoctonear() - alice.view_account().await?.balance.as_yoctonear();
+    assert!(alice_spent < NearToken::from_millinear(100).as_yoctonear(), "only storage, the rest is refunded");
+
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(!touched(&outcome, &vault_id(&setup, "a1")));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_disabling_vaults_pays_the_owner_instead() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    assert!(alice
+        .call(setup.contract.id(), "set_vaults_enabled")
+        .args_json(json!({ "vaults_enabled": false }))
+        .transact()
+        .await?
+        .is_failure());
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_vaults_enabled")
+        .args_json(json!({ "vaults_enabled": false }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(!view::<bool>(&setup, "vaults_enabled", json!({})).await?);
+
+    let outcome = mint_with_deposit(
+        &setup,
+        &alice,
+        "a2",
+        NearToken::from_yoctonear(MINT_PRICE.as_yoctonear() + NearToken::from_millinear(100).as_yoctonear()),
+    )
+    .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(!touched(&outcome, &vault_id(&setup, "a2")));
+
+    // Tokens minted with a vault keep it
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(touched(&outcome, &vault_id(&setup, "a1")));
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+fn holder_events(logs: &[&str]) -> Vec<Value> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|event| serde_json::from_str::<Value>(event).unwrap())
+        .filter(|event| event["event"] == "holder_changed")
+        .map(|event| event["data"][0].clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_transfer_emptying_the_sender_emits_remove_and_add() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+
+    let outcome = mint(&setup, &alice, "a1").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let events = holder_events(&outcome.logs());
+    assert_eq!(events, vec![json!({
+        "account_id": alice.id(),
+        "direction": "added",
+        "token_count": "1",
+        "total_holders": "1",
+    })]);
+
+    let outcome = alice
+        .call(setup.contract.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": bob.id(), "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let events = holder_events(&outcome.logs());
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["account_id"], alice.id().as_str());
+    assert_eq!(events[0]["direction"], "removed");
+    assert_eq!(events[0]["token_count"], "0");
+    assert_eq!(events[1]["account_id"], bob.id().as_str());
+    assert_eq!(events[1]["direction"], "added");
+    assert_eq!(events[1]["total_holders"], "1");
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_invariants_hold_across_mint_transfer_burn_withdraw() -> TestResult {
+    let setup = init(10).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+    assert_eq!(view::<Vec<String>>(&setup, "check_invariants", json!({})).await?, Vec::<String>::new());
+
+    let outcome = alice
+        .call(setup.contract.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": bob.id(), "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    assert_eq!(view::<Vec<String>>(&setup, "check_invariants", json!({})).await?, Vec::<String>::new());
+
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a2" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    assert_eq!(view::<Vec<String>>(&setup, "check_invariants", json!({})).await?, Vec::<String>::new());
+
+    let outcome = bob.call(setup.contract.id(), "withdraw").args_json(json!({})).max_gas().transact().await?;
+    assert!(outcome.is_success());
+    // Paging with a small window reports the same, empty, result
+    for from_index in 0..2 {
+        let violations: Vec<String> =
+            view(&setup, "check_invariants", json!({ "from_index": from_index.to_string(), "limit": 1 })).await?;
+        assert!(violations.is_empty(), "{:?}", violations);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_supply_views_agree_through_batch_mints_and_burns() -> TestResult {
+    let setup = init(10).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let supply = || async {
+        let minted: String = view(&setup, "minted", json!({})).await?;
+        let circulating: String = view(&setup, "circulating_supply", json!({})).await?;
+        let mintable: String = view(&setup, "mintable_remaining", json!({})).await?;
+        let burned: String = view(&setup, "burned_supply", json!({})).await?;
+        let live: String = view(&setup, "nft_total_supply", json!({})).await?;
+        assert_eq!(view::<Vec<String>>(&setup, "check_invariants", json!({})).await?, Vec::<String>::new());
+        // What's circulating is what's live, and everything minted is either live or burned
+        assert_eq!(circulating, live);
+        assert_eq!(minted.parse::<u128>()?, live.parse::<u128>()? + burned.parse::<u128>()?);
+        TestResult::Ok((minted, circulating, mintable))
+    };
+    assert_eq!(supply().await?, ("0".to_string(), "0".to_string(), "10".to_string()));
+
+    let tokens: Vec<_> = ["a1", "a2", "a3"].iter()
+        .map(|token_id| json!([token_id, alice.id(), token_metadata(token_id)]))
+        .collect();
+    let outcome = alice
+        .call(setup.contract.id(), "nft_mint_many")
+        .args_json(json!({ "tokens": tokens }))
+        .deposit(NearToken::from_yoctonear((MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear()) * 3))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(supply().await?, ("3".to_string(), "3".to_string(), "7".to_string()));
+
+    let outcome = alice
+        .call(setup.contract.id(), "burn_many")
+        .args_json(json!({ "token_ids": ["a1", "a3"] }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(supply().await?, ("3".to_string(), "1".to_string(), "7".to_string()), "burning frees no supply");
+
+    let unlimited = init(0).await?;
+    assert_eq!(view::<String>(&unlimited, "mintable_remaining", json!({})).await?, "unlimited");
+
+    Ok(())
+}
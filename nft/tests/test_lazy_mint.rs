@@ -0,0 +1,100 @@
+mod common;
+
+use common::*;
+use near_contract_standards::non_fungible_token::metadata::TokenMetadata;
+use near_crypto::{KeyType, SecretKey, Signature};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
+use near_workspaces::types::NearToken;
+use near_workspaces::Account;
+use nft::MintVoucher;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+fn metadata(title: &str) -> TokenMetadata {
+    TokenMetadata { title: Some(title.to_string()), ..Default::default() }
+}
+
+fn voucher(token_id: &str, price: NearToken, expiry: u64, nonce: u64) -> MintVoucher {
+    MintVoucher {
+        token_id: token_id.to_string(),
+        metadata_hash: Sha256::digest(borsh::to_vec(&metadata(token_id)).unwrap()).into(),
+        price: U128(price.as_yoctonear()),
+        expiry: U64(expiry),
+        nonce: U64(nonce),
+    }
+}
+
+fn sign(signer: &SecretKey, voucher: &MintVoucher) -> Base64VecU8 {
+    match signer.sign(&Sha256::digest(borsh::to_vec(voucher).unwrap())) {
+        Signature::ED25519(signature) => Base64VecU8::from(signature.to_bytes().to_vec()),
+        _ => unreachable!(),
+    }
+}
+
+async fn redeem(
+    setup: &Setup,
+    buyer: &Account,
+    voucher: &MintVoucher,
+    signature: &Base64VecU8,
+) -> TestResult<near_workspaces::result::ExecutionFinalResult> {
+    Ok(buyer
+        .call(setup.contract.id(), "mint_with_voucher")
+        .args_json(json!({
+            "voucher": voucher,
+            "token_metadata": metadata(&voucher.token_id),
+            "signature": signature,
+        }))
+        .deposit(NearToken::from_yoctonear(voucher.price.0 + VAULT_DEPOSIT.as_yoctonear() + NearToken::from_millinear(100).as_yoctonear()))
+        .max_gas()
+        .transact()
+        .await?)
+}
+
+#[tokio::test]
+async fn test_vouchers_mint_once_before_they_expire() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let signer = SecretKey::from_random(KeyType::ED25519);
+    let now = setup.worker.view_block().await?.timestamp();
+    let hour = 3_600 * 1_000_000_000;
+    let price = NearToken::from_millinear(500);
+
+    let first = voucher("v1", price, now + hour, 1);
+    let outcome = redeem(&setup, &alice, &first, &sign(&signer, &first)).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("No voucher signer is set"));
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_voucher_signer")
+        .args_json(json!({ "signer_pk": signer.public_key().to_string() }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let forged = SecretKey::from_random(KeyType::ED25519);
+    let outcome = redeem(&setup, &alice, &first, &sign(&forged, &first)).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Invalid voucher signature"));
+    let mut discounted = first.clone();
+    discounted.price = U128(0);
+    let outcome = redeem(&setup, &alice, &discounted, &sign(&signer, &first)).await?;
+    assert!(outcome.is_failure(), "the signature covers the price");
+
+    let outcome = redeem(&setup, &alice, &first, &sign(&signer, &first)).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let token: Value = view(&setup, "nft_token", json!({ "token_id": "v1" })).await?;
+    assert_eq!(token["owner_id"], alice.id().to_string());
+    assert!(view::<bool>(&setup, "is_voucher_used", json!({ "nonce": "1" })).await?);
+
+    // Replaying the voucher, or reusing its nonce for another token, fails
+    let outcome = redeem(&setup, &alice, &first, &sign(&signer, &first)).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Voucher was already used"));
+    let reused = voucher("v2", price, now + hour, 1);
+    let outcome = redeem(&setup, &alice, &reused, &sign(&signer, &reused)).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Voucher was already used"));
+
+    let expired = voucher("v3", price, now, 3);
+    let outcome = redeem(&setup, &alice, &expired, &sign(&signer, &expired)).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Voucher has expired"));
+    assert!(!view::<bool>(&setup, "is_voucher_used", json!({ "nonce": "3" })).await?);
+
+    Ok(())
+}
@@ -0,0 +1,251 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use near_workspaces::network::Sandbox;
+use near_workspaces::types::AccountId;
+use near_workspaces::{Account, Worker};
+use serde_json::{json, Value};
+
+// Page size of the contract's paginated calls, which the backfill bounty is paid per
+const MAX_PAGE_LIMIT: u128 = 200;
+
+// The contract as first deployed, built from the baseline commit
+const ORIGINAL_WASM: &[u8] = include_bytes!("res/nft_original.wasm");
+
+/// Deploy the original contract on `worker` and call its `new`, which takes no optional settings
+/// besides the FT mints are paid in.
+async fn init_original(worker: Worker<Sandbox>, mint_currency: Option<&AccountId>) -> TestResult<Setup> {
+    let contract = worker.dev_deploy(ORIGINAL_WASM).await?;
+    let owner = worker.dev_create_account().await?;
+    let treasury = worker.dev_create_account().await?;
+    let outcome = contract
+        .call("new")
+        .args_json(json!({
+            "owner_id": owner.id(),
+            "metadata": { "spec": "nft-1.0.0", "name": "Test Collection", "symbol": "TEST" },
+            "mint_price": MINT_PRICE.as_yoctonear().to_string(),
+            "payment_split_percent": "50",
+            "total_supply": "10",
+            "burn_fee": "10",
+            "treasury": treasury.id(),
+            "royalty": "500",
+            "mint_currency": mint_currency,
+        }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    Ok(Setup { worker, contract, owner, treasury })
+}
+
+async fn original_mint(setup: &Setup, minter: &Account, token_id: &str) -> TestResult {
+    let outcome = minter
+        .call(setup.contract.id(), "nft_mint")
+        .args_json(json!({ "token_id": token_id, "token_owner_id": minter.id(), "token_metadata": token_metadata(token_id) }))
+        .deposit(NearToken::from_yoctonear(MINT_PRICE.as_yoctonear() + NearToken::from_near(2).as_yoctonear()))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    Ok(())
+}
+
+/// Deploy the current contract over the original one and migrate its state, with `reward_pool`
+/// the sum of the balances the original kept.
+async fn upgrade(setup: &Setup, reward_pool: u128) -> TestResult {
+    let new_wasm = near_workspaces::compile_project("./").await?;
+    let outcome = setup.contract.as_account().deploy(&new_wasm).await?;
+    assert!(outcome.is_success());
+    let outcome = setup
+        .contract
+        .call("migrate")
+        .args_json(json!({ "reward_pool": reward_pool.to_string() }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_migrate_upgrades_an_original_deployment() -> TestResult {
+    let setup = init_original(near_workspaces::sandbox().await?, None).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    original_mint(&setup, &alice, "a1").await?;
+    original_mint(&setup, &alice, "a2").await?;
+    original_mint(&setup, &bob, "b1").await?;
+    // Alice keeps a holder, so the whole burn fee went to bob's balance
+    burn(&setup, &alice, "a2").await?;
+    let legacy_reward: u128 = view(&setup, "balance_of", json!({ "account_id": bob.id() })).await?;
+    assert_eq!(legacy_reward, MINT_PRICE.as_yoctonear() * 50 * 10 / 20_000);
+
+    upgrade(&setup, legacy_reward).await?;
+
+    // What the original stored carries over
+    let config: Value = view(&setup, "get_config", json!({})).await?;
+    assert_eq!(config["owner_id"], setup.owner.id().to_string());
+    assert_eq!(config["treasury"], setup.treasury.id().to_string());
+    assert_eq!(config["mint_price"], MINT_PRICE.as_yoctonear().to_string());
+    assert_eq!(config["payment_split_percent"], "50");
+    assert_eq!(config["total_supply"], "10");
+    assert_eq!(config["burn_fee"], "10");
+    assert_eq!(config["base_currency"], Value::Null);
+    let token: Value = view(&setup, "nft_token", json!({ "token_id": "a1" })).await?;
+    assert_eq!(token["owner_id"], alice.id().to_string());
+    assert_eq!(view::<String>(&setup, "nft_total_supply", json!({})).await?, "2");
+    assert_eq!(view::<String>(&setup, "minted", json!({})).await?, "3");
+    assert_eq!(view::<String>(&setup, "burned_supply", json!({})).await?, "1");
+    assert_eq!(view::<u64>(&setup, "total_holders", json!({})).await?, 2);
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "account_id": bob.id() })).await?, legacy_reward);
+    let outcome = setup.contract.call("migrate").args_json(json!({ "reward_pool": "0" })).max_gas().transact().await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("already migrated"));
+
+    // Burns wait for every holder to accrue
+    assert_eq!(view::<u64>(&setup, "reward_weights_to_migrate", json!({})).await?, 2);
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("migrate_reward_weights"));
+
+    // Anyone can run the backfill, for a bounty of 2 millinear per holder while the treasury lasts
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_processor_bounty")
+        .args_json(json!({ "processor_bounty": NearToken::from_millinear(2 * MAX_PAGE_LIMIT).as_yoctonear().to_string() }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "fund_treasury")
+        .deposit(NearToken::from_millinear(3))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let backfill = |account: &Account| {
+        account
+            .call(setup.contract.id(), "migrate_reward_weights")
+            .args_json(json!({ "limit": 1 }))
+            .max_gas()
+            .transact()
+    };
+    let progress = |outcome: &near_workspaces::result::ExecutionFinalResult| -> Value {
+        outcome.logs().iter()
+            .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+            .map(|event| serde_json::from_str::<Value>(event).unwrap())
+            .find(|event| event["event"] == "reward_weights_migrated")
+            .unwrap()["data"][0]
+            .clone()
+    };
+    let outcome = backfill(&alice).await?;
+    let event = progress(&outcome);
+    assert_eq!(outcome.json::<u64>()?, 1);
+    assert_eq!(event["processor_id"], alice.id().as_str());
+    assert_eq!((event["from_index"].as_str(), event["to_index"].as_str()), (Some("1"), Some("2")));
+    assert_eq!(event["bounty"], NearToken::from_millinear(2).as_yoctonear().to_string());
+    let outcome = backfill(&bob).await?;
+    assert_eq!(progress(&outcome)["bounty"], NearToken::from_millinear(1).as_yoctonear().to_string());
+    assert_eq!(outcome.json::<u64>()?, 0);
+    assert_eq!(view::<String>(&setup, "treasury_balance", json!({})).await?, "0");
+    assert_eq!(backfill(&bob).await?.json::<u64>()?, 0, "nothing is left to process or be paid for");
+    assert_eq!(view::<u64>(&setup, "reward_weights_to_migrate", json!({})).await?, 0);
+    assert_eq!(view::<Vec<String>>(&setup, "check_invariants", json!({})).await?, Vec::<String>::new());
+
+    // Fees accrue on top of the rewards credited before the upgrade
+    burn(&setup, &alice, "a1").await?;
+    assert!(view::<u128>(&setup, "balance_of", json!({ "account_id": bob.id() })).await? > legacy_reward);
+    let outcome = bob.call(setup.contract.id(), "withdraw").args_json(json!({})).max_gas().transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "account_id": bob.id() })).await?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_balances_of_former_holders_join_the_reward_pool() -> TestResult {
+    let setup = init_original(near_workspaces::sandbox().await?, None).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let carol = setup.worker.dev_create_account().await?;
+    original_mint(&setup, &alice, "a1").await?;
+    original_mint(&setup, &alice, "a2").await?;
+    original_mint(&setup, &bob, "b1").await?;
+    original_mint(&setup, &carol, "c1").await?;
+    burn(&setup, &alice, "a2").await?;
+    // Bob leaves the holders with his reward still in his balance
+    let outcome = bob
+        .call(setup.contract.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": carol.id(), "token_id": "b1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let balance_of = |account: &Account| view::<u128>(&setup, "balance_of", json!({ "account_id": account.id() }));
+    let bob_reward = balance_of(&bob).await?;
+    let carol_reward = balance_of(&carol).await?;
+    assert!(bob_reward > 0 && carol_reward > 0);
+
+    upgrade(&setup, bob_reward + carol_reward).await?;
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "migrate_reward_weights")
+        .args_json(json!({}))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    // Bob's withdrawal comes out of his own share of the pool, not carol's
+    let outcome = bob.call(setup.contract.id(), "withdraw").args_json(json!({})).max_gas().transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(balance_of(&bob).await?, 0);
+    assert_eq!(balance_of(&carol).await?, carol_reward);
+    assert_eq!(view::<Vec<String>>(&setup, "check_invariants", json!({})).await?, Vec::<String>::new());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ft_deposits_made_before_the_upgrade_stay_usable() -> TestResult {
+    let worker = near_workspaces::sandbox().await?;
+    let ft_wasm = near_workspaces::compile_project("../ft").await?;
+    let ft_owner = worker.dev_create_account().await?;
+    let ft = worker.dev_deploy(&ft_wasm).await?;
+    let outcome = ft
+        .call("new_default_meta")
+        .args_json(json!({ "owner_id": ft_owner.id(), "total_supply": "1000000000000000000000000000000" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let setup = init_original(worker, Some(ft.id())).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    for account_id in [setup.contract.id(), setup.owner.id(), setup.treasury.id(), alice.id()] {
+        ft_storage_deposit(&ft, &ft_owner, account_id.as_str()).await?;
+    }
+    let outcome = ft_owner
+        .call(ft.id(), "ft_transfer")
+        .args_json(json!({ "receiver_id": alice.id(), "amount": MINT_PRICE.as_yoctonear().to_string() }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = ft_transfer_call(&ft, &alice, setup.contract.id().as_str(), MINT_PRICE.as_yoctonear(), "").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    upgrade(&setup, 0).await?;
+
+    // The deposit the original kept per account is now the account's deposit in the base currency
+    let deposits_of = || view::<u128>(&setup, "ft_deposits_of", json!({ "account_id": alice.id(), "ft_contract": ft.id() }));
+    assert_eq!(deposits_of().await?, MINT_PRICE.as_yoctonear());
+    let outcome = ft_mint_with_deposit(&setup, &ft, &alice, "a1", VAULT_DEPOSIT).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(deposits_of().await?, 0);
+    assert!(ft_balance_of(&ft, &vault_id(&setup, "a1")).await? > 0);
+
+    Ok(())
+}
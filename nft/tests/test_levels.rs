@@ -0,0 +1,58 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_upgrades_spend_ft_deposits_per_level() -> TestResult {
+    let (setup, ft, ft_owner) = init_ft_collection(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(ft_mint(&setup, &ft, &ft_owner, &alice, "a1").await?.is_success());
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_level_costs")
+        .args_json(json!({ "level_costs": ["100", "200", "300"] }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let outcome = ft_owner
+        .call(ft.id(), "ft_transfer")
+        .args_json(json!({ "receiver_id": alice.id(), "amount": "1000" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = ft_transfer_call(&ft, &alice, setup.contract.id().as_str(), 1000, "").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let upgrade = |account: &near_workspaces::Account, levels: u32| {
+        account
+            .call(setup.contract.id(), "upgrade_token")
+            .args_json(json!({ "token_id": "a1", "levels": levels }))
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+    };
+
+    let deposited: u128 = view(&setup, "ft_deposits_of", json!({ "account_id": alice.id() })).await?;
+    assert!(upgrade(&bob, 1).await?.is_failure(), "only the owner can upgrade");
+    let outcome = upgrade(&alice, 2).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(outcome.logs().iter().any(|log| log.contains("\"event\":\"nft_metadata_update\"")));
+
+    let level: u32 = view(&setup, "token_level", json!({ "token_id": "a1" })).await?;
+    assert_eq!(level, 2);
+    let deposits: u128 = view(&setup, "ft_deposits_of", json!({ "account_id": alice.id() })).await?;
+    assert_eq!(deposited - deposits, 300);
+
+    assert!(upgrade(&alice, 2).await?.is_failure(), "level 3 is the maximum");
+    assert!(upgrade(&alice, 1).await?.is_success());
+    let deposits: u128 = view(&setup, "ft_deposits_of", json!({ "account_id": alice.id() })).await?;
+    assert_eq!(deposited - deposits, 600);
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::*;
+use near_workspaces::result::ExecutionFinalResult;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+const SECOND: u64 = 1_000_000_000;
+
+/// Timestamp of the block the transaction of `outcome` executed in.
+async fn executed_at(setup: &Setup, outcome: &ExecutionFinalResult) -> TestResult<u64> {
+    let block = setup.worker.view_block().block_hash(outcome.outcome().block_hash).await?;
+    Ok(block.timestamp())
+}
+
+#[tokio::test]
+async fn test_locked_tokens_cant_move_until_the_release_date() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let locked_until = setup.worker.view_block().await?.timestamp() + 30 * SECOND;
+
+    let outcome = mint_with_args(&setup, &alice, "a1", json!({ "transfer_locked_until": locked_until.to_string() })).await?;
+    assert!(outcome.is_failure(), "only the collection owner mints locked tokens");
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "nft_mint")
+        .args_json(json!({
+            "token_id": "team-1",
+            "token_owner_id": alice.id(),
+            "token_metadata": token_metadata("team-1"),
+            "transfer_locked_until": locked_until.to_string(),
+        }))
+        .deposit(NearToken::from_yoctonear(MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear()))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(view::<Option<String>>(&setup, "unlock_time", json!({ "token_id": "team-1" })).await?, Some(locked_until.to_string()));
+    assert_eq!(
+        view::<Vec<String>>(&setup, "locked_tokens_of", json!({ "account_id": alice.id() })).await?,
+        vec!["team-1".to_string()]
+    );
+
+    // Approvals can be granted, but neither they nor a listing can execute before the release date
+    let outcome = alice
+        .call(setup.contract.id(), "nft_approve")
+        .args_json(json!({ "token_id": "team-1", "account_id": bob.id() }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = alice
+        .call(setup.contract.id(), "nft_approve")
+        .args_json(json!({ "token_id": "team-1", "account_id": bob.id(), "msg": "{}" }))
+        .deposit(NearToken::from_millinear(10))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "locked tokens can't be listed");
+
+    let transfer = |account: &near_workspaces::Account| {
+        account
+            .call(setup.contract.id(), "nft_transfer")
+            .args_json(json!({ "receiver_id": bob.id(), "token_id": "team-1" }))
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+    };
+    let outcome = transfer(&bob).await?;
+    assert!(outcome.is_failure(), "the approval can't execute before the release date");
+    let outcome = transfer(&alice).await?;
+    assert!(outcome.is_failure(), "locked tokens can't move");
+    assert!(executed_at(&setup, &outcome).await? < locked_until);
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "team-1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "locked tokens can't burn");
+
+    // Walk the chain up to one second before the release date, where the lock still holds
+    while setup.worker.view_block().await?.timestamp() + 2 * SECOND < locked_until {
+        setup.worker.fast_forward(1).await?;
+    }
+    let outcome = transfer(&alice).await?;
+    let at = executed_at(&setup, &outcome).await?;
+    if at < locked_until {
+        assert!(outcome.is_failure(), "transfer at {} went through before {}", at, locked_until);
+    }
+
+    // And past it, by at least a second
+    while setup.worker.view_block().await?.timestamp() < locked_until + SECOND {
+        setup.worker.fast_forward(1).await?;
+    }
+    let outcome = transfer(&alice).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(executed_at(&setup, &outcome).await? >= locked_until + SECOND);
+    assert!(view::<Vec<String>>(&setup, "locked_tokens_of", json!({ "account_id": alice.id() })).await?.is_empty());
+    assert_eq!(view::<Option<String>>(&setup, "unlock_time", json!({ "token_id": "team-1" })).await?, Some(locked_until.to_string()));
+
+    Ok(())
+}
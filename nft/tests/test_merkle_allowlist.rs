@@ -0,0 +1,62 @@
+mod common;
+
+use common::*;
+use near_workspaces::Account;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+fn leaf(account: &Account) -> [u8; 32] {
+    Sha256::digest(account.id().as_bytes()).into()
+}
+
+fn parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    Sha256::digest([left, right].concat()).into()
+}
+
+async fn set_root(setup: &Setup, root: Option<[u8; 32]>) -> TestResult {
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_allowlist_root")
+        .args_json(json!({ "root": root }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    Ok(())
+}
+
+async fn mint_with_proof(setup: &Setup, minter: &Account, token_id: &str, proof: &[[u8; 32]]) -> TestResult<bool> {
+    Ok(mint_with_args(setup, minter, token_id, json!({ "merkle_proof": proof })).await?.is_success())
+}
+
+#[tokio::test]
+async fn test_only_accounts_in_the_tree_can_mint() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let carol = setup.worker.dev_create_account().await?;
+    let dave = setup.worker.dev_create_account().await?;
+
+    // Three leaves: alice and bob share a parent, carol is promoted to the level above
+    let alice_bob = parent(leaf(&alice), leaf(&bob));
+    let root = parent(alice_bob, leaf(&carol));
+    set_root(&setup, Some(root)).await?;
+
+    assert!(mint_with_proof(&setup, &alice, "a1", &[leaf(&bob), leaf(&carol)]).await?);
+    assert!(mint_with_proof(&setup, &carol, "c1", &[alice_bob]).await?);
+    assert!(!mint_with_proof(&setup, &dave, "d1", &[leaf(&bob), leaf(&carol)]).await?, "not a leaf");
+    assert!(!mint_with_proof(&setup, &bob, "b1", &[leaf(&carol), leaf(&alice)]).await?, "wrong siblings");
+    assert!(!mint_with_proof(&setup, &bob, "b1", &[]).await?, "incomplete proof");
+    assert!(mint(&setup, &bob, "b1").await?.is_failure(), "a proof is required");
+
+    // A single-leaf tree: the root is the leaf and the proof is empty
+    set_root(&setup, Some(leaf(&dave))).await?;
+    assert!(mint_with_proof(&setup, &dave, "d1", &[]).await?);
+    assert!(!mint_with_proof(&setup, &alice, "a2", &[]).await?);
+
+    // Without a root nobody needs a proof
+    set_root(&setup, None).await?;
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+
+    Ok(())
+}
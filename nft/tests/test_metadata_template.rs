@@ -0,0 +1,54 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_template_generates_metadata_from_the_mint_index() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let template = json!({ "base_uri": "https://example.com/drop/", "title_prefix": "Drop", "copies": "1" });
+    assert!(alice
+        .call(setup.contract.id(), "set_metadata_template")
+        .args_json(json!({ "metadata_template": template }))
+        .transact()
+        .await?
+        .is_failure());
+    assert!(mint(&setup, &alice, "a0").await?.is_success());
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_metadata_template")
+        .args_json(json!({ "metadata_template": template }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let preview: Value = view(&setup, "preview_metadata", json!({ "index": "7" })).await?;
+    assert_eq!(preview["title"], "Drop #7");
+    assert_eq!(preview["media"], "https://example.com/drop/7.json");
+
+    // The minter's metadata is ignored, the media sits under the contract's base URI
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    let token: Value = view(&setup, "nft_token", json!({ "token_id": "a1" })).await?;
+    assert_eq!(token["metadata"]["title"], "Drop #1");
+    assert_eq!(token["metadata"]["copies"], 1);
+    let contract_metadata: Value = view(&setup, "nft_metadata", json!({})).await?;
+    let base_uri = contract_metadata["base_uri"].as_str().unwrap().trim_end_matches('/');
+    assert_eq!(token["metadata"]["media"], format!("{}/1.json", base_uri));
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_metadata_template")
+        .args_json(json!({ "metadata_template": null }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+    let token: Value = view(&setup, "nft_token", json!({ "token_id": "a2" })).await?;
+    assert_eq!(token["metadata"]["title"], "a2");
+    assert_eq!(view::<Value>(&setup, "preview_metadata", json!({ "index": "7" })).await?, Value::Null);
+
+    Ok(())
+}
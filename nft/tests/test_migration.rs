@@ -0,0 +1,65 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_migrate_account_moves_the_whole_position() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let carol = setup.worker.dev_create_account().await?;
+    let new_alice = setup.worker.dev_create_account().await?;
+    for token_id in ["a1", "a2"] {
+        assert!(mint(&setup, &alice, token_id).await?.is_success());
+    }
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+    burn(&setup, &bob, "b1").await?;
+    let rewards: u128 = view(&setup, "balance_of", json!({ "owner": alice.id() })).await?;
+    assert!(rewards > 0);
+    let outcome = alice
+        .call(setup.contract.id(), "storage_deposit")
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let migrate = |account: &near_workspaces::Account, new_account: &near_workspaces::Account| {
+        account
+            .call(setup.contract.id(), "migrate_account")
+            .args_json(json!({ "new_account": new_account.id() }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+    };
+    let outcome = migrate(&alice, &new_alice).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(outcome.logs().iter().any(|log| log.contains("\"event\":\"migrated\"") && log.contains("\"token_count\":\"2\"")));
+    assert_eq!(outcome.json::<u64>()?, 0);
+
+    assert_eq!(view::<String>(&setup, "nft_supply_for_owner", json!({ "account_id": alice.id() })).await?, "0");
+    assert_eq!(view::<String>(&setup, "nft_supply_for_owner", json!({ "account_id": new_alice.id() })).await?, "2");
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "owner": alice.id() })).await?, 0);
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "owner": new_alice.id() })).await?, rewards);
+    assert_eq!(view::<String>(&setup, "storage_balance_of", json!({ "account_id": alice.id() })).await?, "0");
+    assert_eq!(
+        view::<String>(&setup, "storage_balance_of", json!({ "account_id": new_alice.id() })).await?,
+        NearToken::from_millinear(10).as_yoctonear().to_string()
+    );
+    assert_eq!(view::<u64>(&setup, "total_holders", json!({})).await?, 1);
+
+    // Tokens that can't move stop the migration before anything is transferred
+    assert!(mint(&setup, &carol, "c1").await?.is_success());
+    let outcome = carol
+        .call(setup.contract.id(), "stake")
+        .args_json(json!({ "token_id": "c1" }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(migrate(&carol, &bob).await?.is_failure(), "staked tokens can't migrate");
+    assert_eq!(view::<String>(&setup, "nft_supply_for_owner", json!({ "account_id": carol.id() })).await?, "1");
+
+    Ok(())
+}
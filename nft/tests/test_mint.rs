@@ -0,0 +1,277 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract};
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_oversized_metadata_mint_creates_no_vault() -> TestResult {
+    let setup = init(0).await?;
+    let user = setup.worker.dev_create_account().await?;
+    let balance_before = user.view_account().await?.balance;
+
+    let outcome = user
+        .call(setup.contract.id(), "nft_mint")
+        .args_json(json!({
+            "token_id": "big",
+            "token_owner_id": user.id(),
+            "token_metadata": { "title": "Big", "extra": "x".repeat(100_000) },
+        }))
+        .deposit(NearToken::from_yoctonear(MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear()))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+    assert!(!touched(&outcome, &vault_id(&setup, "big")));
+
+    // Only gas was spent, the deposit came back in full
+    let balance_after = user.view_account().await?.balance;
+    assert!(balance_before.as_yoctonear() - balance_after.as_yoctonear() < NearToken::from_millinear(50).as_yoctonear());
+    assert_eq!(view::<String>(&setup, "nft_total_supply", json!({})).await?, "0");
+    assert_eq!(view::<u128>(&setup, "index", json!({})).await?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_metadata_limits_are_enforced_and_exposed() -> TestResult {
+    let setup = init_with(0, json!({ "metadata_limits": {
+        "max_title_len": 8,
+        "max_description_len": 16,
+        "max_media_len": 64,
+        "max_extra_len": 32,
+    }}))
+    .await?;
+    let user = setup.worker.dev_create_account().await?;
+
+    let config: serde_json::Value = view(&setup, "get_config", json!({})).await?;
+    assert_eq!(config["metadata_limits"]["max_title_len"], 8);
+
+    let outcome = user
+        .call(setup.contract.id(), "nft_mint")
+        .args_json(json!({
+            "token_id": "long",
+            "token_owner_id": user.id(),
+            "token_metadata": { "title": "Much too long" },
+        }))
+        .deposit(NearToken::from_yoctonear(MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear()))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Token metadata title is 13 bytes, the maximum is 8"));
+
+    assert!(mint(&setup, &user, "ok").await?.is_success());
+
+    Ok(())
+}
+
+/// NEAR `minter` spends minting `token_id` with `deposit` attached, paying with `ft` if given, with
+/// the breakdown's estimate of the cost without and with token storage.
+async fn mint_spend(
+    setup: &Setup,
+    minter: &Account,
+    token_id: &str,
+    deposit: NearToken,
+    ft: Option<&Contract>,
+) -> TestResult<(u128, u128, u128)> {
+    let breakdown: Value = view(
+        setup,
+        "mint_cost_breakdown",
+        json!({ "token_metadata": token_metadata(token_id), "pay_with": ft.map(|ft| ft.id()) }),
+    )
+    .await?;
+    let item = |name: &str| breakdown[name].as_str().unwrap().parse::<u128>().unwrap();
+    let before = minter.view_account().await?.balance;
+    let outcome = match ft {
+        Some(ft) => ft_mint_with_deposit(setup, ft, minter, token_id, deposit).await?,
+        None => mint_with_deposit(setup, minter, token_id, deposit).await?,
+    };
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let after = minter.view_account().await?.balance;
+    let near_total = item("near_total");
+    Ok((before.as_yoctonear() - after.as_yoctonear(), near_total - item("token_storage"), near_total))
+}
+
+#[tokio::test]
+async fn test_excess_deposit_is_refunded() -> TestResult {
+    let gas_tolerance = NearToken::from_millinear(50).as_yoctonear();
+    let extra = NearToken::from_near(5).as_yoctonear();
+
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let deposit = MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear() + extra;
+    let (spent, without_storage, near_total) = mint_spend(&setup, &alice, "a1", NearToken::from_yoctonear(deposit), None).await?;
+    assert!(spent > without_storage, "the price, vault deposit and some storage are paid");
+    assert!(spent < near_total + gas_tolerance, "spent {} of {}", spent, deposit);
+
+    // Priced in an FT, only the vault deposit and storage come from the attached NEAR
+    let (setup, ft, ft_owner) = init_ft_collection(0).await?;
+    let bob = setup.worker.dev_create_account().await?;
+    ft_storage_deposit(&ft, &ft_owner, bob.id().as_str()).await?;
+    let outcome = ft_owner
+        .call(ft.id(), "ft_transfer")
+        .args_json(json!({ "receiver_id": bob.id(), "amount": MINT_PRICE.as_yoctonear().to_string() }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(ft_transfer_call(&ft, &bob, setup.contract.id().as_str(), MINT_PRICE.as_yoctonear(), "").await?.is_success());
+    let deposit = VAULT_DEPOSIT.as_yoctonear() + extra;
+    let (spent, without_storage, near_total) =
+        mint_spend(&setup, &bob, "b1", NearToken::from_yoctonear(deposit), Some(&ft)).await?;
+    assert!(spent > without_storage);
+    assert!(spent < near_total + gas_tolerance, "spent {} of {}", spent, deposit);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_duplicate_token_id_fails_before_anything_is_paid() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    let bob_before = bob.view_account().await?.balance;
+    let outcome = mint(&setup, &bob, "a1").await?;
+    assert!(format!("{:?}", outcome.clone().into_result().unwrap_err()).contains("Token a1 already exists"));
+    assert!(!touched(&outcome, &vault_id(&setup, "a1")), "no vault call was scheduled");
+    let bob_spent = bob_before.as_yoctonear() - bob.view_account().await?.balance.as_yoctonear();
+    assert!(bob_spent < NearToken::from_millinear(50).as_yoctonear(), "the deposit came back");
+    assert_eq!(view::<u128>(&setup, "index", json!({})).await?, 1);
+    assert_eq!(view::<String>(&setup, "mints_of", json!({ "account_id": bob.id() })).await?, "0");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_minting_past_the_supply_creates_no_vault() -> TestResult {
+    let setup = init(1).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    let alice_before = alice.view_account().await?.balance;
+    let outcome = mint(&setup, &alice, "a2").await?;
+    assert!(format!("{:?}", outcome.clone().into_result().unwrap_err()).contains("Exceeded total supply"));
+    assert!(!touched(&outcome, &vault_id(&setup, "a2")), "no vault call was scheduled");
+    let alice_spent = alice_before.as_yoctonear() - alice.view_account().await?.balance.as_yoctonear();
+    assert!(alice_spent < NearToken::from_millinear(50).as_yoctonear(), "the deposit came back");
+    assert_eq!(view::<u128>(&setup, "index", json!({})).await?, 1);
+    assert!(setup.worker.view_account(&vault_id(&setup, "a2").parse()?).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_burned_token_ids_cant_be_minted_again() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &alice, "2").await?.is_success());
+    burn(&setup, &alice, "a1").await?;
+    burn(&setup, &alice, "2").await?;
+    assert!(view::<bool>(&setup, "is_burned", json!({ "token_id": "a1" })).await?);
+    assert_eq!(view::<String>(&setup, "burned_supply", json!({})).await?, "2");
+
+    let bob_before = bob.view_account().await?.balance;
+    let outcome = mint(&setup, &bob, "a1").await?;
+    assert!(format!("{:?}", outcome.clone().into_result().unwrap_err()).contains("Token a1 was burned"));
+    assert!(!touched(&outcome, &vault_id(&setup, "a1")), "the old vault is left alone");
+    let bob_spent = bob_before.as_yoctonear() - bob.view_account().await?.balance.as_yoctonear();
+    assert!(bob_spent < NearToken::from_millinear(50).as_yoctonear(), "the deposit came back");
+
+    // Auto ids skip burned ones too
+    let outcome = mint_with_args(&setup, &bob, "", json!({ "token_id": null })).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let token: serde_json::Value = outcome.json()?;
+    assert_eq!(token["token_id"], "3");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_vault_deployment_rolls_the_mint_back() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    // The vault account already exists, so creating it fails
+    let taken = setup
+        .contract
+        .as_account()
+        .create_subaccount("x1")
+        .initial_balance(NearToken::from_millinear(100))
+        .transact()
+        .await?;
+    assert!(taken.is_success());
+
+    let alice_before = alice.view_account().await?.balance;
+    let owner_before = setup.owner.view_account().await?.balance;
+    let outcome = mint(&setup, &alice, "x1").await?;
+    assert!(outcome.logs().iter().any(|log| log.contains("vault deployment failed")));
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("the mint was rolled back"));
+
+    assert_eq!(view::<String>(&setup, "nft_total_supply", json!({})).await?, "0");
+    assert_eq!(view::<u128>(&setup, "index", json!({})).await?, 0);
+    assert!(view::<Option<Value>>(&setup, "nft_token", json!({ "token_id": "x1" })).await?.is_none());
+    assert_eq!(setup.owner.view_account().await?.balance, owner_before, "the owner isn't paid");
+    let alice_spent = alice_before.as_yoctonear() - alice.view_account().await?.balance.as_yoctonear();
+    assert!(alice_spent < NearToken::from_millinear(50).as_yoctonear(), "only gas was spent");
+
+    // The rolled back mint left nothing behind for the next one
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_nft_mint_returns_the_token_once_the_vault_is_live() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    let outcome = mint(&setup, &alice, "a1").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(touched(&outcome, &vault_id(&setup, "a1")));
+    let token: Value = outcome.json()?;
+    assert_eq!(token["token_id"], "a1");
+    assert_eq!(token["owner_id"], alice.id().as_str());
+
+    // The eager variant hands the token back while the vault is still being deployed
+    let outcome = alice
+        .call(setup.contract.id(), "nft_mint_eager")
+        .args_json(json!({
+            "token_id": "a2",
+            "token_owner_id": alice.id(),
+            "token_metadata": token_metadata("a2"),
+        }))
+        .deposit(NearToken::from_yoctonear(MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear()))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let token: Value = outcome.json()?;
+    assert_eq!(token["token_id"], "a2");
+    assert_eq!(view::<String>(&setup, "nft_total_supply", json!({})).await?, "2");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_each_ft_mint_spends_its_own_deposit() -> TestResult {
+    let (setup, ft, ft_owner) = init_ft_collection(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    assert!(ft_mint(&setup, &ft, &ft_owner, &alice, "a1").await?.is_success());
+    assert_eq!(view::<u128>(&setup, "ft_deposits_of", json!({ "account_id": alice.id() })).await?, 0);
+    let outcome = ft_mint_with_deposit(&setup, &ft, &alice, "a2", VAULT_DEPOSIT).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Insufficient price to mint"));
+    let outcome = mint_with_deposit(&setup, &alice, "a2", VAULT_DEPOSIT).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("NEAR is not accepted for mints"));
+
+    assert!(ft_mint(&setup, &ft, &ft_owner, &alice, "a2").await?.is_success());
+    assert_eq!(view::<u128>(&setup, "ft_deposits_of", json!({ "account_id": alice.id() })).await?, 0);
+
+    Ok(())
+}
@@ -0,0 +1,236 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract};
+use serde_json::{json, Value};
+
+const SECOND_PRICE: u128 = 500;
+
+/// Give `account` `amount` of `ft` and deposit it into the collection.
+async fn deposit_ft(setup: &Setup, ft: &Contract, ft_owner: &Account, account: &Account, amount: u128) -> TestResult {
+    let outcome = ft_owner
+        .call(ft.id(), "ft_transfer")
+        .args_json(json!({ "receiver_id": account.id(), "amount": amount.to_string() }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(ft_transfer_call(ft, account, setup.contract.id().as_str(), amount, "").await?.is_success());
+    Ok(())
+}
+
+async fn deposits_of(setup: &Setup, account: &Account, ft: &Contract) -> TestResult<u128> {
+    view(setup, "ft_deposits_of", json!({ "account_id": account.id(), "ft_contract": ft.id() })).await
+}
+
+/// Deploy another FT for the collection to accept besides its base one, with the collection, its
+/// owner, its treasury and `accounts` registered.
+async fn deploy_second_ft(setup: &Setup, ft_owner: &Account, accounts: &[&Account]) -> TestResult<Contract> {
+    let ft_wasm = near_workspaces::compile_project("../ft").await?;
+    let second = setup.worker.dev_deploy(&ft_wasm).await?;
+    let outcome = second
+        .call("new_default_meta")
+        .args_json(json!({ "owner_id": ft_owner.id(), "total_supply": "1000000000000000000000000000000" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    for account_id in [setup.contract.id(), setup.owner.id(), setup.treasury.id()] {
+        ft_storage_deposit(&second, ft_owner, account_id.as_str()).await?;
+    }
+    for account in accounts {
+        ft_storage_deposit(&second, ft_owner, account.id().as_str()).await?;
+    }
+    Ok(second)
+}
+
+#[tokio::test]
+async fn test_mints_can_be_paid_in_any_accepted_currency() -> TestResult {
+    let (setup, ft, ft_owner) = init_ft_collection(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let second = deploy_second_ft(&setup, &ft_owner, &[&alice]).await?;
+
+    // Not accepted yet, so the transfer is refunded
+    deposit_ft(&setup, &second, &ft_owner, &alice, SECOND_PRICE).await?;
+    assert_eq!(deposits_of(&setup, &alice, &second).await?, 0);
+    assert_eq!(ft_balance_of(&second, alice.id().as_str()).await?, SECOND_PRICE);
+
+    let add_currency = |account: &Account| {
+        account
+            .call(setup.contract.id(), "add_mint_currency")
+            .args_json(json!({ "ft_contract": second.id(), "price": SECOND_PRICE.to_string() }))
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+    };
+    assert!(add_currency(&alice).await?.is_failure());
+    let outcome = add_currency(&setup.owner).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(ft_transfer_call(&second, &alice, setup.contract.id().as_str(), SECOND_PRICE, "").await?.is_success());
+    assert_eq!(deposits_of(&setup, &alice, &second).await?, SECOND_PRICE);
+    assert_eq!(deposits_of(&setup, &alice, &ft).await?, 0, "deposits don't mix");
+
+    let outcome = ft_mint_with_deposit(&setup, &second, &alice, "a1", VAULT_DEPOSIT).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(deposits_of(&setup, &alice, &second).await?, 0);
+    let vault = vault_id(&setup, "a1");
+    assert!(ft_balance_of(&second, &vault).await? > 0, "the vault holds the currency it was paid in");
+    assert_eq!(ft_balance_of(&ft, &vault).await?, 0);
+
+    // NEAR only once the owner prices it
+    assert!(mint(&setup, &alice, "a2").await?.is_failure());
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_near_mint_price")
+        .args_json(json!({ "near_mint_price": MINT_PRICE.as_yoctonear().to_string() }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+
+    // Removing a currency stops mints in it, deposits stay withdrawable
+    deposit_ft(&setup, &second, &ft_owner, &alice, SECOND_PRICE).await?;
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "remove_mint_currency")
+        .args_json(json!({ "ft_contract": second.id() }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = ft_mint_with_deposit(&setup, &second, &alice, "a3", VAULT_DEPOSIT).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("is not accepted for mints"));
+    let balance = ft_balance_of(&second, alice.id().as_str()).await?;
+    let outcome = alice
+        .call(setup.contract.id(), "withdraw_ft_deposit")
+        .args_json(json!({ "ft_contract": second.id() }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(ft_balance_of(&second, alice.id().as_str()).await?, balance + SECOND_PRICE);
+    assert_eq!(deposits_of(&setup, &alice, &second).await?, 0);
+
+    // Each vault pays out in the currency it was funded in, not the base currency
+    let burn = |token_id: &'static str| {
+        alice
+            .call(setup.contract.id(), "burn")
+            .args_json(json!({ "token_id": token_id }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+    };
+    let balance = ft_balance_of(&second, alice.id().as_str()).await?;
+    let outcome = burn("a1").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(ft_balance_of(&second, alice.id().as_str()).await? > balance);
+    assert_eq!(ft_balance_of(&second, &vault).await?, 0);
+    assert!(!touched(&outcome, ft.id().as_str()));
+    let near_balance = alice.view_account().await?.balance;
+    let outcome = burn("a2").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(alice.view_account().await?.balance > near_balance, "the NEAR vault pays out NEAR");
+    assert!(!touched(&outcome, ft.id().as_str()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ft_transfer_call_can_mint_in_one_transaction() -> TestResult {
+    let (setup, ft, ft_owner) = init_ft_collection(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    ft_storage_deposit(&ft, &ft_owner, alice.id().as_str()).await?;
+    let outcome = ft_owner
+        .call(ft.id(), "ft_transfer")
+        .args_json(json!({ "receiver_id": alice.id(), "amount": (2 * MINT_PRICE.as_yoctonear()).to_string() }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let msg = json!({
+        "action": "mint",
+        "token_id": "a1",
+        "token_owner_id": alice.id(),
+        "token_metadata": token_metadata("a1"),
+    })
+    .to_string();
+
+    // Without a storage balance there is no NEAR for the vault, and every FT comes back
+    let outcome = ft_transfer_call(&ft, &alice, setup.contract.id().as_str(), 2 * MINT_PRICE.as_yoctonear(), &msg).await?;
+    assert!(!touched(&outcome, &vault_id(&setup, "a1")));
+    assert_eq!(ft_balance_of(&ft, alice.id().as_str()).await?, 2 * MINT_PRICE.as_yoctonear());
+
+    let outcome = alice
+        .call(setup.contract.id(), "storage_deposit")
+        .args_json(json!({}))
+        .deposit(VAULT_DEPOSIT)
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = ft_transfer_call(&ft, &alice, setup.contract.id().as_str(), 2 * MINT_PRICE.as_yoctonear(), &msg).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(touched(&outcome, &vault_id(&setup, "a1")));
+    let token: serde_json::Value = view(&setup, "nft_token", json!({ "token_id": "a1" })).await?;
+    assert_eq!(token["owner_id"], alice.id().to_string());
+
+    // Only the price was kept, the rest went back without touching the deposits
+    assert_eq!(ft_balance_of(&ft, alice.id().as_str()).await?, MINT_PRICE.as_yoctonear());
+    assert_eq!(deposits_of(&setup, &alice, &ft).await?, 0);
+    let storage_left: String = view(&setup, "storage_balance_of", json!({ "account_id": alice.id() })).await?;
+    assert!(storage_left.parse::<u128>()? < VAULT_DEPOSIT.as_yoctonear());
+
+    let outcome = ft_transfer_call(&ft, &alice, setup.contract.id().as_str(), 1, "{\"action\":\"burn\"}").await?;
+    assert!(format!("{:?}", outcome).contains("Unknown action"));
+
+    Ok(())
+}
+
+/// The burn fee `burn_quote` expects from `token_id`, checking it shares none with the holders.
+async fn quoted_burn_fee(setup: &Setup, token_id: &str) -> TestResult<u128> {
+    let quote: Value = view(setup, "burn_quote", json!({ "token_id": token_id })).await?;
+    assert_eq!(quote["per_holder"], "0", "holders get no share of {}", token_id);
+    let vault_amount: u128 = quote["vault_amount"].as_str().unwrap().parse()?;
+    Ok(vault_amount / 10)
+}
+
+#[tokio::test]
+async fn test_burn_fees_in_another_currency_go_to_the_treasury() -> TestResult {
+    let (setup, ft, ft_owner) = init_ft_collection(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let second = deploy_second_ft(&setup, &ft_owner, &[&alice]).await?;
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "add_mint_currency")
+        .args_json(json!({ "ft_contract": second.id(), "price": SECOND_PRICE.to_string() }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_near_mint_price")
+        .args_json(json!({ "near_mint_price": MINT_PRICE.as_yoctonear().to_string() }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(ft_mint(&setup, &ft, &ft_owner, &bob, "b1").await?.is_success());
+    deposit_ft(&setup, &second, &ft_owner, &alice, SECOND_PRICE).await?;
+    assert!(ft_mint_with_deposit(&setup, &second, &alice, "a1", VAULT_DEPOSIT).await?.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+
+    // Holders are paid in the base currency, so neither share is theirs
+    let burn_fee = quoted_burn_fee(&setup, "a1").await?;
+    let treasury_balance = ft_balance_of(&second, setup.treasury.id().as_str()).await?;
+    burn(&setup, &alice, "a1").await?;
+    assert_eq!(ft_balance_of(&second, setup.treasury.id().as_str()).await?, treasury_balance + burn_fee);
+    let burn_fee = quoted_burn_fee(&setup, "a2").await?;
+    let treasury_balance = setup.treasury.view_account().await?.balance.as_yoctonear();
+    burn(&setup, &alice, "a2").await?;
+    assert_eq!(setup.treasury.view_account().await?.balance.as_yoctonear(), treasury_balance + burn_fee);
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "owner": bob.id() })).await?, 0);
+    let outcome = bob.call(setup.contract.id(), "withdraw").args_json(json!({})).max_gas().transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(ft_balance_of(&ft, bob.id().as_str()).await?, 0);
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::*;
+use serde_json::{json, Value};
+
+/// Every NEP-297 event named `event` in `logs`, with its envelope.
+fn events(logs: &[&str], event: &str) -> Vec<Value> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|log| serde_json::from_str::<Value>(log).unwrap())
+        .filter(|log| log["event"] == event)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_near_mints_report_price_and_split() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let outcome = mint(&setup, &alice, "a1").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let logs = outcome.logs();
+    let half = (MINT_PRICE.as_yoctonear() / 2).to_string();
+
+    let mint_event = &events(&logs, "nft_mint")[0];
+    let memo: Value = serde_json::from_str(mint_event["data"][0]["memo"].as_str().unwrap())?;
+    assert_eq!(memo, json!({
+        "price": MINT_PRICE.as_yoctonear().to_string(),
+        "currency": "near",
+        "vault_amount": half,
+        "owner_amount": half,
+    }));
+
+    let paid = &events(&logs, "mint_paid")[0];
+    assert_eq!(paid["standard"], "marketplace_nft");
+    assert_eq!(paid["version"], "1.0.0");
+    assert_eq!(paid["data"][0], json!({
+        "token_id": "a1",
+        "owner_id": alice.id(),
+        "minter": alice.id(),
+        "price": MINT_PRICE.as_yoctonear().to_string(),
+        "currency": "near",
+        "vault_amount": half,
+        "owner_amount": half,
+    }));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ft_mints_report_the_currency() -> TestResult {
+    let (setup, ft, ft_owner) = init_ft_collection(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let outcome = ft_mint(&setup, &ft, &ft_owner, &alice, "a1").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let logs = outcome.logs();
+    let half = (MINT_PRICE.as_yoctonear() / 2).to_string();
+
+    let mint_event = &events(&logs, "nft_mint")[0];
+    let memo: Value = serde_json::from_str(mint_event["data"][0]["memo"].as_str().unwrap())?;
+    assert_eq!(memo["currency"], ft.id().as_str());
+    assert_eq!(memo["price"], MINT_PRICE.as_yoctonear().to_string());
+    assert_eq!(memo["vault_amount"], half);
+
+    let paid = &events(&logs, "mint_paid")[0];
+    assert_eq!(paid["data"][0]["currency"], ft.id().as_str());
+    assert_eq!(paid["data"][0]["owner_amount"], half);
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::*;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_mint_fee_is_capped_at_init() -> TestResult {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker.dev_deploy(&near_workspaces::compile_project("./").await?).await?;
+    let outcome = contract
+        .call("new")
+        .args_json(json!({
+            "owner_id": contract.id(),
+            "metadata": { "spec": "nft-1.0.0", "name": "Test Collection", "symbol": "TEST" },
+            "mint_price": "1",
+            "payment_split_percent": "50",
+            "total_supply": "0",
+            "burn_fee": "10",
+            "treasury": contract.id(),
+            "royalty": "500",
+            "mint_fee_bps": "1001",
+        }))
+        .transact()
+        .await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("can't exceed 1000 basis points"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mint_fee_goes_to_the_treasury_before_the_split() -> TestResult {
+    let setup = init_with(0, json!({ "mint_fee_bps": "500" })).await?;
+    assert_eq!(view::<String>(&setup, "mint_fee_bps", json!({})).await?, "500");
+    let alice = setup.worker.dev_create_account().await?;
+
+    let treasury_before = setup.treasury.view_account().await?.balance.as_yoctonear();
+    let outcome = mint(&setup, &alice, "a1").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let treasury_gain = setup.treasury.view_account().await?.balance.as_yoctonear() - treasury_before;
+    let mint_fee = MINT_PRICE.as_yoctonear() * 500 / 10_000;
+    assert_eq!(treasury_gain, mint_fee);
+
+    // The rest is split 50/50 between the vault and the owner
+    let split = outcome.logs().into_iter().find(|log| log.contains("\"event\":\"mint_split\"")).unwrap();
+    let rest = (MINT_PRICE.as_yoctonear() - mint_fee) / 2;
+    assert!(split.contains(&format!("\"mint_fee\":\"{}\"", mint_fee)), "{}", split);
+    assert!(split.contains(&format!("\"vault_amount\":\"{}\"", rest)), "{}", split);
+    assert!(split.contains(&format!("\"owner_amount\":\"{}\"", rest)), "{}", split);
+    // and burning quotes what the vault was funded with, not the split of the whole price
+    let quote: serde_json::Value = view(&setup, "burn_quote", json!({ "token_id": "a1" })).await?;
+    assert_eq!(quote["vault_amount"], rest.to_string());
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use near_workspaces::Account;
+use serde_json::{json, Value};
+
+async fn mint_many(
+    setup: &Setup,
+    minter: &Account,
+    token_ids: &[&str],
+    deposit: NearToken,
+) -> TestResult<near_workspaces::result::ExecutionFinalResult> {
+    let tokens: Vec<Value> = token_ids.iter()
+        .map(|token_id| json!([token_id, minter.id(), token_metadata(token_id)]))
+        .collect();
+    Ok(minter
+        .call(setup.contract.id(), "nft_mint_many")
+        .args_json(json!({ "tokens": tokens }))
+        .deposit(deposit)
+        .max_gas()
+        .transact()
+        .await?)
+}
+
+fn batch_deposit(count: u128) -> NearToken {
+    NearToken::from_yoctonear((MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear()) * count)
+}
+
+#[tokio::test]
+async fn test_mint_many_emits_one_event_and_deploys_every_vault() -> TestResult {
+    let setup = init(3).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    let outcome = mint_many(&setup, &alice, &["a1", "a2"], batch_deposit(2)).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let mint_events: Vec<&str> = outcome.logs().into_iter()
+        .filter(|log| log.contains("\"event\":\"nft_mint\""))
+        .collect();
+    assert_eq!(mint_events.len(), 1);
+    assert!(mint_events[0].contains("\"token_ids\":[\"a1\",\"a2\"]"));
+    for token_id in ["a1", "a2"] {
+        assert!(touched(&outcome, &vault_id(&setup, token_id)));
+    }
+    assert_eq!(view::<String>(&setup, "nft_total_supply", json!({})).await?, "2");
+
+    // Two more would pass the supply of 3, so nothing is minted and no vault is created
+    let outcome = mint_many(&setup, &alice, &["a3", "a4"], batch_deposit(2)).await?;
+    assert!(format!("{:?}", outcome.clone().into_result().unwrap_err()).contains("Exceeded total supply"));
+    assert!(!touched(&outcome, &vault_id(&setup, "a3")));
+    assert_eq!(view::<String>(&setup, "nft_total_supply", json!({})).await?, "2");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mint_many_requires_the_deposit_of_every_token() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    let outcome = mint_many(&setup, &alice, &["a1", "a2"], batch_deposit(1)).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Insufficient price to mint"));
+    assert!(mint_many(&setup, &alice, &[], batch_deposit(1)).await?.is_failure());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mint_many_is_capped_per_transaction() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    assert_eq!(view::<u64>(&setup, "max_mints_per_tx", json!({})).await?, 10);
+    let set_max = |max_mints_per_tx: u64| {
+        setup
+            .owner
+            .call(setup.contract.id(), "set_max_mints_per_tx")
+            .args_json(json!({ "max_mints_per_tx": max_mints_per_tx }))
+            .transact()
+    };
+    assert!(set_max(11).await?.is_failure(), "past the gas ceiling");
+    assert!(set_max(0).await?.is_failure());
+    let outcome = set_max(2).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let outcome = mint_many(&setup, &alice, &["a1", "a2", "a3"], batch_deposit(3)).await?;
+    assert!(format!("{:?}", outcome.clone().into_result().unwrap_err()).contains("At most 2 tokens can be minted at once"));
+    for token_id in ["a1", "a2", "a3"] {
+        assert!(!touched(&outcome, &vault_id(&setup, token_id)));
+    }
+    assert_eq!(view::<String>(&setup, "nft_total_supply", json!({})).await?, "0");
+
+    Ok(())
+}
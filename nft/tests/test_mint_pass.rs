@@ -0,0 +1,57 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_minting_requires_a_verified_pass() -> TestResult {
+    let setup = init(0).await?;
+    let pass = init_partner(&setup).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&pass, &alice, "pass-1").await?.is_success());
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_mint_pass_contract")
+        .args_json(json!({ "mint_pass_contract": pass.contract.id() }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    assert!(mint(&setup, &alice, "a1").await?.is_failure(), "the pass must be verified first");
+
+    for (account, holds_pass) in [(&alice, true), (&bob, false)] {
+        let verified: bool = account
+            .call(setup.contract.id(), "verify_pass")
+            .deposit(NearToken::from_millinear(10))
+            .max_gas()
+            .transact()
+            .await?
+            .json()?;
+        assert_eq!(verified, holds_pass);
+        let recorded: bool = view(&setup, "is_pass_verified", json!({ "account_id": account.id() })).await?;
+        assert_eq!(recorded, holds_pass);
+    }
+
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_failure(), "bob holds no pass");
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_pass_verification_ttl")
+        .args_json(json!({ "ttl": "1" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    let outcome = alice
+        .call(setup.contract.id(), "verify_pass")
+        .deposit(NearToken::from_millinear(10))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_failure(), "the verification expired");
+
+    Ok(())
+}
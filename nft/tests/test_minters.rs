@@ -0,0 +1,82 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_mint_counts_survive_transfers_and_burns() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+
+    let outcome = alice
+        .call(setup.contract.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": bob.id(), "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    burn(&setup, &alice, "a2").await?;
+
+    assert_eq!(view::<String>(&setup, "minted_by", json!({ "account_id": alice.id() })).await?, "2");
+    assert_eq!(view::<String>(&setup, "minted_by", json!({ "account_id": bob.id() })).await?, "1");
+    let minters: Vec<(String, String)> = view(&setup, "minters", json!({ "from_index": "0", "limit": 10 })).await?;
+    assert_eq!(minters, vec![(alice.id().to_string(), "2".to_string()), (bob.id().to_string(), "1".to_string())]);
+    let rest: Vec<(String, String)> = view(&setup, "minters", json!({ "from_index": "1" })).await?;
+    assert_eq!(rest.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_only_approved_minters_mint_when_public_minting_is_off() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(alice
+        .call(setup.contract.id(), "set_public_minting")
+        .args_json(json!({ "public_minting": false }))
+        .transact()
+        .await?
+        .is_failure());
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_public_minting")
+        .args_json(json!({ "public_minting": false }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "grant_minter")
+        .args_json(json!({ "account_id": alice.id() }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(view::<bool>(&setup, "is_minter", json!({ "account_id": alice.id() })).await?);
+    let minters: Vec<String> = view(&setup, "get_minters", json!({ "from_index": "0", "limit": 10 })).await?;
+    assert_eq!(minters, vec![alice.id().to_string()]);
+
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &setup.owner, "o1").await?.is_success());
+    let outcome = mint(&setup, &bob, "b1").await?;
+    assert!(!touched(&outcome, &vault_id(&setup, "b1")));
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Only approved minters can mint"));
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "revoke_minter")
+        .args_json(json!({ "account_id": alice.id() }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(mint(&setup, &alice, "a2").await?.is_failure());
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_admin_burn_pays_the_owner_and_blocks_the_id() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    // Even with the treasury as vault beneficiary, a moderated token's vault goes to its owner
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_vault_beneficiary")
+        .args_json(json!({ "vault_beneficiary": "Treasury" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let admin_burn = |account: &near_workspaces::Account| {
+        account
+            .call(setup.contract.id(), "admin_burn")
+            .args_json(json!({ "token_id": "a1", "memo": "DMCA notice 42" }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+    };
+    assert!(admin_burn(&alice).await?.is_failure());
+
+    let vault_balance = setup.worker.view_account(&vault_id(&setup, "a1").parse()?).await?.balance;
+    let alice_before = alice.view_account().await?.balance;
+    let treasury_before = setup.treasury.view_account().await?.balance;
+    let storage_before = setup.contract.view_account().await?.storage_usage;
+    let outcome = admin_burn(&setup.owner).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let burn_event: Value = outcome.logs().iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|event| serde_json::from_str::<Value>(event).unwrap())
+        .find(|event| event["event"] == "nft_burn")
+        .unwrap();
+    assert_eq!(burn_event["data"][0]["owner_id"], alice.id().as_str());
+    assert_eq!(burn_event["data"][0]["authorized_id"], setup.owner.id().as_str());
+    let memo: Value = serde_json::from_str(burn_event["data"][0]["memo"].as_str().unwrap())?;
+    let vault_amount = MINT_PRICE.as_yoctonear() / 2;
+    assert_eq!(memo, json!({ "vault_withdrawal": vault_amount.to_string(), "moderation": "DMCA notice 42" }));
+
+    // The whole vault, its storage stake included, goes to alice with the token's freed storage,
+    // and nothing to the treasury
+    let freed = storage_before - setup.contract.view_account().await?.storage_usage;
+    let alice_gain = alice.view_account().await?.balance.as_yoctonear() - alice_before.as_yoctonear();
+    assert!(alice_gain >= vault_amount, "{}", alice_gain);
+    assert!(alice_gain <= vault_balance.as_yoctonear() + freed as u128 * 10u128.pow(19));
+    assert_eq!(setup.treasury.view_account().await?.balance, treasury_before);
+    assert!(view::<bool>(&setup, "is_moderated", json!({ "token_id": "a1" })).await?);
+
+    let outcome = mint(&setup, &setup.owner, "a1").await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Token a1 was removed by moderation"));
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use near_workspaces::Account;
+use serde_json::json;
+
+async fn owner_mint(
+    setup: &Setup,
+    caller: &Account,
+    token_id: &str,
+    receiver: &Account,
+    create_vault: bool,
+    deposit: NearToken,
+) -> TestResult<near_workspaces::result::ExecutionFinalResult> {
+    Ok(caller
+        .call(setup.contract.id(), "owner_mint")
+        .args_json(json!({
+            "token_id": token_id,
+            "receiver_id": receiver.id(),
+            "token_metadata": token_metadata(token_id),
+            "create_vault": create_vault,
+        }))
+        .deposit(deposit)
+        .max_gas()
+        .transact()
+        .await?)
+}
+
+#[tokio::test]
+async fn test_owner_mints_are_free_and_capped() -> TestResult {
+    let setup = init_with(3, json!({ "owner_allocation": "2" })).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let storage_only = NearToken::from_millinear(100);
+
+    assert!(owner_mint(&setup, &alice, "x1", &alice, true, VAULT_DEPOSIT).await?.is_failure(), "owner only");
+
+    let owner_before = setup.owner.view_account().await?.balance;
+    let outcome = owner_mint(&setup, &setup.owner, "o1", &alice, true, VAULT_DEPOSIT).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(touched(&outcome, &vault_id(&setup, "o1")));
+    assert!(outcome.logs().iter().any(|log| log.contains("\"event\":\"nft_mint\"")));
+    let owner_spent = owner_before.as_yoctonear() - setup.owner.view_account().await?.balance.as_yoctonear();
+    assert!(owner_spent < VAULT_DEPOSIT.as_yoctonear(), "no mint price, and the rest is refunded");
+
+    let outcome = owner_mint(&setup, &setup.owner, "o2", &alice, false, storage_only).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(!touched(&outcome, &vault_id(&setup, "o2")));
+    assert_eq!(view::<String>(&setup, "owner_mints", json!({})).await?, "2");
+    assert_eq!(view::<u64>(&setup, "total_holders", json!({})).await?, 1);
+    let outcome = owner_mint(&setup, &setup.owner, "o3", &alice, false, storage_only).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("allocation of 2 tokens is used up"));
+
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "o2" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(!touched(&outcome, &vault_id(&setup, "o2")), "nothing to withdraw from");
+
+    // Owner mints count against the total supply
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_failure());
+
+    Ok(())
+}
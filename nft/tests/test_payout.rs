@@ -0,0 +1,94 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_payouts_that_round_to_zero_are_rejected() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    let sell = |balance: &str| {
+        alice
+            .call(setup.contract.id(), "nft_transfer_payout")
+            .args_json(json!({ "receiver_id": bob.id(), "token_id": "a1", "balance": balance }))
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+    };
+
+    // With a 5% royalty, 1 yocto rounds both shares to zero and 2 is the first balance paying the seller
+    let outcome = sell("1").await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Balance is too small to pay anyone"));
+    let token: Value = view(&setup, "nft_token", json!({ "token_id": "a1" })).await?;
+    assert_eq!(token["owner_id"], alice.id().to_string(), "the token stays with the seller");
+    let payout: Value = view(&setup, "nft_payout", json!({ "token_id": "a1", "balance": "2" })).await?;
+    assert_eq!(payout["payout"][alice.id().as_str()], "1");
+    assert_eq!(payout["payout"][setup.owner.id().as_str()], "0");
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_min_payout_balance")
+        .args_json(json!({ "min_payout_balance": "100" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let config: Value = view(&setup, "get_config", json!({})).await?;
+    assert_eq!(config["min_payout_balance"], "100");
+    assert!(sell("99").await?.is_failure(), "below the minimum balance");
+
+    let outcome = sell("100").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let payout: Value = outcome.json()?;
+    assert_eq!(payout["payout"][alice.id().as_str()], "95");
+    assert_eq!(payout["payout"][setup.owner.id().as_str()], "5");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_royalties_set_at_mint_replace_the_collection_royalty() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let artist = setup.worker.dev_create_account().await?;
+
+    let too_many: serde_json::Map<String, Value> =
+        (0..7).map(|i| (format!("r{}.{}", i, setup.contract.id()), json!(100))).collect();
+    let outcome = mint_with_args(&setup, &alice, "a0", json!({ "perpetual_royalties": too_many })).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("At most 6 royalty recipients"));
+    let outcome = mint_with_args(&setup, &alice, "a0", json!({ "perpetual_royalties": { artist.id().as_str(): 5001 } })).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("can't exceed 5000 basis points"));
+
+    let royalties = json!({ artist.id().as_str(): 1000, alice.id().as_str(): 500 });
+    let outcome = mint_with_args(&setup, &alice, "a1", json!({ "perpetual_royalties": royalties })).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let stored: Value = view(&setup, "nft_royalties", json!({ "token_id": "a1" })).await?;
+    assert_eq!(stored, royalties);
+
+    // The seller's royalty is added to its share, the collection owner isn't paid
+    let payout: Value = view(&setup, "nft_payout", json!({ "token_id": "a1", "balance": "1000" })).await?;
+    assert_eq!(payout["payout"][artist.id().as_str()], "100");
+    assert_eq!(payout["payout"][alice.id().as_str()], "900");
+    assert_eq!(payout["payout"][setup.owner.id().as_str()], Value::Null);
+
+    let outcome = alice
+        .call(setup.contract.id(), "nft_transfer_payout")
+        .args_json(json!({ "receiver_id": bob.id(), "token_id": "a1", "balance": "1000" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let payout: Value = outcome.json()?;
+    assert_eq!(payout["payout"][artist.id().as_str()], "100");
+    assert_eq!(payout["payout"][alice.id().as_str()], "900");
+
+    // Royalties stay with the token, the new owner collects the remainder
+    let payout: Value = view(&setup, "nft_payout", json!({ "token_id": "a1", "balance": "1000" })).await?;
+    assert_eq!(payout["payout"][alice.id().as_str()], "50");
+    assert_eq!(payout["payout"][bob.id().as_str()], "850");
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_tier_prices_apply_by_mint_index() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let tier_price = NearToken::from_millinear(500).as_yoctonear();
+    let set_tiers = |tiers: serde_json::Value| {
+        setup
+            .owner
+            .call(setup.contract.id(), "set_price_tiers")
+            .args_json(json!({ "price_tiers": tiers }))
+            .transact()
+    };
+    assert!(set_tiers(json!([["2", "1"], ["1", "1"]])).await?.is_failure(), "tiers must ascend");
+    let outcome = set_tiers(json!([["2", tier_price.to_string()]])).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(view::<String>(&setup, "current_mint_price", json!({})).await?, tier_price.to_string());
+
+    // The owner's half of the split comes from the tier price
+    let owner_before = setup.owner.view_account().await?.balance;
+    let outcome = mint_with_deposit(&setup, &alice, "a1", NearToken::from_yoctonear(tier_price + VAULT_DEPOSIT.as_yoctonear())).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let owner_after = setup.owner.view_account().await?.balance;
+    assert_eq!(owner_after.as_yoctonear() - owner_before.as_yoctonear(), tier_price / 2);
+    let quote: serde_json::Value = view(&setup, "burn_quote", json!({ "token_id": "a1" })).await?;
+    assert_eq!(quote["vault_amount"], (tier_price / 2).to_string(), "the vault holds half the tier price");
+    assert!(set_tiers(json!([])).await?.is_failure(), "the schedule is fixed after the first mint");
+
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+    assert_eq!(view::<String>(&setup, "current_mint_price", json!({})).await?, MINT_PRICE.as_yoctonear().to_string());
+    let breakdown: serde_json::Value = view(&setup, "mint_cost_breakdown", json!({ "token_metadata": token_metadata("a3") })).await?;
+    assert_eq!(breakdown["mint_price"], MINT_PRICE.as_yoctonear().to_string());
+    let near_total: u128 = breakdown["near_total"].as_str().unwrap().parse()?;
+    let outcome = mint_with_deposit(&setup, &alice, "a3", NearToken::from_yoctonear(near_total - 1)).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Insufficient price to mint"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dutch_auction_price_falls_to_the_floor() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let start_price = NearToken::from_near(2).as_yoctonear();
+    let floor_price = NearToken::from_millinear(500).as_yoctonear();
+    let hour: u64 = 3_600 * 1_000_000_000;
+    let now = setup.worker.view_block().await?.timestamp();
+    let set_auction = |start_time: u64| {
+        setup
+            .owner
+            .call(setup.contract.id(), "set_dutch_auction")
+            .args_json(json!({ "dutch_auction": {
+                "start_price": start_price.to_string(),
+                "floor_price": floor_price.to_string(),
+                "start_time": start_time.to_string(),
+                "duration": hour.to_string(),
+            }}))
+            .transact()
+    };
+
+    assert!(set_auction(now + hour).await?.is_success());
+    assert_eq!(view::<String>(&setup, "current_mint_price", json!({})).await?, start_price.to_string());
+
+    // Long over: the floor price is charged, the overpayment refunded and the split uses the floor
+    assert!(set_auction(now - 2 * hour).await?.is_success());
+    assert_eq!(view::<String>(&setup, "current_mint_price", json!({})).await?, floor_price.to_string());
+    let owner_before = setup.owner.view_account().await?.balance;
+    let alice_before = alice.view_account().await?.balance;
+    let outcome = mint_with_deposit(&setup, &alice, "a1", NearToken::from_yoctonear(start_price + VAULT_DEPOSIT.as_yoctonear())).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let owner_after = setup.owner.view_account().await?.balance;
+    assert_eq!(owner_after.as_yoctonear() - owner_before.as_yoctonear(), floor_price / 2);
+    let alice_spent = alice_before.as_yoctonear() - alice.view_account().await?.balance.as_yoctonear();
+    assert!(alice_spent < floor_price + VAULT_DEPOSIT.as_yoctonear());
+    let quote: serde_json::Value = view(&setup, "burn_quote", json!({ "token_id": "a1" })).await?;
+    assert_eq!(quote["vault_amount"], (floor_price / 2).to_string(), "the vault holds half the floor price");
+
+    Ok(())
+}
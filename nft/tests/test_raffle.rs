@@ -0,0 +1,81 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use near_workspaces::Account;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_raffle_mints_to_winners_and_refunds_the_rest() -> TestResult {
+    let setup = init(0).await?;
+    let entrants = [
+        setup.worker.dev_create_account().await?,
+        setup.worker.dev_create_account().await?,
+        setup.worker.dev_create_account().await?,
+    ];
+    let now = setup.worker.view_block().await?.timestamp();
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "open_raffle")
+        .args_json(json!({ "ends_at": (now + 20_000_000_000).to_string(), "winners": "2" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let raffle: Value = view(&setup, "raffle", json!({})).await?;
+    let entry_price: u128 = raffle["entry_price"].as_str().unwrap().parse()?;
+    assert!(entry_price > MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear() / 2);
+
+    let enter = |account: &Account, deposit: u128| {
+        account
+            .call(setup.contract.id(), "enter_raffle")
+            .deposit(NearToken::from_yoctonear(deposit))
+            .transact()
+    };
+    assert!(enter(&entrants[0], entry_price / 2).await?.is_failure(), "the entry price is required");
+    let deposit = entry_price + NearToken::from_millinear(10).as_yoctonear();
+    for entrant in entrants.iter() {
+        let outcome = enter(entrant, deposit).await?;
+        assert!(outcome.is_success(), "{:?}", outcome);
+    }
+    assert!(enter(&entrants[0], deposit).await?.is_failure(), "one entry per account");
+
+    let draw = |count: u64| {
+        setup
+            .owner
+            .call(setup.contract.id(), "draw_raffle")
+            .args_json(json!({ "count": count }))
+            .max_gas()
+            .transact()
+    };
+    let outcome = draw(5).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("still taking entries"));
+    setup.worker.fast_forward(100).await?;
+    assert!(enter(&setup.owner, deposit).await?.is_failure(), "entries have closed");
+
+    // Only as many winners as the raffle has, which closes it
+    let outcome = draw(5).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(outcome.json::<Vec<String>>()?.len(), 2);
+    let raffle: Value = view(&setup, "raffle", json!({})).await?;
+    assert_eq!(raffle["closed"], true);
+    let winners: Vec<(String, String)> = view(&setup, "raffle_winners", json!({})).await?;
+    assert_eq!(winners.len(), 2);
+    for (winner, token_id) in winners.iter() {
+        let token: Value = view(&setup, "nft_token", json!({ "token_id": token_id })).await?;
+        assert_eq!(token["owner_id"], winner.as_str());
+    }
+
+    let loser = entrants.iter().find(|entrant| winners.iter().all(|(winner, _)| winner != entrant.id().as_str())).unwrap();
+    let refunds: Vec<(String, String)> = view(&setup, "raffle_refunds", json!({})).await?;
+    assert_eq!(refunds.len(), 1);
+    assert_eq!(refunds[0].0, loser.id().as_str());
+    let winner = entrants.iter().find(|entrant| entrant.id() != loser.id()).unwrap();
+    assert!(winner.call(setup.contract.id(), "claim_raffle_refund").transact().await?.is_failure());
+    let outcome = loser.call(setup.contract.id(), "claim_raffle_refund").transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(outcome.json::<String>()?, refunds[0].1);
+    assert!(loser.call(setup.contract.id(), "claim_raffle_refund").transact().await?.is_failure());
+    assert!(draw(1).await?.is_failure(), "a closed raffle draws no more");
+
+    Ok(())
+}
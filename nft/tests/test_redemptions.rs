@@ -0,0 +1,70 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_physical_redemption_is_tracked_until_settled() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    for token_id in ["a1", "a2"] {
+        assert!(mint(&setup, &alice, token_id).await?.is_success());
+    }
+
+    let redeem = |token_id: &'static str, shipping_ref: String| {
+        alice
+            .call(setup.contract.id(), "redeem_physical")
+            .args_json(json!({ "token_id": token_id, "encrypted_shipping_ref": shipping_ref }))
+            .deposit(NearToken::from_millinear(50))
+            .max_gas()
+            .transact()
+    };
+    assert!(redeem("a1", "x".repeat(2000)).await?.is_failure(), "the reference is bounded");
+    for token_id in ["a1", "a2"] {
+        let outcome = redeem(token_id, "ciphertext".to_string()).await?;
+        assert!(outcome.is_success(), "{:?}", outcome);
+        assert!(touched(&outcome, &vault_id(&setup, token_id)));
+    }
+
+    let pending: Vec<Value> = view(&setup, "redemptions_by_status", json!({ "status": "Pending" })).await?;
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending[0]["ref"], "ciphertext");
+    assert_eq!(pending[0]["redeemer"], alice.id().as_str());
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "fund_treasury")
+        .deposit(NearToken::from_near(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_redemption_consolation")
+        .args_json(json!({ "consolation": NearToken::from_millinear(100) }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+
+    let mark = |token_id: &'static str, status: &'static str| {
+        setup
+            .owner
+            .call(setup.contract.id(), "mark_redemption")
+            .args_json(json!({ "token_id": token_id, "status": status }))
+            .transact()
+    };
+    assert!(mark("a1", "Fulfilled").await?.is_success());
+    assert!(mark("a1", "Rejected").await?.is_failure(), "settled redemptions are final");
+    let before: u128 = view(&setup, "balance_of", json!({ "owner": alice.id() })).await?;
+    assert!(mark("a2", "Rejected").await?.is_success());
+    let after: u128 = view(&setup, "balance_of", json!({ "owner": alice.id() })).await?;
+    assert_eq!(after - before, NearToken::from_millinear(100).as_yoctonear());
+
+    let redemption: Value = view(&setup, "redemption", json!({ "token_id": "a1" })).await?;
+    assert_eq!(redemption["status"], "Fulfilled");
+    let pending: Vec<Value> = view(&setup, "redemptions_by_status", json!({ "status": "Pending" })).await?;
+    assert!(pending.is_empty());
+
+    Ok(())
+}
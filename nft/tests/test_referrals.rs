@@ -0,0 +1,43 @@
+mod common;
+
+use common::*;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_referrer_gets_its_share_of_the_owner_amount() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let referrer = setup.worker.dev_create_account().await?;
+    let set_bps = |bps: u32| {
+        setup
+            .owner
+            .call(setup.contract.id(), "set_referral_bps")
+            .args_json(json!({ "referral_bps": bps }))
+            .transact()
+    };
+    assert!(set_bps(2_001).await?.is_failure(), "capped at 2000 bps");
+    assert!(set_bps(1_000).await?.is_success());
+
+    let outcome = mint_with_args(&setup, &alice, "a0", json!({ "referrer": alice.id() })).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Can't refer yourself"));
+    let outcome = mint_with_args(&setup, &alice, "a0", json!({ "referrer": setup.contract.id() })).await?;
+    assert!(outcome.is_failure(), "the contract can't be a referrer");
+
+    let owner_before = setup.owner.view_account().await?.balance;
+    let referrer_before = referrer.view_account().await?.balance;
+    let outcome = mint_with_args(&setup, &alice, "a1", json!({ "referrer": referrer.id() })).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    // 10% of the owner's half of the price
+    let owner_amount = MINT_PRICE.as_yoctonear() / 2;
+    let share = owner_amount / 10;
+    assert!(outcome.logs().iter().any(|log| {
+        log.contains("\"event\":\"referral\"") && log.contains(&format!("\"amount\":\"{}\"", share))
+    }));
+    let referrer_after = referrer.view_account().await?.balance;
+    assert_eq!(referrer_after.as_yoctonear() - referrer_before.as_yoctonear(), share);
+    let owner_after = setup.owner.view_account().await?.balance;
+    assert_eq!(owner_after.as_yoctonear() - owner_before.as_yoctonear(), owner_amount - share);
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_reserved_token_ids_can_only_be_minted_by_the_reserver() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let reserve = |account: &near_workspaces::Account, token_id: &str, deposit: NearToken| {
+        account
+            .call(setup.contract.id(), "reserve_token_id")
+            .args_json(json!({ "token_id": token_id }))
+            .deposit(deposit)
+            .transact()
+    };
+
+    let outcome = reserve(&alice, "7", NearToken::from_millinear(10)).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("to reserve a token id"));
+    let outcome = reserve(&alice, "7", NearToken::from_near(1)).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let reservation: Value = view(&setup, "reservation_of", json!({ "token_id": "7" })).await?;
+    assert_eq!(reservation["account_id"], alice.id().to_string());
+    assert!(reserve(&bob, "7", NearToken::from_near(1)).await?.is_failure(), "already reserved");
+
+    let outcome = mint(&setup, &bob, "7").await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Token id 7 is reserved"));
+    let balance = alice.view_account().await?.balance;
+    let outcome = mint(&setup, &alice, "7").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(view::<Value>(&setup, "reservation_of", json!({ "token_id": "7" })).await?, Value::Null);
+    let spent = balance.as_yoctonear() - alice.view_account().await?.balance.as_yoctonear();
+    assert!(
+        spent < MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear(),
+        "the reservation deposit is refunded on mint"
+    );
+
+    assert!(reserve(&bob, "8", NearToken::from_near(1)).await?.is_success());
+    let cancel = |account: &near_workspaces::Account| {
+        account
+            .call(setup.contract.id(), "cancel_reservation")
+            .args_json(json!({ "token_id": "8" }))
+            .transact()
+    };
+    assert!(cancel(&alice).await?.is_failure(), "only the reserver can cancel");
+    let outcome = cancel(&bob).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(mint(&setup, &alice, "8").await?.is_success());
+
+    Ok(())
+}
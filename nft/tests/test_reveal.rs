@@ -0,0 +1,64 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_tokens_show_the_placeholder_until_the_reveal() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_placeholder_metadata")
+        .args_json(json!({ "placeholder_metadata": { "title": "Mystery", "media": "https://example.com/box.png" } }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(!view::<bool>(&setup, "is_revealed", json!({})).await?);
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+
+    let token: Value = view(&setup, "nft_token", json!({ "token_id": "a1" })).await?;
+    assert_eq!(token["metadata"]["title"], "Mystery");
+    let tokens: Vec<Value> = view(&setup, "nft_tokens", json!({})).await?;
+    assert!(tokens.iter().all(|token| token["metadata"]["title"] == "Mystery"));
+    let owned: Vec<Value> = view(&setup, "nft_tokens_for_owner", json!({ "account_id": alice.id() })).await?;
+    assert!(owned.iter().all(|token| token["metadata"]["title"] == "Mystery"));
+
+    assert!(alice.call(setup.contract.id(), "reveal").transact().await?.is_failure());
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "reveal_range")
+        .args_json(json!({ "from": "0", "to": "2" }))
+        .transact()
+        .await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("isn't revealed yet"));
+
+    let outcome = setup.owner.call(setup.contract.id(), "reveal").transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let update = outcome.logs().into_iter().find(|log| log.contains("nft_metadata_update")).unwrap();
+    assert!(update.contains("\"a1\"") && update.contains("\"a2\""), "{}", update);
+    let token: Value = view(&setup, "nft_token", json!({ "token_id": "a1" })).await?;
+    assert_eq!(token["metadata"]["title"], "a1");
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "reveal_range")
+        .args_json(json!({ "from": "1", "to": "2" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(outcome.logs().iter().any(|log| log.contains("nft_metadata_update") && log.contains("\"a2\"")));
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_placeholder_metadata")
+        .args_json(json!({ "placeholder_metadata": { "title": "Again" } }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "can't hide a revealed collection again");
+
+    Ok(())
+}
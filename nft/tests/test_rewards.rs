@@ -0,0 +1,345 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_claim_delegate_withdraws_to_the_holder() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let delegate = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+    burn(&setup, &bob, "b1").await?;
+
+    let reward: u128 = view(&setup, "balance_of", json!({ "owner": alice.id() })).await?;
+    assert!(reward > 0);
+
+    let outcome = delegate
+        .call(setup.contract.id(), "withdraw")
+        .args_json(json!({ "on_behalf_of": alice.id() }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "no delegation was set");
+
+    let outcome = alice
+        .call(setup.contract.id(), "set_claim_delegate")
+        .args_json(json!({ "delegate": delegate.id() }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    assert_eq!(
+        view::<Option<String>>(&setup, "claim_delegate_of", json!({ "account_id": alice.id() })).await?,
+        Some(delegate.id().to_string())
+    );
+
+    let alice_before = alice.view_account().await?.balance;
+    let outcome = delegate
+        .call(setup.contract.id(), "withdraw")
+        .args_json(json!({ "on_behalf_of": alice.id() }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let alice_after = alice.view_account().await?.balance;
+    assert_eq!(alice_after.as_yoctonear() - alice_before.as_yoctonear(), reward);
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "owner": alice.id() })).await?, 0);
+
+    let outcome = alice
+        .call(setup.contract.id(), "clear_claim_delegate")
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    assert_eq!(view::<Option<String>>(&setup, "claim_delegate_of", json!({ "account_id": alice.id() })).await?, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rewards_accrue_without_visiting_the_holders() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let carol = setup.worker.dev_create_account().await?;
+    for (account, token_id) in [(&alice, "a1"), (&alice, "a2"), (&bob, "b1"), (&carol, "c1")] {
+        assert!(mint(&setup, account, token_id).await?.is_success());
+    }
+    assert_eq!(view::<String>(&setup, "total_reward_weight", json!({})).await?, "30000");
+
+    // Bob and carol split alice's burn fee, the burner earns nothing from it
+    burn(&setup, &alice, "a1").await?;
+    // Half of the 10% burn fee on the vault's half of the price goes to the holders
+    let fee = MINT_PRICE.as_yoctonear() / 2 / 10 / 2;
+    let claimable = |account: &near_workspaces::Account| {
+        view::<String>(&setup, "claimable_of", json!({ "account_id": account.id() }))
+    };
+    assert_eq!(claimable(&alice).await?, "0");
+    assert_eq!(claimable(&bob).await?, (fee / 2).to_string());
+    assert_eq!(claimable(&carol).await?, (fee / 2).to_string());
+
+    // Leaving the holders keeps what accrued so far, but nothing from later burns
+    let outcome = carol
+        .call(setup.contract.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": bob.id(), "token_id": "c1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(view::<String>(&setup, "total_reward_weight", json!({})).await?, "20000");
+    burn(&setup, &alice, "a2").await?;
+    assert_eq!(claimable(&bob).await?, (fee / 2 + fee).to_string());
+    assert_eq!(claimable(&carol).await?, (fee / 2).to_string());
+
+    let carol_before = carol.view_account().await?.balance;
+    let outcome = carol.call(setup.contract.id(), "withdraw").args_json(json!({})).max_gas().transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(carol.view_account().await?.balance > carol_before);
+    assert_eq!(claimable(&carol).await?, "0");
+    assert!(view::<Vec<String>>(&setup, "check_invariants", json!({})).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reward_dust_goes_to_the_treasury() -> TestResult {
+    // Half of the 10% burn fee on the vault's half of the price goes to the holders
+    let pool = MINT_PRICE.as_yoctonear() / 2 / 10 / 2;
+    for holder_count in [7, 13] {
+        let setup = init(0).await?;
+        let alice = setup.worker.dev_create_account().await?;
+        assert!(mint(&setup, &alice, "a1").await?.is_success());
+        let mut holders = Vec::new();
+        for i in 0..holder_count {
+            let holder = setup.worker.dev_create_account().await?;
+            assert!(mint(&setup, &holder, &format!("h{}", i)).await?.is_success());
+            holders.push(holder);
+        }
+
+        let outcome = alice
+            .call(setup.contract.id(), "burn")
+            .args_json(json!({ "token_id": "a1" }))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await?;
+        assert!(outcome.is_success(), "{:?}", outcome);
+        let mut distributed = 0;
+        for holder in holders.iter() {
+            distributed += view::<String>(&setup, "claimable_of", json!({ "account_id": holder.id() })).await?.parse::<u128>()?;
+        }
+        // The treasury doesn't share in rewards, its dust is still withdrawable
+        assert_eq!(view::<String>(&setup, "claimable_of", json!({ "account_id": setup.treasury.id() })).await?, "0");
+        let dust: u128 = view(&setup, "balance_of", json!({ "owner": setup.treasury.id() })).await?;
+        // Every holder weighs 10000, and the dust is what is left of whole yoctos per unit of weight
+        assert!(dust > 0 && dust < holder_count as u128 * 10_000, "{}", dust);
+        assert_eq!(distributed + dust, pool, "{} holders", holder_count);
+    }
+
+    Ok(())
+}
+
+/// Claimable rewards of alice, bob and the treasury after alice, minted `alice_tokens` tokens
+/// while bob minted `bob_tokens`, burns one of hers.
+async fn rewards_after_burn(alice_tokens: usize, bob_tokens: usize) -> TestResult<[u128; 3]> {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    for i in 0..alice_tokens {
+        assert!(mint(&setup, &alice, &format!("a{}", i)).await?.is_success());
+    }
+    for i in 0..bob_tokens {
+        assert!(mint(&setup, &bob, &format!("b{}", i)).await?.is_success());
+    }
+    burn(&setup, &alice, "a0").await?;
+    let mut claimable = [0; 3];
+    for (i, account_id) in [alice.id(), bob.id(), setup.treasury.id()].into_iter().enumerate() {
+        claimable[i] = view(&setup, "balance_of", json!({ "owner": account_id })).await?;
+    }
+    Ok(claimable)
+}
+
+#[tokio::test]
+async fn test_burners_never_share_their_own_burn_fee() -> TestResult {
+    let pool = MINT_PRICE.as_yoctonear() / 2 / 10 / 2;
+
+    // Nobody else holds a token, so the whole share goes to the treasury instead of staying behind
+    assert_eq!(rewards_after_burn(1, 0).await?, [0, 0, pool]);
+    assert_eq!(rewards_after_burn(2, 0).await?, [0, 0, pool]);
+    // Alice still holds a token after burning, and is excluded all the same
+    assert_eq!(rewards_after_burn(2, 1).await?, [0, pool, 0]);
+    assert_eq!(rewards_after_burn(1, 1).await?, [0, pool, 0]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_per_token_mode_weighs_holders_by_tokens_held() -> TestResult {
+    let pool = MINT_PRICE.as_yoctonear() / 2 / 10 / 2;
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let carol = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+    assert!(mint(&setup, &bob, "b2").await?.is_success());
+    assert!(mint(&setup, &carol, "c1").await?.is_success());
+    assert_eq!(view::<String>(&setup, "total_reward_weight", json!({})).await?, "30000");
+
+    let outcome = alice
+        .call(setup.contract.id(), "set_reward_mode")
+        .args_json(json!({ "reward_mode": "PerToken" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_reward_mode")
+        .args_json(json!({ "reward_mode": "PerToken" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let event = outcome.logs().iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|event| serde_json::from_str::<serde_json::Value>(event).unwrap())
+        .find(|event| event["event"] == "reward_mode_changed")
+        .unwrap();
+    assert_eq!(event["data"]["reward_mode"], "PerToken");
+    assert_eq!(view::<String>(&setup, "reward_mode", json!({})).await?, "PerToken");
+
+    // Holders keep their weight until they are next settled
+    assert_eq!(view::<String>(&setup, "total_reward_weight", json!({})).await?, "30000");
+    let outcome = carol
+        .call(setup.contract.id(), "update_reward_weight")
+        .args_json(json!({ "account_id": bob.id() }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(outcome.json::<String>()?, "20000");
+    assert_eq!(view::<String>(&setup, "total_reward_weight", json!({})).await?, "40000");
+
+    burn(&setup, &alice, "a1").await?;
+    let claimable = |account: &near_workspaces::Account| {
+        view::<String>(&setup, "claimable_of", json!({ "account_id": account.id() }))
+    };
+    let bob_reward: u128 = claimable(&bob).await?.parse::<u128>()?;
+    let carol_reward: u128 = claimable(&carol).await?.parse::<u128>()?;
+    assert_eq!(bob_reward, 2 * carol_reward);
+    assert_eq!(bob_reward + carol_reward, pool / 30_000 * 30_000);
+
+    // Handing a token over moves its weight along with it
+    let outcome = bob
+        .call(setup.contract.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": carol.id(), "token_id": "b2" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(view::<String>(&setup, "total_reward_weight", json!({})).await?, "30000");
+    assert_eq!(claimable(&bob).await?.parse::<u128>()?, bob_reward);
+    assert_eq!(claimable(&carol).await?.parse::<u128>()?, carol_reward);
+    assert!(view::<Vec<String>>(&setup, "check_invariants", json!({})).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_treasury_and_owner_are_excluded_from_rewards() -> TestResult {
+    let pool = MINT_PRICE.as_yoctonear() / 2 / 10 / 2;
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &setup.owner, "o1").await?.is_success());
+    assert!(mint(&setup, &setup.treasury, "t1").await?.is_success());
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+    assert_eq!(view::<String>(&setup, "total_reward_weight", json!({})).await?, "20000");
+
+    // Bob is the only holder left to share the fee
+    burn(&setup, &alice, "a1").await?;
+    let claimable = |account: &near_workspaces::Account| {
+        view::<String>(&setup, "claimable_of", json!({ "account_id": account.id() }))
+    };
+    assert_eq!(claimable(&bob).await?, pool.to_string());
+    assert_eq!(claimable(&setup.owner).await?, "0");
+    assert_eq!(claimable(&setup.treasury).await?, "0");
+
+    let outcome = alice
+        .call(setup.contract.id(), "exclude_from_rewards")
+        .args_json(json!({ "account_id": bob.id() }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "exclude_from_rewards")
+        .args_json(json!({ "account_id": bob.id() }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(view::<String>(&setup, "total_reward_weight", json!({})).await?, "0");
+    assert_eq!(claimable(&bob).await?, "0");
+    // What bob accrued before can still be withdrawn
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "owner": bob.id() })).await?, pool);
+    let bob_before = bob.view_account().await?.balance;
+    let outcome = bob.call(setup.contract.id(), "withdraw").args_json(json!({})).transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(bob.view_account().await?.balance > bob_before);
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "owner": bob.id() })).await?, 0);
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "include_in_rewards")
+        .args_json(json!({ "account_id": setup.owner.id() }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(view::<String>(&setup, "total_reward_weight", json!({})).await?, "10000");
+    let excluded: Vec<String> = view(&setup, "excluded_from_rewards", json!({})).await?;
+    assert!(excluded.contains(&setup.treasury.id().to_string()) && excluded.contains(&bob.id().to_string()));
+    assert!(!excluded.contains(&setup.owner.id().to_string()));
+    let page: Vec<String> = view(&setup, "excluded_from_rewards", json!({ "from_index": "1", "limit": 1 })).await?;
+    assert_eq!(page, excluded[1..2]);
+    assert!(view::<Vec<String>>(&setup, "check_invariants", json!({})).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_withdrawals_keep_the_rewards() -> TestResult {
+    let (setup, ft, ft_owner) = init_ft_collection(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(ft_mint(&setup, &ft, &ft_owner, &alice, "a1").await?.is_success());
+    assert!(ft_mint(&setup, &ft, &ft_owner, &bob, "b1").await?.is_success());
+    burn(&setup, &bob, "b1").await?;
+    let rewards: u128 = view(&setup, "balance_of", json!({ "owner": alice.id() })).await?;
+    assert!(rewards > 0);
+
+    // Alice spent all her FT on the mint, so she can leave the FT and no longer receive it
+    let outcome = alice
+        .call(ft.id(), "storage_unregister")
+        .args_json(json!({ "force": false }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let withdraw = || alice.call(setup.contract.id(), "withdraw").args_json(json!({})).max_gas().transact();
+    let outcome = withdraw().await?;
+    assert!(outcome.logs().iter().any(|log| log.contains("the rewards were credited back")), "{:?}", outcome);
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "owner": alice.id() })).await?, rewards);
+    assert!(view::<Vec<String>>(&setup, "check_invariants", json!({})).await?.is_empty());
+
+    ft_storage_deposit(&ft, &ft_owner, alice.id().as_str()).await?;
+    assert!(withdraw().await?.is_success());
+    assert_eq!(ft_balance_of(&ft, alice.id().as_str()).await?, rewards);
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "owner": alice.id() })).await?, 0);
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_presale_and_closed_phases_gate_minting() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let set_phase = |phase: &str| {
+        setup
+            .owner
+            .call(setup.contract.id(), "set_sale_phase")
+            .args_json(json!({ "sale_phase": phase }))
+            .transact()
+    };
+    assert!(alice
+        .call(setup.contract.id(), "set_sale_phase")
+        .args_json(json!({ "sale_phase": "Closed" }))
+        .transact()
+        .await?
+        .is_failure());
+
+    assert!(set_phase("Presale").await?.is_success());
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "add_to_allowlist")
+        .args_json(json!({ "accounts": [alice.id(), bob.id()] }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "remove_from_allowlist")
+        .args_json(json!({ "accounts": [bob.id()] }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let allowlist: Vec<String> = view(&setup, "get_allowlist", json!({ "from_index": "0", "limit": 10 })).await?;
+    assert_eq!(allowlist, vec![alice.id().to_string()]);
+
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    let outcome = mint(&setup, &bob, "b1").await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Only allowlisted accounts"));
+
+    assert!(set_phase("Closed").await?.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_failure(), "closed to everyone but the owner");
+    assert!(mint(&setup, &setup.owner, "o1").await?.is_success());
+
+    assert!(set_phase("Public").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pausing_stops_mints_but_not_transfers_or_burns() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+
+    assert!(alice.call(setup.contract.id(), "pause_minting").transact().await?.is_failure());
+    let outcome = setup.owner.call(setup.contract.id(), "pause_minting").transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(view::<bool>(&setup, "is_mint_paused", json!({})).await?);
+
+    let outcome = mint(&setup, &alice, "a3").await?;
+    assert!(!touched(&outcome, &vault_id(&setup, "a3")));
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Minting is paused"));
+    assert_eq!(view::<u128>(&setup, "index", json!({})).await?, 2);
+
+    let outcome = alice
+        .call(setup.contract.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": bob.id(), "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    burn(&setup, &alice, "a2").await?;
+
+    let outcome = setup.owner.call(setup.contract.id(), "resume_minting").transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(mint(&setup, &alice, "a3").await?.is_success());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mints_only_open_inside_the_sale_window() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let set_window = |sale_start: Option<u64>, sale_end: Option<u64>| {
+        setup
+            .owner
+            .call(setup.contract.id(), "set_sale_window")
+            .args_json(json!({
+                "sale_start": sale_start.map(|start| start.to_string()),
+                "sale_end": sale_end.map(|end| end.to_string()),
+            }))
+            .transact()
+    };
+    let now = setup.worker.view_block().await?.timestamp();
+    let hour = 3_600 * 1_000_000_000;
+    assert!(set_window(Some(now + hour), Some(now)).await?.is_failure(), "must end after it starts");
+
+    assert!(set_window(Some(now + hour), None).await?.is_success());
+    let window: Value = view(&setup, "sale_window", json!({})).await?;
+    assert_eq!(window["sale_start"], (now + hour).to_string());
+    assert_eq!(window["sale_end"], Value::Null);
+    let outcome = mint(&setup, &alice, "a1").await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("The sale has not started"));
+    assert!(mint(&setup, &setup.owner, "o1").await?.is_success(), "the owner can mint before the sale");
+
+    assert!(set_window(None, Some(now)).await?.is_success());
+    let outcome = mint(&setup, &alice, "a1").await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("The sale has ended"));
+
+    assert!(set_window(Some(now), Some(now + hour)).await?.is_success());
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_presale_tiers_set_price_and_quota() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let og_price = MINT_PRICE.as_yoctonear() / 2;
+    let set_tier = |accounts: Vec<&str>, tier: Value| {
+        setup
+            .owner
+            .call(setup.contract.id(), "set_tier")
+            .args_json(json!({ "accounts": accounts, "tier": tier }))
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+    };
+    let outcome = setup.owner.call(setup.contract.id(), "set_sale_phase").args_json(json!({ "sale_phase": "Presale" })).transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let outcome = set_tier(vec![alice.id().as_str()], json!({ "price": og_price.to_string(), "quota": "2" })).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let tier: Value = view(&setup, "tier_of", json!({ "account_id": alice.id() })).await?;
+    assert_eq!(tier["quota"], "2");
+    assert_eq!(view::<String>(&setup, "remaining_quota", json!({ "account_id": alice.id() })).await?, "2");
+
+    // A tier lets the account into the presale at the tier's price
+    let outcome = mint(&setup, &alice, "a1").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let paid = outcome.logs().into_iter().find(|log| log.contains("\"event\":\"mint_paid\"")).unwrap();
+    assert!(paid.contains(&format!("\"price\":\"{}\"", og_price)), "{}", paid);
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+    assert_eq!(view::<String>(&setup, "remaining_quota", json!({ "account_id": alice.id() })).await?, "0");
+    let outcome = mint(&setup, &alice, "a3").await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Tier quota of 2 mints used up"));
+
+    assert!(mint(&setup, &bob, "b1").await?.is_failure(), "no tier, not allowlisted");
+    assert!(set_tier(vec![alice.id().as_str()], Value::Null).await?.is_success());
+    assert_eq!(view::<Option<Value>>(&setup, "tier_of", json!({ "account_id": alice.id() })).await?, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_allowlist_pages_are_capped_at_the_max_limit() -> TestResult {
+    let setup = init(0).await?;
+    let accounts: Vec<String> = (0..201).map(|i| format!("buyer-{}.test.near", i)).collect();
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "add_to_allowlist")
+        .args_json(json!({ "accounts": accounts }))
+        .deposit(NearToken::from_near(3))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let page = |args: Value| view::<Vec<String>>(&setup, "get_allowlist", args);
+    assert_eq!(page(json!({})).await?.len(), 50);
+    assert_eq!(page(json!({ "limit": 200 })).await?.len(), 200);
+    // A larger limit is truncated to the max rather than rejected
+    assert_eq!(page(json!({ "limit": 201 })).await?.len(), 200);
+    assert_eq!(page(json!({ "from_index": "200", "limit": 200 })).await?.len(), 1);
+    assert!(page(json!({ "from_index": "201" })).await?.is_empty());
+    assert!(page(json!({ "from_index": "1000" })).await?.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_series_mint_with_their_own_price_and_supply() -> TestResult {
+    let setup = init(1).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    let series_id: u32 = setup
+        .owner
+        .call(setup.contract.id(), "create_collection")
+        .args_json(json!({
+            "config": {
+                "name": "Drop 2",
+                "mint_price": NearToken::from_near(2),
+                "payment_split_percent": "50",
+                "total_supply": "2",
+            }
+        }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?
+        .json()?;
+    assert_eq!(series_id, 1);
+
+    let mint_series = |deposit: NearToken| {
+        alice
+            .call(setup.contract.id(), "nft_mint")
+            .args_json(json!({
+                "token_owner_id": alice.id(),
+                "token_metadata": token_metadata("series"),
+                "series_id": series_id,
+            }))
+            .deposit(deposit)
+            .max_gas()
+            .transact()
+    };
+    let series_price = NearToken::from_yoctonear(NearToken::from_near(2).as_yoctonear() + VAULT_DEPOSIT.as_yoctonear());
+    assert!(mint_series(NearToken::from_yoctonear(MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear())).await?.is_failure(), "the series price applies");
+    let mut minted = vec![];
+    for _ in 0..2 {
+        let outcome = mint_series(series_price).await?;
+        assert!(outcome.is_success(), "{:?}", outcome);
+        let token: Value = outcome.json()?;
+        minted.push(token["token_id"].as_str().unwrap().to_string());
+    }
+    assert_eq!(minted, vec!["1-1", "1-2"]);
+    assert!(mint_series(series_price).await?.is_failure(), "the series is sold out");
+
+    // The series doesn't use up the collection's own supply
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_failure());
+
+    let stats: Value = view(&setup, "series_stats", json!({ "series_id": series_id })).await?;
+    assert_eq!(stats["minted"], "2");
+    assert_eq!(stats["name"], "Drop 2");
+    let stats: Value = view(&setup, "series_stats", json!({ "series_id": 0 })).await?;
+    assert_eq!(stats["minted"], "1");
+    let violations: Vec<String> = view(&setup, "check_invariants", json!({})).await?;
+    assert!(violations.is_empty(), "{:?}", violations);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_series_token_ids_cant_be_taken_first() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    // Ids of the `{series_id}-{n}` form are refused before the series even exists
+    let outcome = mint(&setup, &alice, "1-1").await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("reserved for series tokens"));
+    let outcome = alice
+        .call(setup.contract.id(), "reserve_token_id")
+        .args_json(json!({ "token_id": "1-2" }))
+        .deposit(NearToken::from_near(1))
+        .transact()
+        .await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("reserved for series tokens"));
+    // Other ids with a dash are still free to pick
+    assert!(mint(&setup, &alice, "gold-1").await?.is_success());
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "create_collection")
+        .args_json(json!({
+            "config": {
+                "name": "Drop 2",
+                "mint_price": MINT_PRICE,
+                "payment_split_percent": "50",
+                "total_supply": "0",
+            }
+        }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    for expected in ["1-1", "1-2"] {
+        let outcome = mint_with_args(&setup, &alice, "series", json!({ "token_id": null, "series_id": 1 })).await?;
+        assert!(outcome.is_success(), "{:?}", outcome);
+        assert_eq!(outcome.json::<Value>()?["token_id"], expected);
+    }
+
+    Ok(())
+}
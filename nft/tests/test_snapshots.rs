@@ -0,0 +1,59 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_snapshot_records_holders_and_can_be_pruned() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+
+    let outcome = alice
+        .call(setup.contract.id(), "take_snapshot")
+        .args_json(json!({ "label": "airdrop" }))
+        .deposit(NearToken::from_millinear(100))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "only the owner can take snapshots");
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "take_snapshot")
+        .args_json(json!({ "label": "airdrop" }))
+        .deposit(NearToken::from_millinear(100))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let snapshot_id: u64 = outcome.json()?;
+
+    let snapshot: Value = view(&setup, "get_snapshot", json!({ "snapshot_id": snapshot_id })).await?;
+    assert_eq!(snapshot["finalized"], true);
+    assert_eq!(snapshot["holder_count"], 2);
+    let entries = snapshot["entries"].as_array().unwrap();
+    let alice_entry = entries.iter().find(|entry| entry["account_id"] == alice.id().as_str()).unwrap();
+    assert_eq!(alice_entry["token_count"], 2);
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "continue_snapshot")
+        .args_json(json!({ "snapshot_id": snapshot_id }))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "finalized snapshots are immutable");
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "prune_snapshot")
+        .args_json(json!({ "snapshot_id": snapshot_id }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    assert!(view::<Option<Value>>(&setup, "get_snapshot", json!({ "snapshot_id": snapshot_id })).await?.is_none());
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_staked_tokens_earn_a_boosted_share() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let carol = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+    assert!(mint(&setup, &carol, "c1").await?.is_success());
+
+    let outcome = alice
+        .call(setup.contract.id(), "stake")
+        .args_json(json!({ "token_id": "a1" }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(view::<u64>(&setup, "total_staked", json!({})).await?, 1);
+    assert_eq!(
+        view::<Vec<String>>(&setup, "staked_tokens_of", json!({ "account_id": alice.id() })).await?,
+        vec!["a1".to_string()]
+    );
+
+    let outcome = alice
+        .call(setup.contract.id(), "nft_transfer")
+        .args_json(json!({ "receiver_id": bob.id(), "token_id": "a1" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "staked tokens are transfer-locked");
+
+    burn(&setup, &carol, "c1").await?;
+    let staked_reward: u128 = view(&setup, "balance_of", json!({ "owner": alice.id() })).await?;
+    let unstaked_reward: u128 = view(&setup, "balance_of", json!({ "owner": bob.id() })).await?;
+    assert!(unstaked_reward > 0);
+    assert_eq!(staked_reward, 2 * unstaked_reward);
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_unstake_cooldown")
+        .args_json(json!({ "unstake_cooldown": "3600000000000" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+    let released: bool = alice
+        .call(setup.contract.id(), "unstake")
+        .args_json(json!({ "token_id": "a1" }))
+        .transact()
+        .await?
+        .json()?;
+    assert!(!released, "the first unstake starts the cooldown");
+    let outcome = alice
+        .call(setup.contract.id(), "unstake")
+        .args_json(json!({ "token_id": "a1" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "the token is still cooling down");
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::*;
+use near_workspaces::Account;
+use serde_json::{json, Value};
+
+async fn mint_auto(setup: &Setup, minter: &Account) -> TestResult<String> {
+    let outcome = mint_with_args(setup, minter, "", json!({ "token_id": null })).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let token: Value = outcome.clone().json()?;
+    let token_id = token["token_id"].as_str().unwrap().to_string();
+    assert!(touched(&outcome, &vault_id(setup, &token_id)));
+    Ok(token_id)
+}
+
+#[tokio::test]
+async fn test_auto_ids_skip_explicit_ids() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    assert_eq!(mint_auto(&setup, &alice).await?, "0");
+    assert!(mint(&setup, &alice, "2").await?.is_success());
+
+    // The index is now 2, which was taken explicitly, so the next free number is used
+    assert_eq!(mint_auto(&setup, &alice).await?, "3");
+    assert_eq!(mint_auto(&setup, &alice).await?, "4");
+
+    let outcome = mint(&setup, &alice, "4").await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Token 4 already exists"));
+    assert_eq!(view::<String>(&setup, "nft_total_supply", json!({})).await?, "4");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_token_ids_must_make_valid_vault_accounts() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    let long_id = "a".repeat(60);
+    for (token_id, error) in [
+        ("Token#1", "is not a valid account name part"),
+        ("has.dot", "can't contain '.'"),
+        ("", "can't be empty"),
+        (long_id.as_str(), "longer than 64 characters"),
+    ] {
+        let outcome = mint(&setup, &alice, token_id).await?;
+        assert!(!touched(&outcome, &vault_id(&setup, token_id)));
+        assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains(error), "{}", token_id);
+    }
+    assert_eq!(view::<u128>(&setup, "index", json!({})).await?, 0);
+    assert!(mint(&setup, &alice, "token_1").await?.is_success());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ids_are_drawn_from_the_pool_until_it_runs_out() -> TestResult {
+    let setup = init(3).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let load = |ids: Vec<&str>| {
+        setup
+            .owner
+            .call(setup.contract.id(), "load_id_pool")
+            .args_json(json!({ "ids": ids }))
+            .deposit(near_workspaces::types::NearToken::from_millinear(10))
+            .transact()
+    };
+    assert!(load(vec!["rare", "common", "plain", "extra"]).await?.is_failure(), "more ids than supply");
+    assert!(load(vec!["rare", "common"]).await?.is_success());
+    assert!(load(vec!["plain"]).await?.is_success());
+    assert!(load(vec!["more"]).await?.is_failure(), "the pool already fills the supply");
+    assert_eq!(view::<String>(&setup, "remaining_pool", json!({})).await?, "3");
+
+    let outcome = mint(&setup, &alice, "rare").await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Token ids are drawn from the pool"));
+
+    let mut drawn = vec![mint_auto(&setup, &alice).await?, mint_auto(&setup, &alice).await?, mint_auto(&setup, &alice).await?];
+    drawn.sort();
+    assert_eq!(drawn, vec!["common", "plain", "rare"]);
+    assert_eq!(view::<String>(&setup, "remaining_pool", json!({})).await?, "0");
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use near_workspaces::Account;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+#[tokio::test]
+async fn test_writers_set_bounded_token_values() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let game = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    let set = |writer: &Account, key: &str, value: Value| {
+        writer
+            .call(setup.contract.id(), "set_token_kv")
+            .args_json(json!({ "token_id": "a1", "key": key, "value": value }))
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+    };
+    assert!(set(&game, "equipped", json!("true")).await?.is_failure(), "only writers can write");
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "add_kv_writer")
+        .args_json(json!({ "account_id": game.id() }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let outcome = set(&game, "equipped", json!("true")).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(outcome.logs().iter().any(|log| log.contains("\"event\":\"token_kv_changed\"")));
+    let kv: HashMap<String, String> = view(&setup, "token_kv", json!({ "token_id": "a1" })).await?;
+    assert_eq!(kv.get("equipped").map(String::as_str), Some("true"));
+
+    assert!(set(&game, "bio", json!("x".repeat(129))).await?.is_failure(), "values are at most 128 bytes");
+    for i in 1..8 {
+        assert!(set(&game, &format!("slot-{}", i), json!("1")).await?.is_success());
+    }
+    assert!(set(&game, "slot-8", json!("1")).await?.is_failure(), "at most 8 keys");
+
+    let outcome = game
+        .call(setup.contract.id(), "set_token_kv")
+        .args_json(json!({ "token_id": "a1", "key": "equipped", "value": null }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(view::<HashMap<String, String>>(&setup, "token_kv", json!({ "token_id": "a1" })).await?.len(), 7);
+
+    burn(&setup, &alice, "a1").await?;
+    assert!(view::<HashMap<String, String>>(&setup, "token_kv", json!({ "token_id": "a1" })).await?.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_attributes_are_validated_against_the_schema() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_trait_schema")
+        .args_json(json!({ "schema": [
+            { "trait_type": "background", "allowed_values": ["red", "blue"] },
+            { "trait_type": "eyes" },
+        ]}))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    for attributes in [
+        json!([{ "trait_type": "background", "value": "green" }]),
+        json!([{ "trait_type": "hat", "value": "cap" }]),
+        json!([{ "trait_type": "eyes", "value": "big" }, { "trait_type": "eyes", "value": "small" }]),
+    ] {
+        let outcome = mint_with_args(&setup, &alice, "bad", json!({ "attributes": attributes })).await?;
+        assert!(outcome.is_failure(), "{} should be rejected", attributes);
+    }
+
+    let attributes = json!([
+        { "trait_type": "background", "value": "red" },
+        { "trait_type": "eyes", "value": "laser" },
+    ]);
+    let outcome = mint_with_args(&setup, &alice, "good", json!({ "attributes": attributes })).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(view::<Value>(&setup, "nft_attributes", json!({ "token_id": "good" })).await?, attributes);
+    assert_eq!(view::<Vec<Value>>(&setup, "get_trait_schema", json!({})).await?.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rarity_scores_follow_burns() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    for (token_id, background) in [("r1", "red"), ("r2", "red"), ("b1", "blue")] {
+        let attributes = json!([{ "trait_type": "background", "value": background }]);
+        let outcome = mint_with_args(&setup, &alice, token_id, json!({ "attributes": attributes })).await?;
+        assert!(outcome.is_success(), "{:?}", outcome);
+    }
+
+    let score = |value: Value| value["score"].as_str().unwrap().parse::<u128>().unwrap();
+    assert_eq!(score(view(&setup, "rarity_score", json!({ "token_id": "r1" })).await?), 1_500_000);
+    assert_eq!(score(view(&setup, "rarity_score", json!({ "token_id": "b1" })).await?), 3_000_000);
+
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "rebuild_rarity_index")
+        .args_json(json!({}))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let ranking: Vec<Value> = view(&setup, "rarity_ranking", json!({})).await?;
+    assert_eq!(ranking[0]["token_id"], "b1");
+    assert_eq!(ranking.len(), 3);
+
+    let outcome = alice
+        .call(setup.contract.id(), "burn")
+        .args_json(json!({ "token_id": "r2" }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(score(view(&setup, "rarity_score", json!({ "token_id": "r1" })).await?), 2_000_000);
+    assert_eq!(score(view(&setup, "rarity_score", json!({ "token_id": "b1" })).await?), 2_000_000);
+    assert_eq!(view::<Vec<Value>>(&setup, "rarity_ranking", json!({})).await?.len(), 2);
+
+    Ok(())
+}
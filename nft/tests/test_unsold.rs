@@ -0,0 +1,51 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_owner_sweeps_the_unsold_supply_after_the_sale() -> TestResult {
+    let setup = init(5).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let treasury = setup.worker.dev_create_account().await?;
+    let claim_unsold = |caller: &near_workspaces::Account, count: u64| {
+        caller
+            .call(setup.contract.id(), "claim_unsold")
+            .args_json(json!({ "receiver_id": treasury.id(), "count": count }))
+            .deposit(NearToken::from_millinear(500))
+            .max_gas()
+            .transact()
+    };
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    let outcome = claim_unsold(&setup.owner, 3).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("The sale has no end"));
+    let now = setup.worker.view_block().await?.timestamp();
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_sale_window")
+        .args_json(json!({ "sale_start": null, "sale_end": now.to_string() }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(claim_unsold(&alice, 3).await?.is_failure(), "owner only");
+    assert_eq!(view::<String>(&setup, "unsold_remaining", json!({})).await?, "4");
+
+    let outcome = claim_unsold(&setup.owner, 3).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(["0", "1", "2"].iter().all(|token_id| !touched(&outcome, &vault_id(&setup, token_id))));
+    assert_eq!(outcome.json::<u64>()?, 3);
+    assert_eq!(view::<String>(&setup, "unsold_remaining", json!({})).await?, "1");
+
+    // The last call is bounded by what's left of the supply
+    let outcome = claim_unsold(&setup.owner, 10).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(outcome.json::<u64>()?, 1);
+    assert_eq!(view::<String>(&setup, "unsold_remaining", json!({})).await?, "0");
+    assert_eq!(view::<String>(&setup, "nft_supply_for_owner", json!({ "account_id": treasury.id() })).await?, "4");
+    let outcome = claim_unsold(&setup.owner, 1).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("No unsold supply left"));
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_upgrade_burns_a_tier_into_the_next() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    for token_id in ["a1", "a2", "a3", "a4"] {
+        assert!(mint(&setup, &alice, token_id).await?.is_success());
+    }
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+
+    let set_rule = |account: &near_workspaces::Account| {
+        account
+            .call(setup.contract.id(), "set_upgrade_rule")
+            .args_json(json!({ "inputs_required": 3, "input_tier": 0, "output_tier": 1 }))
+            .transact()
+    };
+    let deposit = NearToken::from_yoctonear(VAULT_DEPOSIT.as_yoctonear() + NearToken::from_millinear(100).as_yoctonear());
+    let upgrade = |token_ids: &[&str]| {
+        alice
+            .call(setup.contract.id(), "upgrade")
+            .args_json(json!({
+                "token_ids": token_ids,
+                "new_metadata": token_metadata("upgraded"),
+            }))
+            .deposit(deposit)
+            .max_gas()
+            .transact()
+    };
+
+    assert!(upgrade(&["a1", "a2", "a3"]).await?.is_failure(), "no rule yet");
+    assert!(set_rule(&alice).await?.is_failure());
+    assert!(set_rule(&setup.owner).await?.is_success());
+    assert!(upgrade(&["a1", "a2"]).await?.is_failure(), "three inputs are needed");
+    assert!(upgrade(&["a1", "a2", "a2"]).await?.is_failure(), "inputs must differ");
+    assert!(upgrade(&["a1", "a2", "b1"]).await?.is_failure(), "inputs must be the caller's");
+
+    let outcome = upgrade(&["a1", "a2", "a3"]).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let events: Vec<Value> = outcome.logs().iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|event| serde_json::from_str::<Value>(event).unwrap())
+        .collect();
+    let token: Value = outcome.json()?;
+    let token_id = token["token_id"].as_str().unwrap().to_string();
+    assert!(token_id.starts_with("upgraded-"));
+    assert_eq!(events.iter().filter(|event| event["event"] == "nft_burn").count(), 3);
+    assert!(events.iter().any(|event| event["event"] == "nft_mint" && event["data"][0]["token_ids"][0] == token_id));
+    let upgrade_event = events.iter().find(|event| event["event"] == "upgrade").unwrap();
+    assert_eq!(upgrade_event["data"][0]["burned_token_ids"], json!(["a1", "a2", "a3"]));
+    assert_eq!(upgrade_event["data"][0]["tier"], 1);
+
+    assert_eq!(view::<u32>(&setup, "token_tier", json!({ "token_id": token_id })).await?, 1);
+    let extra: Value = serde_json::from_str(token["metadata"]["extra"].as_str().unwrap())?;
+    assert_eq!(extra["tier"], 1);
+    for burned in ["a1", "a2", "a3"] {
+        assert!(view::<bool>(&setup, "is_burned", json!({ "token_id": burned })).await?);
+    }
+    // Tier 1 tokens have no upgrade rule
+    assert!(upgrade(&[&token_id, "a4", "b1"]).await?.is_failure());
+
+    Ok(())
+}
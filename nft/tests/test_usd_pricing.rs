@@ -0,0 +1,47 @@
+mod common;
+
+use common::*;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_usd_mints_refund_when_the_oracle_has_no_price() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    // Not a contract, so every price lookup fails
+    let oracle = setup.worker.dev_create_account().await?;
+    let set_pricing = |caller: &near_workspaces::Account, usd_pricing: Value| {
+        caller
+            .call(setup.contract.id(), "set_usd_pricing")
+            .args_json(json!({ "usd_pricing": usd_pricing }))
+            .transact()
+    };
+    let pricing = |slippage_bps: u32| json!({
+        "price_oracle": oracle.id(),
+        "asset_id": "wrap.near",
+        "usd_price": "5000000",
+        "slippage_bps": slippage_bps,
+    });
+    assert!(set_pricing(&alice, pricing(100)).await?.is_failure(), "owner only");
+    let outcome = set_pricing(&setup.owner, pricing(5_000)).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("slippage can't exceed"));
+    let outcome = set_pricing(&setup.owner, pricing(100)).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let stored: Value = view(&setup, "usd_pricing", json!({})).await?;
+    assert_eq!(stored, pricing(100));
+
+    let outcome = setup.owner.call(setup.contract.id(), "quoted_mint_price").max_gas().transact().await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("no usable price"));
+
+    // Nothing is minted and the whole deposit comes back
+    let outcome = mint(&setup, &alice, "a1").await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(!touched(&outcome, &vault_id(&setup, "a1")));
+    assert_eq!(outcome.json::<Option<Value>>()?, None);
+    assert_eq!(view::<u128>(&setup, "index", json!({})).await?, 0);
+    assert_eq!(view::<String>(&setup, "storage_balance_of", json!({ "account_id": alice.id() })).await?, "0");
+
+    assert!(set_pricing(&setup.owner, Value::Null).await?.is_success());
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::*;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_owner_tunes_what_vaults_are_funded_with() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let near = 10u128.pow(24);
+    let set = |caller: &near_workspaces::Account, method: &'static str, args: Value| {
+        caller.call(setup.contract.id(), method).args_json(args).transact()
+    };
+
+    let funding: Value = view(&setup, "vault_funding", json!({})).await?;
+    let deposit_before: u128 = funding["vault_deposit"].as_str().unwrap().parse()?;
+    assert_eq!(funding["vault_storage"], "19800000000000000000000");
+
+    let vault_storage = json!({ "vault_storage": (near / 10).to_string() });
+    assert!(set(&alice, "set_vault_storage", vault_storage.clone()).await?.is_failure(), "owner only");
+    let outcome = set(&setup.owner, "set_vault_storage", json!({ "vault_storage": (2 * near).to_string() })).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("between 0.01 and 1 NEAR"));
+    let outcome = set(&setup.owner, "set_vault_storage", vault_storage).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(set(&setup.owner, "set_near_per_storage", json!({ "near_per_storage": "1" })).await?.is_failure());
+    assert!(set(&setup.owner, "set_storage_per_sale", json!({ "storage_per_sale": "0" })).await?.is_failure());
+
+    // Mints and their quotes use the stored values
+    let funding: Value = view(&setup, "vault_funding", json!({})).await?;
+    let deposit: u128 = funding["vault_deposit"].as_str().unwrap().parse()?;
+    assert_eq!(deposit, deposit_before - 19_800_000_000_000_000_000_000 + near / 10);
+    let cost: Value = view(&setup, "mint_cost", json!({})).await?;
+    assert_eq!(cost["vault_deposit"], deposit.to_string());
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    // A fresh deployment has nothing to backfill
+    let outcome = setup.contract.call("migrate").args_json(json!({ "reward_pool": "0" })).transact().await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("already migrated"));
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::*;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_minters_pick_the_vault_split_within_the_range() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let with_split = |split: &str| json!({ "split_percent_override": split });
+
+    let outcome = mint_with_args(&setup, &alice, "a1", with_split("80")).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Vault split overrides are disabled"));
+    let set_range = |caller: &near_workspaces::Account, range: serde_json::Value| {
+        caller
+            .call(setup.contract.id(), "set_vault_split_range")
+            .args_json(json!({ "range": range }))
+            .transact()
+    };
+    assert!(set_range(&alice, json!(["30", "80"])).await?.is_failure(), "owner only");
+    assert!(set_range(&setup.owner, json!(["80", "30"])).await?.is_failure());
+    let outcome = set_range(&setup.owner, json!(["30", "80"])).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    let outcome = mint_with_args(&setup, &alice, "a1", with_split("90")).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("between 30 and 80 percent"));
+    let outcome = mint_with_args(&setup, &alice, "a1", with_split("80")).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let split = outcome.logs().into_iter().find(|log| log.contains("\"event\":\"mint_split\"")).unwrap();
+    let vault_amount = MINT_PRICE.as_yoctonear() * 80 / 100;
+    assert!(split.contains(&format!("\"vault_amount\":\"{}\"", vault_amount)), "{}", split);
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+
+    assert_eq!(view::<Option<String>>(&setup, "vault_split_of", json!({ "token_id": "a1" })).await?, Some("80".into()));
+    assert_eq!(view::<Option<String>>(&setup, "vault_split_of", json!({ "token_id": "b1" })).await?, Some("50".into()));
+    assert_eq!(view::<Option<String>>(&setup, "vault_split_of", json!({ "token_id": "x" })).await?, None);
+
+    // The burn fee shared with the holders follows the token's own split
+    burn(&setup, &alice, "a1").await?;
+    let burn_fee = 10;
+    let rewards = MINT_PRICE.as_yoctonear() * 80 * burn_fee / 20_000;
+    assert_eq!(view::<u128>(&setup, "balance_of", json!({ "owner": bob.id() })).await?, rewards);
+    assert_eq!(view::<Option<String>>(&setup, "vault_split_of", json!({ "token_id": "a1" })).await?, None);
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use common::*;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_owner_proceeds_vest_linearly() -> TestResult {
+    let day: u64 = 24 * 60 * 60 * 1_000_000_000;
+    let worker = near_workspaces::sandbox().await?;
+    let start = worker.view_block().await?.timestamp();
+    let setup = init_with(0, json!({ "vesting": { "start": start.to_string(), "duration": (365 * day).to_string() } })).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    let owner_before = setup.owner.view_account().await?.balance;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    let owner_after = setup.owner.view_account().await?.balance;
+    assert_eq!(owner_before, owner_after, "proceeds go to the vesting ledger");
+
+    let total: String = view(&setup, "vested_total", json!({})).await?;
+    assert_eq!(total.parse::<u128>()?, MINT_PRICE.as_yoctonear() / 2);
+
+    setup.worker.fast_forward(100).await?;
+    let claimable: String = view(&setup, "vested_claimable", json!({})).await?;
+    let claimable: u128 = claimable.parse()?;
+    assert!(claimable > 0 && claimable < MINT_PRICE.as_yoctonear() / 2);
+
+    let outcome = alice.call(setup.contract.id(), "claim_vested").transact().await?;
+    assert!(outcome.is_failure(), "only the owner can claim");
+    let outcome = setup.owner.call(setup.contract.id(), "claim_vested").transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(outcome.json::<String>()?.parse::<u128>()? >= claimable);
+
+    Ok(())
+}
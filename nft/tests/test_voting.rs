@@ -0,0 +1,197 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_votes_are_weighted_by_token_count() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    let outsider = setup.worker.dev_create_account().await?;
+    for token_id in ["a1", "a2"] {
+        assert!(mint(&setup, &alice, token_id).await?.is_success());
+    }
+    assert!(mint(&setup, &bob, "b1").await?.is_success());
+
+    let ends_at = setup.worker.view_block().await?.timestamp() + 30_000_000_000;
+    let proposal_id: u64 = setup
+        .owner
+        .call(setup.contract.id(), "create_proposal")
+        .args_json(json!({
+            "title": "Treasury",
+            "description": "Spend the treasury on",
+            "options": ["art", "events"],
+            "ends_at": ends_at.to_string(),
+        }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?
+        .json()?;
+
+    let vote = |account: &near_workspaces::Account, option_index: u32| {
+        account
+            .call(setup.contract.id(), "vote")
+            .args_json(json!({ "proposal_id": proposal_id, "option_index": option_index }))
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+    };
+    assert!(vote(&outsider, 0).await?.is_failure(), "only holders can vote");
+    assert!(vote(&alice, 2).await?.is_failure(), "unknown option");
+    assert!(vote(&alice, 0).await?.is_success());
+    assert!(vote(&bob, 0).await?.is_success());
+    assert!(vote(&alice, 1).await?.is_success(), "votes can change before the end");
+
+    let proposal: Value = view(&setup, "get_proposal", json!({ "proposal_id": proposal_id })).await?;
+    assert_eq!(proposal["tallies"], json!(["1", "2"]));
+    assert_eq!(proposal["voters"], 2);
+    let alice_vote: Value = view(&setup, "vote_of", json!({ "proposal_id": proposal_id, "account_id": alice.id() })).await?;
+    assert_eq!(alice_vote, json!({ "option_index": 1, "weight": 2 }));
+
+    let finalize = || {
+        bob.call(setup.contract.id(), "finalize_proposal")
+            .args_json(json!({ "proposal_id": proposal_id }))
+            .transact()
+    };
+    assert!(finalize().await?.is_failure(), "voting is still open");
+    setup.worker.fast_forward(100).await?;
+    assert!(finalize().await?.is_success());
+    assert!(vote(&bob, 1).await?.is_failure(), "voting has closed");
+
+    let proposals: Vec<Value> = view(&setup, "proposals", json!({})).await?;
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(proposals[0]["finalized"], true);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_treasury_spend_executes_once() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    let outcome = bob
+        .call(setup.contract.id(), "fund_treasury")
+        .deposit(NearToken::from_near(3))
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+
+    let ends_at = setup.worker.view_block().await?.timestamp() + 30_000_000_000;
+    let mut proposal_ids = vec![];
+    for amount in [NearToken::from_near(2), NearToken::from_near(5)] {
+        let proposal_id: u64 = setup
+            .owner
+            .call(setup.contract.id(), "create_proposal")
+            .args_json(json!({
+                "title": "Pay bob",
+                "description": "",
+                "options": ["approve", "reject"],
+                "ends_at": ends_at.to_string(),
+                "terms": {
+                    "kind": { "TreasurySpend": { "receiver_id": bob.id(), "amount": amount } },
+                    "quorum_bps": 5000,
+                },
+            }))
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+            .await?
+            .json()?;
+        let outcome = alice
+            .call(setup.contract.id(), "vote")
+            .args_json(json!({ "proposal_id": proposal_id, "option_index": 0 }))
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+            .await?;
+        assert!(outcome.is_success(), "{:?}", outcome);
+        proposal_ids.push(proposal_id);
+    }
+
+    let execute = |proposal_id: u64| {
+        alice
+            .call(setup.contract.id(), "execute_proposal")
+            .args_json(json!({ "proposal_id": proposal_id }))
+            .max_gas()
+            .transact()
+    };
+    assert!(execute(proposal_ids[0]).await?.is_failure(), "voting is still open");
+    setup.worker.fast_forward(100).await?;
+    for proposal_id in &proposal_ids {
+        let outcome = bob
+            .call(setup.contract.id(), "finalize_proposal")
+            .args_json(json!({ "proposal_id": proposal_id }))
+            .transact()
+            .await?;
+        assert!(outcome.is_success(), "{:?}", outcome);
+    }
+
+    let bob_before = bob.view_account().await?.balance;
+    let outcome = execute(proposal_ids[0]).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let bob_after = bob.view_account().await?.balance;
+    assert_eq!(bob_after.as_yoctonear() - bob_before.as_yoctonear(), NearToken::from_near(2).as_yoctonear());
+    assert!(execute(proposal_ids[0]).await?.is_failure(), "a proposal executes once");
+    assert!(execute(proposal_ids[1]).await?.is_failure(), "the treasury holds 1 NEAR");
+
+    let balance: String = view(&setup, "treasury_balance", json!({})).await?;
+    assert_eq!(balance, NearToken::from_near(1).as_yoctonear().to_string());
+    let proposal: Value = view(&setup, "get_proposal", json!({ "proposal_id": proposal_ids[0] })).await?;
+    assert_eq!(proposal["executed"], true);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_voted_tokens_cant_vote_again_from_another_account() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+
+    let ends_at = setup.worker.view_block().await?.timestamp() + 30_000_000_000;
+    let proposal_id: u64 = setup
+        .owner
+        .call(setup.contract.id(), "create_proposal")
+        .args_json(json!({
+            "title": "Treasury",
+            "description": "Spend the treasury on",
+            "options": ["art", "events"],
+            "ends_at": ends_at.to_string(),
+        }))
+        .deposit(NearToken::from_millinear(10))
+        .transact()
+        .await?
+        .json()?;
+
+    let vote = |account: &near_workspaces::Account| {
+        account
+            .call(setup.contract.id(), "vote")
+            .args_json(json!({ "proposal_id": proposal_id, "option_index": 0 }))
+            .deposit(NearToken::from_millinear(10))
+            .transact()
+    };
+    let transfer = || {
+        alice
+            .call(setup.contract.id(), "nft_transfer")
+            .args_json(json!({ "receiver_id": bob.id(), "token_id": "a1" }))
+            .deposit(NearToken::from_yoctonear(1))
+            .transact()
+    };
+    assert!(vote(&alice).await?.is_success());
+    let locked: Vec<String> = view(&setup, "locked_tokens_of", json!({ "account_id": alice.id() })).await?;
+    assert_eq!(locked, vec!["a1".to_string()]);
+    assert!(transfer().await?.is_failure(), "voted tokens can't move before the proposal ends");
+    assert!(vote(&bob).await?.is_failure(), "the token still counts for alice only");
+
+    let proposal: Value = view(&setup, "get_proposal", json!({ "proposal_id": proposal_id })).await?;
+    assert_eq!(proposal["tallies"], json!(["1", "0"]));
+
+    setup.worker.fast_forward(100).await?;
+    assert!(transfer().await?.is_success(), "the lock ends with the proposal");
+    assert!(vote(&bob).await?.is_failure(), "voting has closed");
+
+    Ok(())
+}
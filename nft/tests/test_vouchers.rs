@@ -0,0 +1,107 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+const VOUCHER_DEPOSIT: NearToken = NearToken::from_near(3);
+
+#[tokio::test]
+async fn test_voucher_buy_refund_and_redeem() -> TestResult {
+    let setup = init(2).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    for _ in 0..2 {
+        let outcome = alice
+            .call(setup.contract.id(), "buy_mint_voucher")
+            .deposit(VOUCHER_DEPOSIT)
+            .transact()
+            .await?;
+        assert!(outcome.is_success(), "{:?}", outcome);
+    }
+    assert_eq!(view::<u64>(&setup, "vouchers_of", json!({ "account_id": alice.id() })).await?, 2);
+
+    // The two vouchers hold the whole supply
+    let outcome = alice.call(setup.contract.id(), "buy_mint_voucher").deposit(VOUCHER_DEPOSIT).transact().await?;
+    assert!(outcome.is_failure());
+    assert!(mint(&setup, &alice, "public").await?.is_failure());
+
+    let outcome = alice.call(setup.contract.id(), "refund_voucher").transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert_eq!(view::<u64>(&setup, "vouchers_of", json!({ "account_id": alice.id() })).await?, 1);
+
+    let outcome = alice
+        .call(setup.contract.id(), "redeem_voucher")
+        .args_json(json!({ "token_id": "v1", "token_metadata": token_metadata("v1") }))
+        .deposit(NearToken::from_millinear(100))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "redemption is not open yet");
+
+    let outcome = setup.owner.call(setup.contract.id(), "open_redemption").transact().await?;
+    assert!(outcome.is_success());
+    let outcome = alice.call(setup.contract.id(), "refund_voucher").transact().await?;
+    assert!(outcome.is_failure(), "vouchers can't be refunded once redemption is open");
+
+    let outcome = alice
+        .call(setup.contract.id(), "redeem_voucher")
+        .args_json(json!({ "token_id": "v1", "token_metadata": token_metadata("v1") }))
+        .deposit(NearToken::from_millinear(100))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(touched(&outcome, &vault_id(&setup, "v1")));
+    assert_eq!(view::<u64>(&setup, "vouchers_of", json!({ "account_id": alice.id() })).await?, 0);
+    assert_eq!(view::<u64>(&setup, "total_vouchers_sold", json!({})).await?, 2);
+
+    Ok(())
+}
+
+async fn vault_deposit(setup: &Setup) -> TestResult<u128> {
+    let funding: Value = view(setup, "vault_funding", json!({})).await?;
+    Ok(funding["vault_deposit"].as_str().unwrap().parse()?)
+}
+
+#[tokio::test]
+async fn test_vouchers_redeem_at_the_terms_they_were_bought_at() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let escrowed_deposit = vault_deposit(&setup).await?;
+    let outcome = alice.call(setup.contract.id(), "buy_mint_voucher").deposit(VOUCHER_DEPOSIT).transact().await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    // Vaults cost more by the time the voucher is redeemed
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_vault_storage")
+        .args_json(json!({ "vault_storage": NearToken::from_millinear(500).as_yoctonear().to_string() }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(vault_deposit(&setup).await? > escrowed_deposit);
+    let outcome = setup.owner.call(setup.contract.id(), "open_redemption").transact().await?;
+    assert!(outcome.is_success());
+    let outcome = alice
+        .call(setup.contract.id(), "redeem_voucher")
+        .args_json(json!({ "token_id": "v1", "token_metadata": token_metadata("v1") }))
+        .deposit(NearToken::from_millinear(100))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    // The vault gets what the voucher escrowed for it, and the price is split as it was paid
+    let quote: Value = view(&setup, "burn_quote", json!({ "token_id": "v1" })).await?;
+    assert_eq!(quote["vault_amount"], (MINT_PRICE.as_yoctonear() / 2).to_string());
+    let vault: near_workspaces::AccountId = vault_id(&setup, "v1").parse()?;
+    let vault_balance = setup.worker.view_account(&vault).await?.balance.as_yoctonear();
+    let funded = escrowed_deposit + MINT_PRICE.as_yoctonear() / 2;
+    // Beyond it only the vault's share of the gas its setup burned
+    assert!(vault_balance >= funded, "{} vs {}", vault_balance, funded);
+    assert!(vault_balance - funded < NearToken::from_millinear(1).as_yoctonear(), "{} vs {}", vault_balance, funded);
+    assert_eq!(view::<Vec<String>>(&setup, "check_invariants", json!({})).await?, Vec::<String>::new());
+
+    Ok(())
+}
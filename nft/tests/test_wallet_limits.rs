@@ -0,0 +1,98 @@
+mod common;
+
+use common::*;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_wallets_can_mint_up_to_the_limit() -> TestResult {
+    let setup = init_with(0, json!({ "max_mints_per_wallet": "2" })).await?;
+    let alice = setup.worker.dev_create_account().await?;
+
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+    let outcome = mint(&setup, &alice, "a3").await?;
+    assert!(!touched(&outcome, &vault_id(&setup, "a3")));
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Exceeded the limit of 2 mints per wallet"));
+    assert_eq!(view::<String>(&setup, "mints_of", json!({ "account_id": alice.id() })).await?, "2");
+
+    // Burning doesn't give the allocation back, raising the limit does
+    burn(&setup, &alice, "a1").await?;
+    assert!(mint(&setup, &alice, "a3").await?.is_failure());
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_max_mints_per_wallet")
+        .args_json(json!({ "max_mints_per_wallet": "3" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    assert!(mint(&setup, &alice, "a3").await?.is_success());
+    assert_eq!(view::<String>(&setup, "mints_of", json!({ "account_id": alice.id() })).await?, "3");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_paying_for_another_account_credits_the_receiver() -> TestResult {
+    let setup = init_with(0, json!({ "max_mints_per_wallet": "1" })).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    let bob = setup.worker.dev_create_account().await?;
+
+    let balance_before = alice.view_account().await?.balance;
+    let outcome = mint_with_args(&setup, &alice, "b1", json!({ "token_owner_id": bob.id() })).await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+    let mint_event = outcome.logs().iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|event| serde_json::from_str::<serde_json::Value>(event).unwrap())
+        .find(|event| event["event"] == "nft_mint")
+        .unwrap();
+    assert_eq!(mint_event["data"][0]["owner_id"], bob.id().as_str());
+    let spent = balance_before.as_yoctonear() - alice.view_account().await?.balance.as_yoctonear();
+    assert!(spent >= MINT_PRICE.as_yoctonear() + VAULT_DEPOSIT.as_yoctonear(), "the payer's deposit is spent");
+
+    assert_eq!(view::<u64>(&setup, "total_holders", json!({})).await?, 1);
+    assert_eq!(view::<String>(&setup, "nft_supply_for_owner", json!({ "account_id": bob.id() })).await?, "1");
+    assert_eq!(view::<String>(&setup, "mints_of", json!({ "account_id": bob.id() })).await?, "1");
+    assert_eq!(view::<String>(&setup, "mints_of", json!({ "account_id": alice.id() })).await?, "0");
+    assert_eq!(view::<String>(&setup, "minted_by", json!({ "account_id": alice.id() })).await?, "1");
+
+    // Bob's limit is used up whoever pays, alice's own is still free
+    let outcome = mint_with_args(&setup, &alice, "b2", json!({ "token_owner_id": bob.id() })).await?;
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Exceeded the limit of 1 mints per wallet"));
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    assert_eq!(view::<String>(&setup, "mints_of", json!({ "account_id": alice.id() })).await?, "1");
+    assert_eq!(view::<u64>(&setup, "total_holders", json!({})).await?, 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_accounts_wait_out_the_mint_cooldown() -> TestResult {
+    let setup = init(0).await?;
+    let alice = setup.worker.dev_create_account().await?;
+    assert_eq!(view::<String>(&setup, "mint_cooldown_blocks", json!({})).await?, "1");
+    let outcome = setup
+        .owner
+        .call(setup.contract.id(), "set_mint_cooldown_blocks")
+        .args_json(json!({ "blocks": "100" }))
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome);
+
+    assert!(mint(&setup, &alice, "a1").await?.is_success());
+    let last: u64 = view::<String>(&setup, "last_mint_block", json!({ "account_id": alice.id() })).await?.parse()?;
+    // The boundary is exactly `cooldown` blocks after the last mint
+    let outcome = mint(&setup, &alice, "a2").await?;
+    let error = format!("{:?}", outcome.into_result().unwrap_err());
+    assert!(error.contains(&format!("wait until block {}", last + 100)), "{}", error);
+
+    // The owner's paths have no cooldown
+    assert!(mint(&setup, &setup.owner, "o1").await?.is_success());
+    assert!(mint(&setup, &setup.owner, "o2").await?.is_success());
+
+    setup.worker.fast_forward(100).await?;
+    assert!(mint(&setup, &alice, "a2").await?.is_success());
+    let next: u64 = view::<String>(&setup, "last_mint_block", json!({ "account_id": alice.id() })).await?.parse()?;
+    assert!(next >= last + 100);
+
+    Ok(())
+}
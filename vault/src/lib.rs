@@ -1,9 +1,14 @@
 // Find all our documentation at https://docs.near.org
 use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
-use near_sdk::{env, NearToken, Gas, near_bindgen, AccountId, Promise, serde_json::json, require};
+use near_sdk::{env, NearToken, Gas, near_bindgen, AccountId, Promise, PromiseOrValue, PromiseResult, serde_json::json, require};
 use near_sdk::json_types::U128;
 // use near_contract_standards::fungible_token::core_impl::FungibleToken;
 
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(15);
+const GAS_FOR_STORAGE_UNREGISTER: Gas = Gas::from_tgas(10);
+const GAS_FOR_RESOLVE_UNREGISTER: Gas = Gas::from_tgas(5);
+const GAS_FOR_RESOLVE_WITHDRAW: Gas = Gas::from_tgas(25);
+
 // Define the contract structure
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize)]
@@ -46,64 +51,109 @@ impl Contract {
         let attached_amount = env::attached_deposit();
         self.amount = attached_amount.as_yoctonear();
     }
+
+    /// What the vault holds for its token, in NEAR or in its FT, before any burn fee.
+    pub fn vault_amount(&self) -> U128 {
+        U128(self.amount)
+    }
+
+    /// Pay out the vault to `owner`, less the burn fee in basis points, or in whole percent from
+    /// an owner contract that doesn't send `burn_fee_bps`, then delete the vault account with its
+    /// remaining balance going to `beneficiary`, by default `owner`. An FT vault is only deleted
+    /// once every transfer went through and its FT storage is unregistered, so no FT is left
+    /// behind in a deleted account. Returns the amount withdrawn, before the burn fee, and from an
+    /// FT vault only once every transfer was delivered, failing otherwise.
     #[payable]
     pub fn withdraw(
         &mut self,      
         owner: AccountId,
         burn_fee: U128,
-    ) {
+        beneficiary: Option<AccountId>,
+        burn_fee_bps: Option<U128>,
+    ) -> PromiseOrValue<U128> {
         assert_eq!(
             env::predecessor_account_id(),
             self.owner_contract,
             "Only the owner contract can withdraw"
         );
         let treasury = &self.treasury;
+        let burn_fee_bps = burn_fee_bps.map_or(burn_fee.0 * 100, |bps| bps.0);
         let amount_to_holders: u128 = self.amount
-            .checked_mul(burn_fee.0).unwrap()
-            .checked_div(100u128).unwrap();
+            .checked_mul(burn_fee_bps).unwrap()
+            .checked_div(10_000u128).unwrap();
         let amount_to_owner = self.amount.checked_sub(amount_to_holders).unwrap();
+        let beneficiary = beneficiary.unwrap_or_else(|| owner.clone());
+        let withdrawn = U128(self.amount);
         if let Some(ft_contract) = &self.ft_contract {
-            Promise::new(ft_contract.clone()).function_call(
-                "ft_transfer".to_string(), 
-                json!({
-                    "receiver_id": owner.to_string(),
-                    "amount": amount_to_owner.to_string(),                    
-                }).to_string().into_bytes().to_vec(),
-                NearToken::from_yoctonear(1),
-                Gas::from_tgas(20),
-            );
-            Promise::new(ft_contract.clone()).function_call(
-                "ft_transfer".to_string(), 
-                json!({
-                    "receiver_id": self.owner_contract.to_string(),
-                    "amount": (amount_to_holders/2).to_string(),                    
-                }).to_string().into_bytes().to_vec(),
-                NearToken::from_yoctonear(1),
-                Gas::from_tgas(20),
-            );
-            Promise::new(ft_contract.clone()).function_call(
-                "ft_transfer".to_string(), 
-                json!({
-                    "receiver_id": treasury.clone().to_string(),
-                    "amount": (amount_to_holders/2).to_string(),                    
-                }).to_string().into_bytes().to_vec(),
-                NearToken::from_yoctonear(1),
-                Gas::from_tgas(20),
-            );
-            Promise::new(ft_contract.clone()).function_call(
-                "storage_withdraw".to_string(),
-                json!({}).to_string().into_bytes().to_vec(),
-                NearToken::from_yoctonear(1),
-                Gas::from_tgas(20),
-            );
+            // The treasury takes the odd unit, so the vault's FT balance ends at zero
+            let transfers = ft_transfer(ft_contract, &owner, amount_to_owner)
+                .and(ft_transfer(ft_contract, &self.owner_contract, amount_to_holders / 2))
+                .and(ft_transfer(ft_contract, treasury, amount_to_holders - amount_to_holders / 2));
+            PromiseOrValue::Promise(transfers.then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_WITHDRAW)
+                    .resolve_withdraw(beneficiary, withdrawn)
+            ))
         } else {
+            self.amount = 0;
             Promise::new(owner.clone()).transfer(NearToken::from_yoctonear(amount_to_owner));
             Promise::new(self.owner_contract.clone()).transfer(NearToken::from_yoctonear(amount_to_holders/2));
             Promise::new(treasury.clone()).transfer(NearToken::from_yoctonear(amount_to_holders/2));
+            Promise::new(env::current_account_id()).delete_account(beneficiary);
+            PromiseOrValue::Value(withdrawn)
+        }
+    }
+
+    /// Unregister the vault from its FT once every transfer of `withdraw` went through, and then
+    /// delete it, confirming the `withdrawn` amount was delivered. Only then is the vault's amount
+    /// cleared: a failed transfer leaves its FT in the vault, so the vault is kept with its amount
+    /// and the withdrawal fails.
+    #[private]
+    pub fn resolve_withdraw(&mut self, beneficiary: AccountId, withdrawn: U128) -> U128 {
+        let transferred = (0..env::promise_results_count())
+            .all(|i| matches!(env::promise_result(i), PromiseResult::Successful(_)));
+        if !transferred {
+            env::panic_str("An FT transfer out of the vault failed, the vault is kept");
         }
-        Promise::new(env::current_account_id()).delete_account(owner);
         self.amount = 0;
+        let ft_contract = self.ft_contract.clone().unwrap();
+        Promise::new(ft_contract).function_call(
+            "storage_unregister".to_string(),
+            json!({ "force": false }).to_string().into_bytes().to_vec(),
+            NearToken::from_yoctonear(1),
+            GAS_FOR_STORAGE_UNREGISTER,
+        ).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_RESOLVE_UNREGISTER)
+                .resolve_unregister(beneficiary)
+        );
+        withdrawn
     }
+
+    /// Delete the vault once it is unregistered from its FT. The FT refuses while the vault still
+    /// holds a balance, e.g. tokens sent to it directly, and the vault is kept.
+    #[private]
+    pub fn resolve_unregister(&mut self, beneficiary: AccountId) {
+        let unregistered = matches!(env::promise_result(0), PromiseResult::Successful(result) if result == b"true");
+        if !unregistered {
+            env::log_str("The vault still holds FT, it is kept");
+            return;
+        }
+        Promise::new(env::current_account_id()).delete_account(beneficiary);
+    }
+}
+
+/// Transfer `amount` of `ft_contract` from the vault to `receiver_id`.
+fn ft_transfer(ft_contract: &AccountId, receiver_id: &AccountId, amount: u128) -> Promise {
+    Promise::new(ft_contract.clone()).function_call(
+        "ft_transfer".to_string(),
+        json!({
+            "receiver_id": receiver_id.to_string(),
+            "amount": amount.to_string(),
+        }).to_string().into_bytes().to_vec(),
+        NearToken::from_yoctonear(1),
+        GAS_FOR_FT_TRANSFER,
+    )
 }
 
 